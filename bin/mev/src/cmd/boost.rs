@@ -1,21 +1,51 @@
+mod status;
+
 use crate::cmd::config::Config;
-use clap::Args;
+use clap::{Args, Subcommand};
 use eyre::OptionExt;
-use mev_boost_rs::Service;
-use tracing::info;
+use mev_boost_rs::{RelayReloader, Service};
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::{info, warn};
 
 #[derive(Debug, Args)]
-#[clap(about = "🚀 connecting proposers to the external builder network")]
+#[clap(
+    about = "🚀 connecting proposers to the external builder network",
+    args_conflicts_with_subcommands = true
+)]
 pub struct Command {
     #[clap(env, default_value = "config.toml")]
     config_file: String,
+    #[clap(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    /// Check that the configured relays and the local boost server are reachable
+    Status {
+        #[clap(env, default_value = "config.toml")]
+        config_file: String,
+    },
 }
 
 impl Command {
+    pub fn config_file(&self) -> &str {
+        match &self.command {
+            Some(Commands::Status { config_file }) => config_file,
+            None => &self.config_file,
+        }
+    }
+
     pub async fn execute(self) -> eyre::Result<()> {
+        if let Some(Commands::Status { config_file }) = &self.command {
+            let config = Config::from_file(config_file)?;
+            let config = config.boost.ok_or_eyre("missing boost config from file provided")?;
+            return status::run(&config).await
+        }
+
         let config_file = &self.config_file;
 
-        let config = Config::from_toml_file(config_file)?;
+        let config = Config::from_file(config_file)?;
 
         let network = config.network.ok_or_eyre("missing `network` from configuration)")?;
         info!("configured for `{network}`");
@@ -23,9 +53,29 @@ impl Command {
         if let Some(config) = config.boost {
             let service = Service::from(network, config);
             let handle = service.spawn()?;
+            tokio::spawn(reload_on_hangup(config_file.clone(), handle.relay_reloader()));
             Ok(handle.await?)
         } else {
             Err(eyre::eyre!("missing boost config from file provided"))
         }
     }
 }
+
+// Reloads the relays from the `config_file` each time the process receives `SIGHUP`
+async fn reload_on_hangup(config_file: String, reloader: RelayReloader) {
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(hangups) => hangups,
+        Err(err) => {
+            warn!(%err, "could not listen for SIGHUP; relays will not be reloaded");
+            return
+        }
+    };
+    while hangups.recv().await.is_some() {
+        info!(%config_file, "reloading relays");
+        match Config::from_file(&config_file) {
+            Ok(Config { boost: Some(config), .. }) => reloader.reload(&config),
+            Ok(_) => warn!("missing boost config from file provided; ignoring reload"),
+            Err(err) => warn!(%err, "could not reload config"),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use futures_util::future::join_all;
+use mev_boost_rs::Config;
+use mev_rs::{
+    blinded_block_provider::Client as BlockProvider,
+    config::RelayClientConfig,
+    relay::{Relay, RelayEndpoint},
+};
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::{Duration, Instant},
+};
+use url::Url;
+
+#[cfg(not(feature = "minimal-preset"))]
+use beacon_api_client::mainnet::Client as BeaconApiClient;
+#[cfg(feature = "minimal-preset")]
+use beacon_api_client::minimal::Client as BeaconApiClient;
+
+struct StatusRow {
+    target: String,
+    // public key given for the target, only checked to be well-formed
+    public_key: String,
+    status: String,
+    latency: Option<Duration>,
+}
+
+impl StatusRow {
+    fn failed(target: String, public_key: String, status: String) -> Self {
+        Self { target, public_key, status, latency: None }
+    }
+}
+
+async fn check(target: String, public_key: String, provider: &BlockProvider) -> StatusRow {
+    let start = Instant::now();
+    let status = match provider.check_status().await {
+        Ok(()) => "ok".to_string(),
+        Err(err) => err.to_string(),
+    };
+    StatusRow { target, public_key, status, latency: Some(start.elapsed()) }
+}
+
+async fn check_relay(relay_url: &str, config: &RelayClientConfig) -> StatusRow {
+    let url = match relay_url.parse::<Url>() {
+        Ok(url) => url,
+        Err(err) => {
+            return StatusRow::failed(
+                relay_url.to_string(),
+                "-".to_string(),
+                format!("invalid URL: {err}"),
+            )
+        }
+    };
+    // NOTE: only show the host so any auth token in the URL is not printed
+    let target = url.host_str().unwrap_or_default().to_string();
+    let endpoint = match RelayEndpoint::try_from(url) {
+        Ok(endpoint) => endpoint,
+        Err(err) => return StatusRow::failed(target, "invalid".to_string(), err.to_string()),
+    };
+    let public_key = endpoint.public_key().to_string();
+    match Relay::new(endpoint, config) {
+        Ok(relay) => check(target, public_key, &relay).await,
+        Err(err) => StatusRow::failed(target, public_key, err.to_string()),
+    }
+}
+
+async fn check_boost(config: &Config) -> StatusRow {
+    // reach a server listening on all interfaces via loopback
+    let host = match config.host {
+        IpAddr::V4(host) if host.is_unspecified() => Ipv4Addr::LOCALHOST.into(),
+        IpAddr::V6(host) if host.is_unspecified() => Ipv6Addr::LOCALHOST.into(),
+        host => host,
+    };
+    let target = SocketAddr::new(host, config.port).to_string();
+    match Url::parse(&format!("http://{target}")) {
+        Ok(url) => {
+            let provider = BlockProvider::new(BeaconApiClient::new(url));
+            check(format!("boost ({target})"), "-".to_string(), &provider).await
+        }
+        Err(err) => StatusRow::failed(target, "-".to_string(), format!("invalid URL: {err}")),
+    }
+}
+
+fn print_table(rows: &[StatusRow]) {
+    // NOTE: the public key is only parsed from the URL, not checked against the relay
+    let key_header = "public key (format only)";
+    let width = rows.iter().map(|row| row.target.len()).max().unwrap_or_default().max(6);
+    let key_width =
+        rows.iter().map(|row| row.public_key.len()).max().unwrap_or_default().max(key_header.len());
+    println!("{:<width$}  {:<key_width$}  {:<10}  status", "target", key_header, "latency");
+    for row in rows {
+        let latency = row
+            .latency
+            .map(|latency| format!("{}ms", latency.as_millis()))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:<width$}  {:<key_width$}  {:<10}  {}",
+            row.target, row.public_key, latency, row.status
+        );
+    }
+}
+
+/// Checks the `/eth/v1/builder/status` endpoint of each relay in `config` and the local boost
+/// server, printing the results as a table
+pub async fn run(config: &Config) -> eyre::Result<()> {
+    let relays = join_all(
+        config.relays.iter().map(|relay_url| check_relay(relay_url, &config.relay_client)),
+    );
+    let (mut rows, boost) = tokio::join!(relays, check_boost(config));
+    rows.push(boost);
+
+    print_table(&rows);
+
+    let failures = rows.iter().filter(|row| row.status != "ok").count();
+    if failures > 0 {
+        Err(eyre::eyre!("{failures} of {} check(s) failed", rows.len()))
+    } else {
+        Ok(())
+    }
+}
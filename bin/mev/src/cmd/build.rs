@@ -12,7 +12,7 @@ impl TryFrom<CliArgs> for Config {
     type Error = eyre::Error;
 
     fn try_from(value: CliArgs) -> Result<Self, Self::Error> {
-        Self::from_toml_file(value.config_file)
+        Self::from_file(value.config_file)
     }
 }
 
@@ -1,13 +1,16 @@
-use clap::Args;
+mod init;
+
+use crate::logging::LoggingConfig;
+use clap::{Args, Subcommand};
 use ethereum_consensus::networks::Network;
-use eyre::WrapErr;
+use eyre::{OptionExt, WrapErr};
 #[cfg(feature = "boost")]
 use mev_boost_rs::Config as BoostConfig;
 #[cfg(feature = "build")]
-use mev_build_rs::Config as BuildConfig;
+use mev_build_rs::{Config as BuildConfig, SubmitterConfig};
 #[cfg(feature = "relay")]
 use mev_relay_rs::Config as RelayConfig;
-use mev_rs::config::from_toml_file;
+use mev_rs::config::from_file_with_env;
 use serde::Deserialize;
 use std::{fmt, path::Path};
 use tracing::{info, trace};
@@ -15,34 +18,53 @@ use tracing::{info, trace};
 #[derive(Debug, Deserialize)]
 pub struct Config {
     pub network: Option<Network>,
+    #[serde(default)]
+    pub logging: LoggingConfig,
     #[cfg(feature = "boost")]
     pub boost: Option<BoostConfig>,
     #[cfg(feature = "build")]
     pub builder: Option<BuildConfig>,
+    #[cfg(feature = "build")]
+    pub submitter: Option<SubmitterConfig>,
     #[cfg(feature = "relay")]
     pub relay: Option<RelayConfig>,
 }
 
 impl Config {
-    pub fn from_toml_file<P: AsRef<Path> + fmt::Display>(path: P) -> eyre::Result<Config> {
+    pub fn from_file<P: AsRef<Path> + fmt::Display>(path: P) -> eyre::Result<Config> {
         trace!(%path, "loading `mev-rs` config");
 
-        from_toml_file::<_, Self>(path.as_ref()).wrap_err("could not parse TOML")
+        from_file_with_env::<_, Self>(path.as_ref()).wrap_err("could not load configuration")
     }
 }
 
 #[derive(Debug, Args)]
-#[clap(about = "🔬 (debug) utility to verify configuration")]
+#[clap(
+    about = "🔬 (debug) utility to verify configuration",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub struct Command {
-    #[clap(env)]
-    config_file: String,
+    #[clap(env, required = true)]
+    config_file: Option<String>,
+    #[clap(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    Init(init::InitCommand),
 }
 
 impl Command {
     pub async fn execute(self) -> eyre::Result<()> {
-        let config_file = self.config_file;
+        if let Some(Commands::Init(cmd)) = self.command {
+            return cmd.execute()
+        }
+
+        let config_file = self.config_file.ok_or_eyre("missing config file")?;
 
-        let config = Config::from_toml_file(config_file)?;
+        let config = Config::from_file(config_file)?;
         info!("{config:#?}");
 
         Ok(())
@@ -0,0 +1,437 @@
+use crate::logging::{DEFAULT_LOG_FILE_PREFIX, DEFAULT_OTLP_SERVICE_NAME};
+use clap::{Args, ValueEnum};
+use ethereum_consensus::networks::Network;
+use eyre::WrapErr;
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+// Relay used in the generated configuration for networks where one is known.
+const SEPOLIA_RELAY: &str = "https://0x845bd072b7cd566f02faeb0a4033ce9399e42839ced64e8b2adcfc859ed1e8e1a5a293336a49feac6d9a5edb779be53a@boost-relay-sepolia.flashbots.net";
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ServiceKind {
+    Boost,
+    Relay,
+    Build,
+}
+
+#[derive(Debug, Args)]
+#[clap(about = "📝 write a commented example configuration for a service")]
+pub struct InitCommand {
+    #[clap(value_enum)]
+    service: ServiceKind,
+    /// Network to configure, e.g. `mainnet`, `sepolia` or `holesky`, or the path to the
+    /// configuration of a custom network
+    #[clap(long, default_value = "mainnet")]
+    network: String,
+    /// Path to write the configuration to
+    #[clap(long, short, default_value = "config.toml")]
+    output: PathBuf,
+    /// Overwrite `output` if it already exists
+    #[clap(long)]
+    force: bool,
+}
+
+impl InitCommand {
+    pub fn execute(self) -> eyre::Result<()> {
+        if self.output.exists() && !self.force {
+            eyre::bail!("{} already exists; pass `--force` to overwrite it", self.output.display());
+        }
+
+        validate_network(&self.network)?;
+
+        let mut config = render_common(&self.network);
+        match self.service {
+            ServiceKind::Boost => render_boost(&self.network, &mut config)?,
+            ServiceKind::Relay => render_relay(&mut config)?,
+            ServiceKind::Build => render_build(&self.network, &mut config)?,
+        }
+
+        fs::write(&self.output, config)
+            .wrap_err_with(|| format!("could not write {}", self.output.display()))?;
+        info!(path = %self.output.display(), service = ?self.service, "wrote configuration");
+        Ok(())
+    }
+}
+
+fn validate_network(network: &str) -> eyre::Result<()> {
+    match Network::from(network.to_string()) {
+        Network::Custom(path) if !Path::new(&path).exists() => eyre::bail!(
+            "unknown network `{network}`; expected `mainnet`, `sepolia`, `holesky` or the path to \
+             the configuration of a custom network"
+        ),
+        _ => Ok(()),
+    }
+}
+
+fn example_relays(network: &str) -> String {
+    match network {
+        "sepolia" => format!("[\n    \"{SEPOLIA_RELAY}\",\n]"),
+        _ => "[]".to_string(),
+    }
+}
+
+fn render_common(network: &str) -> String {
+    format!(
+        r#"network = "{network}"
+
+# optional logging settings for `mev boost` and `mev relay`
+# [logging]
+# format = "text" # or "json"
+# [logging.file]
+# directory = "logs"
+# prefix = "{DEFAULT_LOG_FILE_PREFIX}"
+# rotation = "daily" # or "minutely", "hourly", "never"
+# format = "text" # or "json"
+# export spans to an OpenTelemetry collector (e.g. Jaeger or Tempo) over gRPC
+# [logging.otlp]
+# endpoint = "http://localhost:4317"
+# service_name = "{DEFAULT_OTLP_SERVICE_NAME}"
+"#
+    )
+}
+
+#[cfg(any(feature = "boost", feature = "relay"))]
+fn render_server(section: &str, config: &mut String) {
+    let server = mev_rs::config::ServerConfig::default();
+    let _ = write!(
+        config,
+        r#"
+# optional limits on requests to the API server
+# [{section}.server]
+# max_body_size_bytes = {}
+# max_submission_body_size_bytes = {}
+# request_timeout_ms = {}
+# submission_timeout_ms = {}
+# compression = {}
+"#,
+        server.max_body_size_bytes,
+        server.max_submission_body_size_bytes,
+        server.request_timeout_ms,
+        server.submission_timeout_ms,
+        server.compression,
+    );
+}
+
+#[cfg(any(feature = "boost", feature = "build"))]
+fn render_relay_client(section: &str, config: &mut String) {
+    let _ = write!(
+        config,
+        r#"
+# optional settings for the HTTP client used to reach relays, unset values use the client defaults
+# [{section}.relay_client]
+# connect_timeout_ms = 500
+# request_timeout_ms = 2000
+# tcp_keepalive_ms = 30000
+# pool_idle_timeout_ms = 90000
+# pool_max_idle_per_host = 8
+# retry status checks that fail to connect or time out, backing off from this interval
+# retry_initial_interval_ms = 100
+# retry_max_elapsed_ms = {}
+# encoding of block submissions: "json", "ssz" or "ssz_gzip"
+# submission_encoding = "json"
+"#,
+        mev_rs::relay::DEFAULT_RETRY_MAX_ELAPSED_MS,
+    );
+}
+
+#[cfg(feature = "boost")]
+fn render_boost(network: &str, config: &mut String) -> eyre::Result<()> {
+    let boost = mev_boost_rs::Config::default();
+    let _ = write!(
+        config,
+        r#"
+[boost]
+host = "{}"
+port = {}
+# relays are given as `https://{{public_key}}@{{host}}`, or `https://{{public_key}}:{{auth_token}}@{{host}}`
+# to authenticate to the relay with a bearer token; any path after the host, e.g. `https://{{public_key}}@{{host}}/relay`,
+# is kept as a prefix to the relay APIs
+relays = {}
+# used to find the genesis time and, with `verify_payload_attributes`, to check bids against the
+# payload attributes of each proposal
+# beacon_node_url = "http://127.0.0.1:5052"
+# verify_payload_attributes = {}
+# file to record delivered payloads in, rotated once it grows past 64 MiB
+# delivered_payloads_path = "delivered_payloads.jsonl"
+
+# optional settings for forwarding validator registrations to relays
+# [boost.registration]
+# chunk_size = {}
+# max_concurrent_requests = {}
+# optional timing of auctions, e.g. for devnets with unusual slot timing; timeouts must fit in a slot
+# [boost.timing]
+# auction_lifetime_slots = {}
+# registration_timeout_ms = {}
+# get_header_timeout_ms = {}
+# get_payload_timeout_ms = {}
+"#,
+        boost.host,
+        boost.port,
+        example_relays(network),
+        boost.verify_payload_attributes,
+        boost.registration.chunk_size,
+        boost.registration.max_concurrent_requests,
+        boost.timing.auction_lifetime_slots,
+        boost.timing.registration_timeout_ms,
+        boost.timing.get_header_timeout_ms,
+        boost.timing.get_payload_timeout_ms,
+    );
+    render_relay_client("boost", config);
+    render_server("boost", config);
+    config.push_str(
+        r#"
+# optional admin API
+# [boost.admin]
+# host = "127.0.0.1"
+# port = 18551
+# auth_token = "some-secret-token"
+# optional Prometheus metrics served at `/metrics`
+# [boost.metrics]
+# host = "127.0.0.1"
+# port = 9100
+"#,
+    );
+    Ok(())
+}
+
+#[cfg(not(feature = "boost"))]
+fn render_boost(_network: &str, _config: &mut String) -> eyre::Result<()> {
+    eyre::bail!("`mev` was built without the `boost` feature")
+}
+
+#[cfg(feature = "relay")]
+fn render_relay(config: &mut String) -> eyre::Result<()> {
+    let relay = mev_relay_rs::Config::default();
+    let _ = write!(
+        config,
+        r#"
+[relay]
+host = "{}"
+port = {}
+beacon_node_url = "{}"
+# required: relay BLS secret key, as 0x-prefixed hex
+# secret_key = "0x<your relay BLS secret key>"
+# public keys of the builders allowed to submit blocks
+accepted_builders = []
+# file to persist changes to `accepted_builders` made via the admin API
+# builder_registry_path = "accepted_builders.json"
+# file of builder public keys to deny, one per line; reloaded on change or `SIGHUP`
+# builder_deny_list_path = "denied_builders.txt"
+# file to persist validator registrations so they survive restarts
+# validator_registrations_path = "validator_registrations.jsonl"
+# number of threads dedicated to verifying validator registrations
+# registration_threads = 4
+# directory to record the block submissions signed by allowed builders for recent slots,
+# see `mev relay replay`
+# submission_archive_path = "submissions"
+# file to append a JSON line to for every registration, submission, `getHeader` and `getPayload` call,
+# with the caller's IP address; the relay does not start if the file can not be opened
+# audit_log_path = "audit.jsonl"
+# directory to persist traces imported with `mev relay backfill`, kept for as long as `[relay.history]`
+# imported_traces_path = "imported_traces"
+# relays to import the registrations of upcoming proposers from, if not registered here
+# registration_peers = ["https://relay.example.com"]
+"#,
+        relay.host, relay.port, relay.beacon_node_url,
+    );
+    render_server("relay", config);
+    config.push_str(
+        r#"# bearer tokens required by the builder routes under `/relay/v1/builder`; the WebSocket pushing
+# payload attributes at `/relay/v1/builder/payload_attributes` is only served if these are set
+# builder_auth_tokens = ["some-secret-token"]
+# addresses allowed to submit blocks to `/relay/v1/builder/blocks` and `/relay/v1/builder/headers`
+# submission_ip_allowlist = ["10.0.0.0/8"]
+# only accept SSZ-encoded submissions (`Content-Type: application/octet-stream`)
+# ssz_only_submissions = true
+# [relay.server.tls]
+# cert_path = "/path/to/cert.pem"
+# key_path = "/path/to/key.pem"
+"#,
+    );
+    let _ = write!(
+        config,
+        r#"
+# bounds on the auction history kept in memory for the data APIs
+[relay.history]
+epochs = {}
+# outbid submissions are kept for fewer epochs, up to a maximum count
+outbid_submission_epochs = {}
+max_outbid_submissions = {}
+# epochs to keep the submissions recorded to `submission_archive_path`
+submission_archive_epochs = {}
+# timing of auctions, e.g. for devnets with unusual slot timing
+[relay.timing]
+# number of slots after its proposal an auction stays open, at most one epoch
+auction_lifetime_slots = {}
+# delay before serving the best bid to `getHeader`, at most a third of a slot
+get_header_delay_ms = {}
+# time into the slot after which `getPayload` is refused, as the block would likely be orphaned
+get_payload_cutoff_ms = {}
+# checks on block submissions beyond those on the bid trace
+[relay.validation]
+# require the last transaction to pay the proposer the bid value
+verify_proposer_payment = {}
+# also accept blocks whose `fee_recipient` is the proposer's, although the payment of their
+# priority fees can not be verified without simulating the block
+allow_fee_recipient_payment = {}
+# builders allowed to bid ahead of their payload at `/relay/v1/builder/headers`; a builder is
+# dropped from this list if the payload of a winning bid does not arrive
+# header_submission_builders = []
+"#,
+        relay.history.epochs,
+        relay.history.outbid_submission_epochs,
+        relay.history.max_outbid_submissions,
+        relay.history.submission_archive_epochs,
+        relay.timing.auction_lifetime_slots,
+        relay.timing.get_header_delay_ms,
+        relay.timing.get_payload_cutoff_ms,
+        relay.validation.verify_proposer_payment,
+        relay.validation.allow_fee_recipient_payment,
+    );
+    config.push_str(
+        r#"
+# optional admin API
+# [relay.admin]
+# host = "127.0.0.1"
+# port = 28546
+# auth_token = "some-secret-token"
+# optional Prometheus metrics served at `/metrics`
+# [relay.metrics]
+# host = "127.0.0.1"
+# port = 9101
+"#,
+    );
+    Ok(())
+}
+
+#[cfg(not(feature = "relay"))]
+fn render_relay(_config: &mut String) -> eyre::Result<()> {
+    eyre::bail!("`mev` was built without the `relay` feature")
+}
+
+#[cfg(feature = "build")]
+fn render_build(network: &str, config: &mut String) -> eyre::Result<()> {
+    let builder = mev_build_rs::Config::default();
+    let _ = write!(
+        config,
+        r#"
+[builder]
+# used to find the genesis time if it is not known for the network
+# beacon_node_url = "http://127.0.0.1:5052"
+# optional admin API summarizing the auctioneer's state at `GET /admin/v1/state`; the log filter
+# is not configurable as the node owns logging
+# [builder.admin]
+# host = "127.0.0.1"
+# port = 38546
+# auth_token = "some-secret-token"
+
+[builder.auctioneer]
+# required: builder BLS secret key, as 0x-prefixed hex
+# secret_key = "0x<your builder BLS secret key>"
+# list of relays to submit bids to
+relays = {}
+# [optional] file to record submitted bids and whether relays delivered them
+# ledger_path = "./bids.jsonl"
+# [optional] slots the node's head may trail the current slot by before the builder pauses
+# max_head_lag_slots = {}
+# [optional] standalone submission service, run with `mev submit`, to hand submissions to
+# instead of submitting to relays directly, at `host:port` or `unix:/path/to/socket`, with the
+# secret it requires, sent in the clear so only use TCP endpoints over a trusted network
+# submission_bridge = {{ endpoint = "127.0.0.1:28600", secret = "env:MEV_SUBMISSION_SECRET" }}
+"#,
+        example_relays(network),
+        mev_build_rs::DEFAULT_MAX_HEAD_LAG_SLOTS,
+    );
+    render_relay_client("builder.auctioneer", config);
+    let _ = write!(
+        config,
+        r#"
+[builder.builder]
+# [optional] address to collect transaction fees
+# if missing, sender from `execution_mnemonic` is used
+# fee_recipient = "0x"
+# [optional] extra data to write into built execution payload
+# at most 32 bytes, checked when the config is loaded
+# extra_data = "0x"
+# [optional] extra data for particular networks, keyed by chain name or chain id, taking
+# precedence over `extra_data` when building for that network
+# extra_data_by_network = {{ holesky = "0x" }}
+# required unless `payment_kms_key_ids` is set: wallet seed for builder to author payment transactions
+# execution_mnemonic = "<your wallet seed phrase>"
+# [optional] ids of AWS KMS keys to author payment transactions instead of `execution_mnemonic`
+# payment_kms_key_ids = []
+# [optional] number of accounts, derived from `execution_mnemonic`, authoring payment transactions
+# payment_signer_count = {}
+# [optional] how to pick the payment signer for each payload job, "per_slot" or "round_robin"
+# payment_signer_rotation = "{}"
+# [optional] gas limit of the payment transaction when the proposer's fee recipient is a contract
+# contract_payment_gas_limit = {}
+# [optional] gas limit to target for built blocks when lower than the proposer's registered limit
+# max_gas_limit = 30000000
+# [optional] number of dedicated threads running payload builds, shared by all open auctions
+# build_workers = 4
+# [optional] gas limits to use instead for payments to particular fee recipients
+# [builder.builder.contract_payment_gas_limits]
+# "0x" = {}
+# [optional] limits on the blobs included in built payloads
+# [builder.builder.blob_policy]
+# number of blobs to include regardless of `min_blob_fee_wei`
+# target_blobs = 3
+# most blobs to include in a payload, capped by the protocol's limit
+# max_blobs = 6
+# least fee, in wei, paid to the builder per blob to include blobs beyond `target_blobs`
+# min_blob_fee_wei = "0x38d7ea4c68000"
+
+[builder.bidder]
+# [optional] amount of value to bid as a fraction of the payload's revenue, between 0 and 1
+# bid_percent = {}
+# [optional] amount in wei to add to the bid on top of the payload's revenue,
+# sourced from the builder's wallet authoring the payment transaction
+# subsidy_wei = "{:#x}"
+# NOTE: subsidies are disabled while the builder's wallet balance is below `subsidy_wei`
+# [optional] warn when the builder's wallet balance falls below this amount in wei
+# balance_warning_threshold_wei = "0xde0b6b3a7640000"
+# [optional] time into the slot, in milliseconds, to submit a final bid of the full revenue plus
+# any subsidy
+# final_bid_deadline_ms = 500
+# [optional] bidding settings to use for a particular relay instead of the ones above,
+# relays with different settings are built for separately
+# [[builder.bidder.relay_overrides]]
+# relay_public_key = "0x..."
+# bid_percent = 1.0
+# subsidy_wei = "0x0"
+# [optional] bid the most the builder can early in the slot, then lower bids toward the relay's
+# top bid, for relays supporting cancellations
+# cancellations = false
+# lower_bids_after_ms = 0
+# top_bid_margin_wei = "0x0"
+# [optional] bid no more of the payload's revenue than would have won recent auctions
+# [builder.bidder.shading]
+# target_win_rate = {}
+# min_samples = {}
+# [optional] follow the top bid of a relay and bid just above other builders; not with `shading`
+# [builder.bidder.top_bid_feed]
+# url = "wss://"
+# margin_wei = "0x0"
+"#,
+        builder.builder.payment_signer_count.unwrap_or(mev_build_rs::DEFAULT_PAYMENT_SIGNER_COUNT),
+        builder.builder.payment_signer_rotation.as_str(),
+        mev_build_rs::PAYMENT_TO_CONTRACT_GAS_LIMIT,
+        mev_build_rs::PAYMENT_TO_CONTRACT_GAS_LIMIT,
+        builder.bidder.bid_percent.unwrap_or(1.0),
+        builder.bidder.subsidy_wei.unwrap_or_default(),
+        mev_build_rs::DEFAULT_TARGET_WIN_RATE,
+        mev_build_rs::DEFAULT_MIN_SAMPLES,
+    );
+    Ok(())
+}
+
+#[cfg(not(feature = "build"))]
+fn render_build(_network: &str, _config: &mut String) -> eyre::Result<()> {
+    eyre::bail!("`mev` was built without the `build` feature")
+}
@@ -5,3 +5,13 @@ pub mod build;
 pub mod config;
 #[cfg(feature = "relay")]
 pub mod relay;
+#[cfg(feature = "build")]
+pub mod submit;
+pub mod utils;
+
+use ethereum_consensus::networks::Network;
+
+// Parses a network given on the command line the same way as in a configuration file.
+pub(crate) fn parse_network(network: &str) -> Result<Network, serde_json::Error> {
+    serde_json::from_value(serde_json::Value::String(network.to_string()))
+}
@@ -1,3 +1,7 @@
+mod backfill;
+mod inspect;
+mod replay;
+
 use crate::cmd::config::Config;
 use clap::{Args, Subcommand};
 use eyre::OptionExt;
@@ -17,19 +21,35 @@ pub struct Command {
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     Mock { config_file: String },
+    InspectSubmission(inspect::InspectSubmissionCommand),
+    Backfill(backfill::BackfillCommand),
+    Replay(replay::ReplayCommand),
 }
 
 impl Command {
+    pub fn config_file(&self) -> Option<&str> {
+        match self.command.as_ref() {
+            Some(Commands::Mock { config_file }) => Some(config_file),
+            Some(Commands::InspectSubmission(..)) => None,
+            Some(Commands::Backfill(cmd)) => Some(&cmd.config_file),
+            Some(Commands::Replay(cmd)) => Some(&cmd.config_file),
+            None => self.config_file.as_deref(),
+        }
+    }
+
     pub async fn execute(self) -> eyre::Result<()> {
         let (config_file, _mock) = if let Some(subcommand) = self.command.as_ref() {
             match subcommand {
                 Commands::Mock { config_file } => (config_file, true),
+                Commands::InspectSubmission(cmd) => return cmd.execute(),
+                Commands::Backfill(cmd) => return cmd.execute().await,
+                Commands::Replay(cmd) => return cmd.execute().await,
             }
         } else {
             (self.config_file.as_ref().unwrap(), false)
         };
 
-        let config = Config::from_toml_file(config_file)?;
+        let config = Config::from_file(config_file)?;
 
         let network = config.network.ok_or_eyre("missing `network` from configuration)")?;
         info!("configured for `{network}`");
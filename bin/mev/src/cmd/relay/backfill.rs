@@ -0,0 +1,95 @@
+use crate::cmd::config::Config;
+use clap::Args;
+use ethereum_consensus::primitives::Slot;
+use eyre::OptionExt;
+use mev_rs::blinded_block_relayer::{
+    BlockSubmissionFilter, Client as RelayClient, DeliveredPayloadFilter,
+};
+use serde_json::{json, Value};
+use std::{collections::BTreeSet, net::SocketAddr};
+use tracing::info;
+use url::Url;
+
+#[cfg(not(feature = "minimal-preset"))]
+use beacon_api_client::mainnet::Client as BeaconApiClient;
+#[cfg(feature = "minimal-preset")]
+use beacon_api_client::minimal::Client as BeaconApiClient;
+
+#[derive(Debug, Args)]
+#[clap(about = "import delivered payloads and block submissions from another relay's data API")]
+pub struct BackfillCommand {
+    /// Configuration of the running relay, whose `[relay.admin]` API receives the traces
+    pub(crate) config_file: String,
+    /// Relay to import from, e.g. `https://relay.example.com`
+    #[clap(long)]
+    from: Url,
+    /// Start from the payloads delivered at or before this slot, otherwise the most recent
+    #[clap(long)]
+    cursor: Option<Slot>,
+    /// Number of delivered payloads to request at a time
+    #[clap(long, default_value_t = 100)]
+    page_size: usize,
+    /// Stop after this many delivered payloads
+    #[clap(long)]
+    max_payloads: Option<usize>,
+    /// Also import the block submissions received for the slot of each delivered payload
+    #[clap(long)]
+    with_submissions: bool,
+}
+
+impl BackfillCommand {
+    pub async fn execute(&self) -> eyre::Result<()> {
+        let config = Config::from_file(&self.config_file)?;
+        let admin = config
+            .relay
+            .and_then(|config| config.admin)
+            .ok_or_eyre("backfill requires the `[relay.admin]` API to be configured")?;
+        let admin_url =
+            format!("http://{}/admin/v1/backfill", SocketAddr::new(admin.host, admin.port));
+        let http = reqwest::Client::new();
+        let source = RelayClient::new(BeaconApiClient::new(self.from.clone()));
+
+        let max_payloads = self.max_payloads.unwrap_or(usize::MAX);
+        let mut cursor = self.cursor;
+        let mut payload_count = 0;
+        while payload_count < max_payloads {
+            let limit = self.page_size.min(max_payloads - payload_count);
+            let filters =
+                DeliveredPayloadFilter { cursor, limit: Some(limit), ..Default::default() };
+            let payloads = source.get_delivered_payloads(&filters).await?;
+            let Some(oldest_slot) = payloads.iter().map(|trace| trace.slot).min() else { break };
+
+            let mut submissions = vec![];
+            if self.with_submissions {
+                let slots = payloads.iter().map(|trace| trace.slot).collect::<BTreeSet<_>>();
+                for slot in slots {
+                    let filters = BlockSubmissionFilter { slot: Some(slot), ..Default::default() };
+                    submissions.extend(source.get_block_submissions(&filters).await?);
+                }
+            }
+
+            let fetched = payloads.len();
+            let response = http
+                .post(&admin_url)
+                .bearer_auth(&admin.auth_token)
+                .json(&json!({
+                    "delivered_payloads": payloads,
+                    "block_submissions": submissions,
+                }))
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<Value>()
+                .await?;
+            info!(fetched, oldest_slot, imported = %response, "imported page of traces");
+
+            payload_count += fetched;
+            if fetched < limit || oldest_slot == 0 {
+                break
+            }
+            cursor = Some(oldest_slot - 1);
+        }
+        info!(payload_count, "backfill complete");
+        Ok(())
+    }
+}
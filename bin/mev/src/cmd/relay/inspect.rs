@@ -0,0 +1,125 @@
+use crate::cmd::parse_network;
+use clap::{Args, ValueEnum};
+use ethereum_consensus::{networks::Network, state_transition::Context};
+use eyre::OptionExt;
+use mev_rs::{signing::verify_signed_builder_data, types::SignedBidSubmission};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub(super) enum Encoding {
+    Ssz,
+    Json,
+}
+
+#[derive(Debug, Args)]
+#[clap(about = "decode a `SignedBidSubmission` and verify the builder signature")]
+pub struct InspectSubmissionCommand {
+    /// File with the submission, as sent to `/relay/v1/builder/blocks`
+    submission_file: PathBuf,
+    /// Encoding of the submission, otherwise inferred from the file
+    #[clap(long, value_enum)]
+    encoding: Option<Encoding>,
+    /// Network the submission was made for, used to find the fork of an SSZ submission and to
+    /// verify the signature
+    #[clap(long, default_value = "mainnet", value_parser = parse_network)]
+    network: Network,
+}
+
+impl InspectSubmissionCommand {
+    pub fn execute(&self) -> eyre::Result<()> {
+        let context = Context::try_from(self.network.clone())?;
+        let submission = read_submission(&self.submission_file, self.encoding, &context)?;
+        let bid_trace = submission.message();
+        let payload = submission.payload();
+
+        println!("fork: {}", submission.version());
+        println!("bid trace: {}", serde_json::to_string_pretty(bid_trace)?);
+        println!("payload:");
+        println!("  block number: {}", payload.block_number());
+        println!("  block hash: {}", payload.block_hash());
+        println!("  parent hash: {}", payload.parent_hash());
+        println!("  fee recipient: {}", payload.fee_recipient());
+        println!("  gas used: {} / {}", payload.gas_used(), payload.gas_limit());
+        println!("  timestamp: {}", payload.timestamp());
+        println!("  transactions: {}", payload.transactions().len());
+        if let Some(withdrawals) = payload.withdrawals() {
+            println!("  withdrawals: {}", withdrawals.len());
+        }
+        if let Some(blobs_bundle) = submission.blobs_bundle() {
+            println!("blobs: {}", blobs_bundle.blobs.len());
+        }
+
+        let mut problems = vec![];
+        if &bid_trace.block_hash != payload.block_hash() {
+            problems.push("bid trace block hash does not match the payload");
+        }
+        if &bid_trace.parent_hash != payload.parent_hash() {
+            problems.push("bid trace parent hash does not match the payload");
+        }
+        if bid_trace.gas_limit != payload.gas_limit() {
+            problems.push("bid trace gas limit does not match the payload");
+        }
+        if bid_trace.gas_used != payload.gas_used() {
+            problems.push("bid trace gas used does not match the payload");
+        }
+        if submission.version() != context.fork_for(bid_trace.slot) {
+            problems.push("submission is not for the fork active at its slot");
+        }
+        match verify_signed_builder_data(
+            bid_trace,
+            &bid_trace.builder_public_key,
+            submission.signature(),
+            &context,
+        ) {
+            Ok(()) => println!("builder signature is valid"),
+            Err(err) => {
+                println!("builder signature is invalid: {err}");
+                problems.push("invalid builder signature");
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            for problem in &problems {
+                println!("problem: {problem}");
+            }
+            Err(eyre::eyre!("found {} problem(s) with the submission", problems.len()))
+        }
+    }
+}
+
+fn infer_encoding(path: &Path, data: &[u8]) -> Encoding {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("ssz") => Encoding::Ssz,
+        Some("json") => Encoding::Json,
+        _ => match data.iter().find(|byte| !byte.is_ascii_whitespace()) {
+            Some(b'{') => Encoding::Json,
+            _ => Encoding::Ssz,
+        },
+    }
+}
+
+/// Reads a submission from `path` in the given `encoding`, otherwise inferred from the file
+pub(super) fn read_submission(
+    path: &Path,
+    encoding: Option<Encoding>,
+    context: &Context,
+) -> eyre::Result<SignedBidSubmission> {
+    let data = fs::read(path)?;
+    match encoding.unwrap_or_else(|| infer_encoding(path, &data)) {
+        Encoding::Json => Ok(serde_json::from_slice(&data)?),
+        Encoding::Ssz => decode_ssz(&data, context),
+    }
+}
+
+// The SSZ encoding does not include the fork, so use the fork of the slot in the bid trace,
+// which is encoded first as it is fixed-size.
+fn decode_ssz(data: &[u8], context: &Context) -> eyre::Result<SignedBidSubmission> {
+    let slot = data.get(..8).ok_or_eyre("submission is too short")?;
+    let slot = u64::from_le_bytes(slot.try_into()?);
+    Ok(SignedBidSubmission::deserialize_for_fork(data, context.fork_for(slot))?)
+}
@@ -0,0 +1,137 @@
+use super::inspect::{read_submission, Encoding};
+use crate::cmd::config::Config;
+use clap::Args;
+use ethereum_consensus::state_transition::Context;
+use eyre::OptionExt;
+use mev_relay_rs::{load_auctions, Replayer, AUCTIONS_FILE_NAME};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tracing::{info, warn};
+
+#[derive(Debug, Args)]
+#[clap(about = "replay recorded block submissions through the relay's validation")]
+pub struct ReplayCommand {
+    /// Configuration of the relay whose validation the submissions are replayed through
+    pub(crate) config_file: String,
+    /// Files with a submission each, or directories of them, e.g. the relay's
+    /// `submission_archive_path`; the auctions archived alongside are the state each submission
+    /// is validated against
+    #[clap(required = true)]
+    paths: Vec<PathBuf>,
+    /// Encoding of the submissions, otherwise inferred from each file
+    #[clap(long, value_enum)]
+    encoding: Option<Encoding>,
+    /// Speed of the replay relative to the time the submissions were received, e.g. `2` to
+    /// replay twice as fast
+    #[clap(long, default_value_t = 1.0, value_parser = parse_speed)]
+    speed: f64,
+    /// Validate the submissions back-to-back, ignoring the time they were received
+    #[clap(long)]
+    no_delay: bool,
+}
+
+fn parse_speed(value: &str) -> Result<f64, String> {
+    let speed = value.parse::<f64>().map_err(|err| err.to_string())?;
+    if speed.is_finite() && speed > 0.0 {
+        Ok(speed)
+    } else {
+        Err("must be a finite number greater than 0".to_string())
+    }
+}
+
+// Gathers the files under `path`, in the order they sort by name.
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> eyre::Result<()> {
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.sort();
+        for entry in entries {
+            collect_files(&entry, files)?;
+        }
+    } else {
+        files.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+// Recover the receive time of an archived submission from its file name, if present.
+fn receive_time_ms(path: &Path) -> Option<u64> {
+    let stem = path.file_stem()?.to_str()?;
+    let (receive_time, _) = stem.split_once('-')?;
+    receive_time.parse().ok()
+}
+
+impl ReplayCommand {
+    pub async fn execute(&self) -> eyre::Result<()> {
+        let config = Config::from_file(&self.config_file)?;
+        let network = config.network.clone().ok_or_eyre("missing `network` from configuration")?;
+        let context = Context::try_from(network)?;
+        let relay_config = config.relay.ok_or_eyre("missing relay config from file provided")?;
+        let mut replayer = Replayer::new(relay_config, &context)?;
+
+        let mut files = vec![];
+        for path in &self.paths {
+            collect_files(path, &mut files)?;
+        }
+        let (auction_files, files): (Vec<_>, Vec<_>) = files
+            .into_iter()
+            .partition(|file| file.file_name().is_some_and(|name| name == AUCTIONS_FILE_NAME));
+        for file in &auction_files {
+            for auction in load_auctions(file)? {
+                replayer.insert_auction(auction);
+            }
+        }
+        info!(
+            count = files.len(),
+            auctions = auction_files.len(),
+            speed = self.speed,
+            "replaying block submissions"
+        );
+
+        let mut accepted = 0;
+        let mut rejected = BTreeMap::<String, usize>::new();
+        let mut last_receive_time = None;
+        for file in &files {
+            let submission = match read_submission(file, self.encoding, &context) {
+                Ok(submission) => submission,
+                Err(err) => {
+                    warn!(%err, file = %file.display(), "skipping unreadable submission");
+                    continue
+                }
+            };
+
+            let receive_time = receive_time_ms(file);
+            if let (Some(previous), Some(current)) = (last_receive_time, receive_time) {
+                if !self.no_delay && current > previous {
+                    let delay = Duration::from_millis(current - previous).div_f64(self.speed);
+                    tokio::time::sleep(delay).await;
+                }
+            }
+            last_receive_time = receive_time.or(last_receive_time);
+
+            let bid_trace = submission.message();
+            match replayer.validate(&submission) {
+                Ok(()) => {
+                    info!(slot = bid_trace.slot, block_hash = %bid_trace.block_hash, "accepted");
+                    accepted += 1;
+                }
+                Err(err) => {
+                    info!(slot = bid_trace.slot, block_hash = %bid_trace.block_hash, %err, "rejected");
+                    *rejected.entry(err.to_string()).or_default() += 1;
+                }
+            }
+        }
+
+        println!("accepted: {accepted}");
+        println!("rejected: {}", rejected.values().sum::<usize>());
+        for (err, count) in &rejected {
+            println!("  {count}: {err}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+use crate::cmd::config::Config;
+use clap::Args;
+use eyre::OptionExt;
+use mev_build_rs::Submitter;
+
+#[derive(Debug, Args)]
+#[clap(about = "📨 submitting payloads from a remote builder to relays")]
+pub struct Command {
+    #[clap(env, default_value = "config.toml")]
+    pub config_file: String,
+}
+
+impl Command {
+    pub async fn execute(self) -> eyre::Result<()> {
+        let config = Config::from_file(&self.config_file)?;
+        let config = config.submitter.ok_or_eyre("missing `submitter` configuration")?;
+        Ok(Submitter::new(config).spawn().await?)
+    }
+}
@@ -0,0 +1,235 @@
+use crate::cmd::parse_network;
+use beacon_api_client::{BlockId, VersionedValue};
+use clap::{Args, Subcommand};
+use ethereum_consensus::{
+    networks::Network,
+    primitives::{BlsPublicKey, Slot},
+    state_transition::Context,
+    Fork,
+};
+use eyre::OptionExt;
+use mev_rs::{
+    signing::verify_signed_builder_data,
+    types::{AuctionRequest, SignedBuilderBid},
+    BlindedBlockRelayer, Relay, RelayEndpoint,
+};
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
+use url::Url;
+
+#[cfg(not(feature = "minimal-preset"))]
+use beacon_api_client::mainnet::Client;
+#[cfg(feature = "minimal-preset")]
+use beacon_api_client::minimal::Client;
+
+#[derive(Debug, Args)]
+#[clap(about = "🛠 utilities for debugging relays and builders")]
+pub struct Command {
+    #[clap(subcommand)]
+    command: Commands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    FetchBid(FetchBidCommand),
+    VerifyBid(VerifyBidCommand),
+    #[cfg(feature = "build")]
+    Profits(ProfitsCommand),
+}
+
+impl Command {
+    pub async fn execute(self) -> eyre::Result<()> {
+        match self.command {
+            Commands::FetchBid(cmd) => cmd.execute().await,
+            Commands::VerifyBid(cmd) => cmd.execute(),
+            #[cfg(feature = "build")]
+            Commands::Profits(cmd) => cmd.execute(),
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+#[clap(about = "print the proposer schedule and best bid from a relay for a slot")]
+struct FetchBidCommand {
+    /// Beacon node used to find the parent block of `slot`
+    #[clap(long, env, default_value = "http://localhost:5052")]
+    beacon_node_url: Url,
+    /// Relay given as `https://{public_key}[:{auth_token}]@{host}`
+    #[clap(long)]
+    relay: Url,
+    /// Slot to fetch a bid for, otherwise the slot after the current head
+    #[clap(long)]
+    slot: Option<Slot>,
+}
+
+impl FetchBidCommand {
+    async fn execute(self) -> eyre::Result<()> {
+        let beacon_node = Client::new(self.beacon_node_url);
+        let parent_id = match self.slot {
+            Some(slot) => BlockId::Slot(slot.checked_sub(1).ok_or_eyre("slot has no parent")?),
+            None => BlockId::Head,
+        };
+        let parent = beacon_node.get_beacon_block(parent_id).await?;
+        let slot = self.slot.unwrap_or_else(|| parent.message().slot() + 1);
+        let parent_hash = parent
+            .message()
+            .body()
+            .execution_payload()
+            .ok_or_eyre("parent block has no execution payload")?
+            .block_hash()
+            .clone();
+
+        let relay = Relay::new(RelayEndpoint::try_from(self.relay)?, &Default::default())?;
+        let schedules = relay.get_proposal_schedule().await?;
+        let schedule = schedules
+            .into_iter()
+            .find(|schedule| schedule.slot == slot)
+            .ok_or_eyre("relay has no proposer registered for the slot")?;
+        println!("{}", serde_json::to_string_pretty(&schedule)?);
+
+        let public_key = schedule.entry.message.public_key;
+        let auction_request = AuctionRequest { slot, parent_hash, public_key };
+        let signed_bid = relay.fetch_best_bid(&auction_request).await?;
+        println!("{}", serde_json::to_string_pretty(&signed_bid)?);
+
+        Ok(())
+    }
+}
+
+fn parse_public_key(public_key: &str) -> eyre::Result<BlsPublicKey> {
+    let bytes = ethereum_consensus::serde::try_bytes_from_hex_str(public_key)?;
+    Ok(BlsPublicKey::try_from(bytes.as_ref())?)
+}
+
+#[derive(Debug, Args)]
+#[clap(about = "verify the signature and consistency of a `SignedBuilderBid`")]
+struct VerifyBidCommand {
+    /// JSON file with the bid, as returned by `getHeader`, or `-` to read from stdin
+    #[clap(default_value = "-")]
+    bid_file: PathBuf,
+    /// Public key of the relay that signed the bid
+    #[clap(long, value_parser = parse_public_key)]
+    relay_public_key: BlsPublicKey,
+    /// Network the bid was made for
+    #[clap(long, default_value = "mainnet", value_parser = parse_network)]
+    network: Network,
+    /// Slot the bid was made for, to check the bid is for the fork active at that slot
+    #[clap(long)]
+    slot: Option<Slot>,
+}
+
+impl VerifyBidCommand {
+    fn read_bid(&self) -> eyre::Result<(Option<Fork>, SignedBuilderBid)> {
+        let data = if self.bid_file.as_os_str() == "-" {
+            let mut data = String::new();
+            io::stdin().read_to_string(&mut data)?;
+            data
+        } else {
+            fs::read_to_string(&self.bid_file)?
+        };
+        // accept the response to `getHeader` with its `version`, or just the signed bid
+        if let Ok(response) = serde_json::from_str::<VersionedValue<SignedBuilderBid>>(&data) {
+            return Ok((Some(response.version), response.data))
+        }
+        Ok((None, serde_json::from_str(&data)?))
+    }
+
+    fn execute(self) -> eyre::Result<()> {
+        let (version, signed_bid) = self.read_bid()?;
+        let context = Context::try_from(self.network.clone())?;
+        println!("{signed_bid}");
+
+        let fork = signed_bid.version();
+        if let Some(version) = version {
+            if version != fork {
+                eyre::bail!("bid is for {fork} but response has version {version}");
+            }
+        }
+        if let Some(slot) = self.slot {
+            let expected_fork = context.fork_for(slot);
+            if fork != expected_fork {
+                eyre::bail!("bid is for {fork} but slot {slot} is in {expected_fork}");
+            }
+        }
+
+        let public_key = signed_bid.message.public_key();
+        if public_key != &self.relay_public_key {
+            eyre::bail!("bid is signed by {public_key:?}, not the relay public key");
+        }
+        verify_signed_builder_data(
+            &signed_bid.message,
+            &self.relay_public_key,
+            &signed_bid.signature,
+            &context,
+        )?;
+        println!("bid is consistent and has a valid signature from the relay");
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "build")]
+#[derive(Debug, Args)]
+#[clap(about = "summarize the profit of a builder's bids from its ledger")]
+struct ProfitsCommand {
+    /// Ledger file written by the builder, as given by `builder.auctioneer.ledger_path`
+    ledger_path: PathBuf,
+    /// Only include bids for slots at or after this one
+    #[clap(long)]
+    from_slot: Option<Slot>,
+    /// Only include bids for slots at or before this one
+    #[clap(long)]
+    to_slot: Option<Slot>,
+    /// Also show the totals for each slot with a winning bid
+    #[clap(long)]
+    by_slot: bool,
+}
+
+#[cfg(feature = "build")]
+impl ProfitsCommand {
+    fn print_table<K: std::fmt::Display>(
+        label: &str,
+        totals: impl IntoIterator<Item = (K, mev_build_rs::ledger::Totals)>,
+    ) {
+        println!(
+            "{label:<24}  {:>6}  {:>6}  {:>22}  {:>22}  {:>22}  {:>23}",
+            "bids", "wins", "revenue (wei)", "subsidy (wei)", "payment gas (wei)", "profit (wei)"
+        );
+        for (key, totals) in totals {
+            let (profit, is_loss) = totals.profit();
+            let profit = if is_loss { format!("-{profit}") } else { profit.to_string() };
+            println!(
+                "{:<24}  {:>6}  {:>6}  {:>22}  {:>22}  {:>22}  {profit:>23}",
+                key.to_string(),
+                totals.bids,
+                totals.wins,
+                totals.revenue,
+                totals.subsidy,
+                totals.payment_gas_cost,
+            );
+        }
+    }
+
+    fn execute(self) -> eyre::Result<()> {
+        use mev_build_rs::ledger;
+
+        let mut bids = ledger::load(&self.ledger_path)?;
+        bids.retain(|bid| {
+            self.from_slot.map_or(true, |slot| bid.slot >= slot) &&
+                self.to_slot.map_or(true, |slot| bid.slot <= slot)
+        });
+
+        Self::print_table("relay", ledger::totals_by_relay(&bids));
+        if self.by_slot {
+            println!();
+            let totals =
+                ledger::totals_by_slot(&bids).into_iter().filter(|(_, totals)| totals.wins > 0);
+            Self::print_table("slot", totals);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+use mev_rs::admin::{set_log_filter, LogFilter};
+use opentelemetry::{global, trace::TracerProvider as _, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{runtime, trace::Config as TraceConfig, Resource};
+use serde::Deserialize;
+use std::{error::Error, path::PathBuf};
+use tracing::{warn, Subscriber};
+use tracing_appender::{non_blocking::WorkerGuard, rolling};
+use tracing_subscriber::{
+    fmt::{self, MakeWriter},
+    layer::SubscriberExt,
+    registry::LookupSpan,
+    reload,
+    util::SubscriberInitExt,
+    EnvFilter, Layer, Registry,
+};
+
+pub(crate) const DEFAULT_LOG_FILE_PREFIX: &str = "mev.log";
+pub(crate) const DEFAULT_OTLP_SERVICE_NAME: &str = "mev";
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Minutely,
+    Hourly,
+    #[default]
+    Daily,
+    Never,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LogFileConfig {
+    /// Directory to write log files into
+    pub directory: PathBuf,
+    /// Prefix of each log file name, followed by the date for rotated files
+    #[serde(default = "default_log_file_prefix")]
+    pub prefix: String,
+    #[serde(default)]
+    pub rotation: LogRotation,
+    #[serde(default)]
+    pub format: LogFormat,
+}
+
+fn default_log_file_prefix() -> String {
+    DEFAULT_LOG_FILE_PREFIX.to_string()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OtlpConfig {
+    /// gRPC endpoint of the OTLP collector, e.g. `http://localhost:4317`
+    pub endpoint: String,
+    /// Value of the `service.name` resource attached to exported spans
+    #[serde(default = "default_otlp_service_name")]
+    pub service_name: String,
+}
+
+fn default_otlp_service_name() -> String {
+    DEFAULT_OTLP_SERVICE_NAME.to_string()
+}
+
+/// Logging options for the `boost` and `relay` commands; the `build` command uses the
+/// logging options provided by `reth`
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// Format of logs written to stdout
+    pub format: LogFormat,
+    /// Also write logs to files if provided
+    pub file: Option<LogFileConfig>,
+    /// Export spans to an OpenTelemetry collector if provided
+    pub otlp: Option<OtlpConfig>,
+}
+
+// Allows the admin API to change the log filter at runtime
+struct ReloadableFilter(reload::Handle<EnvFilter, Registry>);
+
+impl LogFilter for ReloadableFilter {
+    fn current(&self) -> String {
+        self.0.with_current(ToString::to_string).unwrap_or_default()
+    }
+
+    fn update(&self, directives: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let filter = EnvFilter::try_new(directives)?;
+        self.0.reload(filter)?;
+        Ok(())
+    }
+}
+
+fn fmt_layer<S, W>(format: LogFormat, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Text => fmt::layer().with_writer(writer).with_ansi(ansi).boxed(),
+        LogFormat::Json => fmt::layer().json().with_writer(writer).boxed(),
+    }
+}
+
+// Must be called from within a `tokio` runtime as the exporter runs on a background task
+fn otlp_layer<S>(config: &OtlpConfig) -> Result<Box<dyn Layer<S> + Send + Sync>, Box<dyn Error>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let exporter = opentelemetry_otlp::new_exporter().tonic().with_endpoint(&config.endpoint);
+    let resource = Resource::new([KeyValue::new("service.name", config.service_name.clone())]);
+    let provider = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(TraceConfig::default().with_resource(resource))
+        .install_batch(runtime::Tokio)?;
+    let tracer = provider.tracer("mev");
+    global::set_tracer_provider(provider);
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer).boxed())
+}
+
+/// Flushes any spans not yet sent to the OTLP collector
+pub fn shutdown_tracing() {
+    global::shutdown_tracer_provider();
+}
+
+/// Installs the global tracing subscriber, returning a guard that must be held
+/// for the lifetime of the process to flush any logs written to file
+pub fn setup_logging(config: &LoggingConfig) -> Option<WorkerGuard> {
+    let filter = EnvFilter::new(std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()));
+    let (filter, handle) = reload::Layer::new(filter);
+
+    let (file_layer, guard) = match config.file.as_ref() {
+        Some(file) => {
+            let appender = match file.rotation {
+                LogRotation::Minutely => rolling::minutely(&file.directory, &file.prefix),
+                LogRotation::Hourly => rolling::hourly(&file.directory, &file.prefix),
+                LogRotation::Daily => rolling::daily(&file.directory, &file.prefix),
+                LogRotation::Never => rolling::never(&file.directory, &file.prefix),
+            };
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            (Some(fmt_layer(file.format, writer, false)), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    let (otlp_layer, otlp_error) = match config.otlp.as_ref().map(otlp_layer) {
+        Some(Ok(layer)) => (Some(layer), None),
+        Some(Err(err)) => (None, Some(err)),
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt_layer(config.format, std::io::stdout, true))
+        .with(file_layer)
+        .with(otlp_layer)
+        .init();
+    set_log_filter(ReloadableFilter(handle));
+
+    if let Some(err) = otlp_error {
+        warn!(%err, "could not install OTLP exporter; spans will not be exported");
+    }
+
+    guard
+}
@@ -1,10 +1,11 @@
 mod cmd;
+mod logging;
 
 use clap::{Parser, Subcommand};
+use logging::{setup_logging, shutdown_tracing, LoggingConfig};
 use std::future::Future;
 use tokio::signal;
 use tracing::warn;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[cfg(feature = "build")]
 use ::{clap::CommandFactory, eyre::OptionExt, std::path::PathBuf};
@@ -27,39 +28,49 @@ enum Commands {
     Build(cmd::build::Command),
     #[cfg(feature = "relay")]
     Relay(cmd::relay::Command),
+    #[cfg(feature = "build")]
+    Submit(cmd::submit::Command),
     Config(cmd::config::Command),
+    Utils(cmd::utils::Command),
 }
 
-fn setup_logging() {
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::new(
-            std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
-        ))
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+// Reads the logging options from `config_file` so logging can be configured before the rest of
+// the configuration is processed
+fn load_logging_config(config_file: &str) -> eyre::Result<LoggingConfig> {
+    cmd::config::Config::from_file(config_file).map(|config| config.logging)
 }
 
-fn run_task_until_signal(task: impl Future<Output = eyre::Result<()>>) -> eyre::Result<()> {
-    setup_logging();
+fn run_task_until_signal(
+    task: impl Future<Output = eyre::Result<()>>,
+    logging_config: LoggingConfig,
+) -> eyre::Result<()> {
+    // impl #[tokio::main]
+    let runtime =
+        tokio::runtime::Builder::new_multi_thread().enable_all().build().expect("can make runtime");
+
+    // the OTLP exporter, if any, is spawned onto the runtime
+    let _guard = {
+        let _enter = runtime.enter();
+        setup_logging(&logging_config)
+    };
 
     if cfg!(feature = "minimal-preset") {
         warn!("{MINIMAL_PRESET_NOTICE}");
     }
 
-    // impl #[tokio::main]
-    tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()
-        .expect("can make runtime")
-        .block_on(async move {
-            tokio::select! {
-                task = task => task,
-                _ = signal::ctrl_c() => {
-                    tracing::info!("shutting down...");
-                    Ok(())
-                }
+    let result = runtime.block_on(async move {
+        tokio::select! {
+            task = task => task,
+            _ = signal::ctrl_c() => {
+                tracing::info!("shutting down...");
+                Ok(())
             }
-        })
+        }
+    });
+
+    shutdown_tracing();
+
+    result
 }
 
 #[cfg(feature = "build")]
@@ -88,7 +99,10 @@ fn main() -> eyre::Result<()> {
 
     match cli.command {
         #[cfg(feature = "boost")]
-        Commands::Boost(cmd) => run_task_until_signal(cmd.execute()),
+        Commands::Boost(cmd) => {
+            let logging_config = load_logging_config(cmd.config_file())?;
+            run_task_until_signal(cmd.execute(), logging_config)
+        }
         #[cfg(feature = "build")]
         Commands::Build(cmd) => cmd.run(|node_builder, cli_args| async move {
             if cfg!(feature = "minimal-preset") {
@@ -102,7 +116,17 @@ fn main() -> eyre::Result<()> {
             mev_build_rs::launch(node_builder, custom_chain_config_directory,  config).await
         }),
         #[cfg(feature = "relay")]
-        Commands::Relay(cmd) => run_task_until_signal(cmd.execute()),
-        Commands::Config(cmd) => run_task_until_signal(cmd.execute()),
+        Commands::Relay(cmd) => {
+            let logging_config =
+                cmd.config_file().map(load_logging_config).transpose()?.unwrap_or_default();
+            run_task_until_signal(cmd.execute(), logging_config)
+        }
+        #[cfg(feature = "build")]
+        Commands::Submit(cmd) => {
+            let logging_config = load_logging_config(&cmd.config_file)?;
+            run_task_until_signal(cmd.execute(), logging_config)
+        }
+        Commands::Config(cmd) => run_task_until_signal(cmd.execute(), Default::default()),
+        Commands::Utils(cmd) => run_task_until_signal(cmd.execute(), Default::default()),
     }
 }
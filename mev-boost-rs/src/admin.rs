@@ -0,0 +1,25 @@
+use crate::{
+    relay_mux::RelayMux,
+    relay_stats::{LatencySummary, RelayRequest},
+};
+use axum::{
+    extract::{Json, State},
+    routing::get,
+};
+use mev_rs::{admin, config::AdminConfig, Error};
+use std::collections::HashMap;
+use tokio::task::JoinHandle;
+
+async fn handle_get_relay_latencies(
+    State(relay_mux): State<RelayMux>,
+) -> Json<HashMap<String, HashMap<RelayRequest, LatencySummary>>> {
+    Json(relay_mux.stats.latencies())
+}
+
+/// Spawns the admin API server for `relay_mux` on a new task returning the handle for it
+pub fn spawn(config: &AdminConfig, relay_mux: RelayMux) -> Result<JoinHandle<()>, Error> {
+    let router = admin::router()
+        .route("/admin/v1/relays/latency", get(handle_get_relay_latencies))
+        .with_state(relay_mux);
+    admin::spawn(config, router)
+}
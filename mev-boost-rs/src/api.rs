@@ -0,0 +1,64 @@
+use crate::{
+    dashboard::handle_get_root,
+    ledger::{DeliveredPayload, ProposerTotals},
+    relay_mux::RelayMux,
+    relay_stats::RelayCounters,
+};
+use axum::{
+    extract::{Json, Query, State},
+    routing::get,
+    Router,
+};
+use ethereum_consensus::primitives::BlsPublicKey;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize)]
+struct RelayStatus {
+    endpoint: String,
+    public_key: BlsPublicKey,
+    #[serde(flatten)]
+    counters: RelayCounters,
+}
+
+async fn handle_get_relays(State(relay_mux): State<RelayMux>) -> Json<Vec<RelayStatus>> {
+    let relays = relay_mux
+        .relays()
+        .iter()
+        .map(|relay| RelayStatus {
+            endpoint: relay.endpoint.to_string(),
+            public_key: relay.public_key.clone(),
+            counters: relay_mux.stats.counters(relay),
+        })
+        .collect();
+    Json(relays)
+}
+
+#[derive(Debug, Deserialize)]
+struct DeliveredPayloadQuery {
+    proposer_public_key: Option<BlsPublicKey>,
+    limit: Option<usize>,
+}
+
+async fn handle_get_delivered_payloads(
+    State(relay_mux): State<RelayMux>,
+    Query(query): Query<DeliveredPayloadQuery>,
+) -> Json<Vec<DeliveredPayload>> {
+    Json(relay_mux.ledger.payloads(query.proposer_public_key.as_ref(), query.limit))
+}
+
+async fn handle_get_delivered_payload_totals(
+    State(relay_mux): State<RelayMux>,
+) -> Json<HashMap<BlsPublicKey, ProposerTotals>> {
+    Json(relay_mux.ledger.totals())
+}
+
+/// Routes served by boost in addition to the builder APIs
+pub fn router(relay_mux: RelayMux) -> Router {
+    Router::new()
+        .route("/", get(handle_get_root))
+        .route("/mev/v1/relays", get(handle_get_relays))
+        .route("/mev/v1/delivered_payloads", get(handle_get_delivered_payloads))
+        .route("/mev/v1/delivered_payloads/totals", get(handle_get_delivered_payload_totals))
+        .with_state(relay_mux)
+}
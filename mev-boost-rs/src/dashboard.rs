@@ -0,0 +1,121 @@
+use crate::{
+    relay_mux::RelayMux,
+    relay_stats::{RelayRequest, HEALTHY_WITHIN_SECS},
+};
+use axum::{extract::State, response::Html};
+use std::{
+    fmt::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::trace;
+
+// Show this many of the most recent delivered payloads.
+const RECENT_PAYLOADS_LIMIT: usize = 16;
+
+// Styles are inlined so the page does not load anything from third parties.
+const HTML_HEADER: &str = r#"
+<html>
+<head>
+<meta http-equiv="refresh" content="12" />
+<style>
+  body { font-family: system-ui, sans-serif; margin: 0 auto; max-width: 1200px; padding: 1rem; color: #1b1b1b; }
+  table { border-collapse: collapse; width: 100%; margin-bottom: 2rem; }
+  th, td { text-align: left; padding: 0.4rem 0.6rem; border-bottom: 1px solid #ddd; }
+  th { background: #f4f4f4; }
+  td { font-family: ui-monospace, monospace; font-size: 0.9rem; word-break: break-all; }
+</style>
+</head>
+<body>
+<header>
+  <h1> mev-boost-rs </h1>
+</header>
+<main>
+"#;
+
+const HTML_TRAILER: &str = r#"
+</main>
+</body>
+</html>
+"#;
+
+fn render_relays(relay_mux: &RelayMux, now: u64, html: &mut String) {
+    html.push_str(
+        "<section><h2>relays</h2><table><thead><tr><th>relay</th><th>health</th>\
+         <th>last seen</th><th>bids</th><th>wins</th><th>timeouts</th>\
+         <th>invalid signatures</th><th>other invalid bids</th><th>mean getHeader latency</th>\
+         </tr></thead><tbody>",
+    );
+    for relay in relay_mux.relays() {
+        let counters = relay_mux.stats.counters(&relay);
+        let (health, last_seen) = match counters.last_seen {
+            Some(last_seen) => {
+                let elapsed = now.saturating_sub(last_seen);
+                let health = if elapsed <= HEALTHY_WITHIN_SECS { "up" } else { "down" };
+                (health, format!("{elapsed}s ago"))
+            }
+            None => ("unknown", "never".to_string()),
+        };
+        let latency = relay_mux
+            .stats
+            .latency(&relay, RelayRequest::GetHeader)
+            .map(|latency| format!("{}ms", latency.mean_ms))
+            .unwrap_or_else(|| "-".to_string());
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td>{health}</td><td>{last_seen}</td><td>{}</td><td>{}</td>\
+             <td>{}</td><td>{}</td><td>{}</td><td>{latency}</td></tr>",
+            relay.endpoint.host_str().unwrap_or_default(),
+            counters.bids,
+            counters.wins,
+            counters.timeouts,
+            counters.invalid_signatures,
+            counters.invalid_bids,
+        );
+    }
+    html.push_str("</tbody></table></section>");
+}
+
+fn render_recent_bids(relay_mux: &RelayMux, html: &mut String) {
+    html.push_str(
+        "<section><h2>recent bids</h2><table><thead><tr><th>slot</th><th>block hash</th>\
+         <th>value (wei)</th><th>relays</th></tr></thead><tbody>",
+    );
+    for bid in relay_mux.recent_bids() {
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            bid.slot,
+            bid.block_hash,
+            bid.value,
+            bid.relays.len(),
+        );
+    }
+    html.push_str("</tbody></table></section>");
+}
+
+fn render_delivered_payloads(relay_mux: &RelayMux, html: &mut String) {
+    html.push_str(
+        "<section><h2>delivered payloads</h2><table><thead><tr><th>slot</th>\
+         <th>block hash</th><th>proposer</th><th>value (wei)</th></tr></thead><tbody>",
+    );
+    for payload in relay_mux.ledger.payloads(None, Some(RECENT_PAYLOADS_LIMIT)) {
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{:?}</td><td>{}</td></tr>",
+            payload.slot, payload.block_hash, payload.proposer_public_key, payload.value,
+        );
+    }
+    html.push_str("</tbody></table></section>");
+}
+
+/// Serves a status page with the configured relays, recent bids and delivered payloads
+pub(crate) async fn handle_get_root(State(relay_mux): State<RelayMux>) -> Html<String> {
+    trace!("serving root");
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let mut html = HTML_HEADER.to_string();
+    render_relays(&relay_mux, now, &mut html);
+    render_recent_bids(&relay_mux, &mut html);
+    render_delivered_payloads(&relay_mux, &mut html);
+    html.push_str(HTML_TRAILER);
+    Html(html)
+}
@@ -0,0 +1,232 @@
+use ethereum_consensus::primitives::{BlsPublicKey, Hash32, Slot, U256};
+use mev_rs::json_lines::{rotated_path, JsonLinesWriter};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::mpsc::TrySendError,
+};
+use tracing::{info, warn};
+
+/// A payload delivered to a proposer from an auction won by one or more relays
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveredPayload {
+    pub slot: Slot,
+    pub block_hash: Hash32,
+    pub proposer_public_key: BlsPublicKey,
+    #[serde(with = "ethereum_consensus::serde::as_str")]
+    pub value: U256,
+    pub relays: Vec<String>,
+}
+
+/// Payloads delivered to a single proposer
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ProposerTotals {
+    pub count: usize,
+    #[serde(with = "ethereum_consensus::serde::as_str")]
+    pub value: U256,
+}
+
+// Number of the most recent delivered payloads kept in memory.
+const MAX_RECENT_PAYLOADS: usize = 4096;
+// Size of the ledger file past which it is rotated, keeping the previous file with a `.1` suffix.
+const MAX_LEDGER_FILE_BYTES: u64 = 64 * 1024 * 1024;
+// Number of payloads that may wait to be written before further payloads are not persisted.
+const MAX_PENDING_PAYLOADS: usize = 1024;
+
+#[derive(Debug, Default)]
+struct State {
+    // most recent last
+    recent_payloads: VecDeque<DeliveredPayload>,
+    totals: HashMap<BlsPublicKey, ProposerTotals>,
+}
+
+impl State {
+    fn insert(&mut self, payload: DeliveredPayload) {
+        let entry = self.totals.entry(payload.proposer_public_key.clone()).or_default();
+        entry.count += 1;
+        entry.value += payload.value;
+        if self.recent_payloads.len() == MAX_RECENT_PAYLOADS {
+            self.recent_payloads.pop_front();
+        }
+        self.recent_payloads.push_back(payload);
+    }
+}
+
+/// Record of the payloads delivered by boost, optionally persisted to a file with one JSON
+/// entry per line so operators can reconcile revenue across restarts
+///
+/// Only the most recent payloads are kept in memory, while the totals for each proposer cover
+/// every payload loaded from the ledger files or delivered since. Payloads are written to the
+/// file from a dedicated thread so recording one never delays the response to the proposer.
+pub struct Ledger {
+    state: RwLock<State>,
+    writer: Option<JsonLinesWriter<DeliveredPayload>>,
+}
+
+impl Ledger {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        let mut state = State::default();
+        if let Some(path) = path.as_ref() {
+            for path in [rotated_path(path), path.clone()] {
+                match load(&path) {
+                    Ok(persisted) => {
+                        info!(count = persisted.len(), path = %path.display(), "loaded delivered payloads");
+                        persisted.into_iter().for_each(|payload| state.insert(payload));
+                    }
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                    Err(err) => {
+                        warn!(%err, path = %path.display(), "could not load delivered payloads")
+                    }
+                }
+            }
+        }
+        let writer = path.and_then(|path| {
+            match JsonLinesWriter::spawn(
+                "mev-boost-ledger",
+                path.clone(),
+                MAX_PENDING_PAYLOADS,
+                Some(MAX_LEDGER_FILE_BYTES),
+            ) {
+                Ok(writer) => Some(writer),
+                Err(err) => {
+                    warn!(%err, path = %path.display(), "could not open delivered payloads; not persisting them");
+                    None
+                }
+            }
+        });
+        Self { state: RwLock::new(state), writer }
+    }
+
+    pub fn record(&self, payload: DeliveredPayload) {
+        if let Some(writer) = self.writer.as_ref() {
+            let slot = payload.slot;
+            match writer.append(payload.clone()) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => {
+                    warn!(slot, "delivered payload writer is behind; not persisting payload")
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    warn!(slot, "delivered payload writer has stopped; not persisting payload")
+                }
+            }
+        }
+        self.state.write().insert(payload);
+    }
+
+    /// Returns the delivered payloads, most recent first, optionally only those for `proposer`
+    pub fn payloads(
+        &self,
+        proposer: Option<&BlsPublicKey>,
+        limit: Option<usize>,
+    ) -> Vec<DeliveredPayload> {
+        let state = self.state.read();
+        state
+            .recent_payloads
+            .iter()
+            .rev()
+            .filter(|payload| {
+                proposer.map(|proposer| &payload.proposer_public_key == proposer).unwrap_or(true)
+            })
+            .take(limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the number and total value of the payloads delivered to each proposer
+    pub fn totals(&self) -> HashMap<BlsPublicKey, ProposerTotals> {
+        self.state.read().totals.clone()
+    }
+}
+
+fn load(path: &Path) -> io::Result<Vec<DeliveredPayload>> {
+    let data = fs::read_to_string(path)?;
+    let mut payloads = vec![];
+    for line in data.lines().filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str(line) {
+            Ok(payload) => payloads.push(payload),
+            Err(err) => warn!(%err, path = %path.display(), "skipping invalid delivered payload"),
+        }
+    }
+    Ok(payloads)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethereum_consensus::crypto::SecretKey;
+
+    #[test]
+    fn test_ledger_totals() {
+        let ledger = Ledger::new(None);
+        let proposer = SecretKey::random(&mut rand::thread_rng()).unwrap().public_key();
+        for slot in 0..3 {
+            ledger.record(DeliveredPayload {
+                slot,
+                block_hash: Default::default(),
+                proposer_public_key: proposer.clone(),
+                value: U256::from(10),
+                relays: vec![],
+            });
+        }
+        ledger.record(DeliveredPayload {
+            slot: 3,
+            block_hash: Default::default(),
+            proposer_public_key: Default::default(),
+            value: U256::from(1),
+            relays: vec![],
+        });
+
+        let totals = ledger.totals();
+        assert_eq!(totals[&proposer].count, 3);
+        assert_eq!(totals[&proposer].value, U256::from(30));
+
+        let payloads = ledger.payloads(Some(&proposer), Some(2));
+        assert_eq!(payloads.iter().map(|payload| payload.slot).collect::<Vec<_>>(), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_ledger_keeps_recent_payloads() {
+        let ledger = Ledger::new(None);
+        let proposer = SecretKey::random(&mut rand::thread_rng()).unwrap().public_key();
+        for slot in 0..=MAX_RECENT_PAYLOADS as Slot {
+            ledger.record(DeliveredPayload {
+                slot,
+                block_hash: Default::default(),
+                proposer_public_key: proposer.clone(),
+                value: U256::from(1),
+                relays: vec![],
+            });
+        }
+
+        let payloads = ledger.payloads(None, None);
+        assert_eq!(payloads.len(), MAX_RECENT_PAYLOADS);
+        assert_eq!(payloads.last().unwrap().slot, 1);
+        assert_eq!(ledger.totals()[&proposer].count, MAX_RECENT_PAYLOADS + 1);
+    }
+
+    #[test]
+    fn test_ledger_persists_payloads() {
+        let path =
+            std::env::temp_dir().join(format!("mev-boost-ledger-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let proposer = SecretKey::random(&mut rand::thread_rng()).unwrap().public_key();
+
+        let ledger = Ledger::new(Some(path.clone()));
+        ledger.record(DeliveredPayload {
+            slot: 1,
+            block_hash: Default::default(),
+            proposer_public_key: proposer.clone(),
+            value: U256::from(10),
+            relays: vec![],
+        });
+        // NOTE: waits for the writer to persist the payload
+        drop(ledger);
+
+        let ledger = Ledger::new(Some(path.clone()));
+        fs::remove_file(&path).unwrap();
+        assert_eq!(ledger.totals()[&proposer].value, U256::from(10));
+    }
+}
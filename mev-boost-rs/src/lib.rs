@@ -1,4 +1,12 @@
+mod admin;
+mod api;
+mod dashboard;
+mod ledger;
+mod payload_attributes;
 mod relay_mux;
+mod relay_stats;
 mod service;
 
-pub use service::{Config, Service};
+pub use service::{
+    Config, RegistrationConfig, RelayReloader, Service, ServiceHandle, TimingConfig,
+};
@@ -0,0 +1,98 @@
+use crate::relay_mux::RelayMux;
+use backoff::ExponentialBackoff;
+use beacon_api_client::{PayloadAttributes, PayloadAttributesTopic};
+use ethereum_consensus::{
+    capella::Withdrawal,
+    primitives::{Bytes32, Root},
+    ssz::prelude::*,
+};
+use futures_util::StreamExt;
+use mev_rs::{types::ExecutionPayloadHeader, BoostError};
+use tracing::{error, warn};
+
+#[cfg(not(feature = "minimal-preset"))]
+use beacon_api_client::mainnet::Client;
+#[cfg(feature = "minimal-preset")]
+use beacon_api_client::minimal::Client;
+#[cfg(not(feature = "minimal-preset"))]
+use ethereum_consensus::capella::mainnet::MAX_WITHDRAWALS_PER_PAYLOAD;
+#[cfg(feature = "minimal-preset")]
+use ethereum_consensus::capella::minimal::MAX_WITHDRAWALS_PER_PAYLOAD;
+
+/// Fields of the execution payload for a proposal that are fixed by the consensus node
+#[derive(Debug, Clone)]
+pub struct ExpectedPayloadFields {
+    timestamp: u64,
+    prev_randao: Bytes32,
+    withdrawals_root: Option<Root>,
+}
+
+fn compute_withdrawals_root(withdrawals: &[Withdrawal]) -> Option<Root> {
+    let withdrawals =
+        List::<Withdrawal, MAX_WITHDRAWALS_PER_PAYLOAD>::try_from(withdrawals.to_vec()).ok()?;
+    withdrawals.hash_tree_root().ok()
+}
+
+impl ExpectedPayloadFields {
+    pub fn from_attributes(attributes: &PayloadAttributes) -> Self {
+        let withdrawals_root = attributes.withdrawals.as_deref().and_then(|withdrawals| {
+            let root = compute_withdrawals_root(withdrawals);
+            if root.is_none() {
+                warn!(count = withdrawals.len(), "could not compute withdrawals root");
+            }
+            root
+        });
+        Self {
+            timestamp: attributes.timestamp,
+            prev_randao: attributes.prev_randao.clone(),
+            withdrawals_root,
+        }
+    }
+
+    pub fn validate(&self, header: &ExecutionPayloadHeader) -> Result<(), BoostError> {
+        if header.timestamp() != self.timestamp {
+            return Err(BoostError::BidPayloadAttributesMismatch("timestamp"))
+        }
+        if header.prev_randao() != &self.prev_randao {
+            return Err(BoostError::BidPayloadAttributesMismatch("prev_randao"))
+        }
+        if let Some(expected) = self.withdrawals_root.as_ref() {
+            if header.withdrawals_root() != Some(expected) {
+                return Err(BoostError::BidPayloadAttributesMismatch("withdrawals_root"))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Follows the payload attributes published by `beacon_node`, passing them to `relay_mux`
+pub async fn follow_payload_attributes(beacon_node: Client, relay_mux: RelayMux) {
+    loop {
+        let result =
+            backoff::future::retry::<(), (), _, _, _>(ExponentialBackoff::default(), || async {
+                let retry = backoff::Error::transient(());
+                let mut stream = match beacon_node.get_events::<PayloadAttributesTopic>().await {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        error!(%err, "could not open payload attributes stream");
+                        return Err(retry)
+                    }
+                };
+
+                while let Some(event) = stream.next().await {
+                    match event {
+                        Ok(event) => relay_mux.on_payload_attributes(event.data),
+                        Err(err) => {
+                            warn!(%err, "error reading payload attributes stream");
+                            return Err(retry)
+                        }
+                    }
+                }
+                Err(retry)
+            })
+            .await;
+        if result.is_err() {
+            error!("failed to read from event stream with exponential backoff, restarting...");
+        }
+    }
+}
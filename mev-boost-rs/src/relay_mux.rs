@@ -1,44 +1,83 @@
+use crate::{
+    ledger::{DeliveredPayload, Ledger},
+    payload_attributes::ExpectedPayloadFields,
+    relay_stats::{InvalidBid, RelayRequest, RelayStats},
+    service::{RegistrationConfig, TimingConfig},
+};
 use async_trait::async_trait;
+use beacon_api_client::PayloadAttributesEvent;
 use ethereum_consensus::{
     crypto::KzgCommitment,
-    primitives::{BlsPublicKey, Hash32, Slot, U256},
+    primitives::{BlsPublicKey, Domain, Hash32, Slot, U256},
     state_transition::Context,
 };
-use futures_util::{stream, StreamExt};
+use futures_util::{
+    future::{BoxFuture, Shared},
+    stream::{self, FuturesUnordered},
+    FutureExt, StreamExt,
+};
 use mev_rs::{
+    admin::Introspect,
+    blinded_block_provider::EncodedRegistrations,
     relay::Relay,
-    signing::verify_signed_builder_data,
+    request_id,
+    signing::{compute_builder_domain, verify_signed_data},
     types::{
         AuctionContents, AuctionRequest, SignedBlindedBeaconBlock, SignedBuilderBid,
         SignedValidatorRegistration,
     },
     BlindedBlockProvider, BoostError, Error,
 };
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use rand::prelude::*;
-use std::{cmp::Ordering, collections::HashMap, ops::Deref, sync::Arc, time::Duration};
-use tokio::time::timeout;
-use tracing::{debug, info, warn};
-
-// Track an auction for this amount of time, in slots.
-const AUCTION_LIFETIME: u64 = 2;
-// Give relays this amount of time in seconds to process validator registrations.
-const VALIDATOR_REGISTRATION_TIME_OUT_SECS: u64 = 4;
-// Give relays this amount of time in seconds to return bids.
-const FETCH_BEST_BID_TIME_OUT_SECS: u64 = 1;
-// Give relays this amount of time in seconds to respond with a payload.
-const FETCH_PAYLOAD_TIME_OUT_SECS: u64 = 4;
+use serde_json::json;
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, VecDeque},
+    ops::Deref,
+    sync::{Arc, OnceLock},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::time::{timeout, Instant};
+use tracing::{debug, info, info_span, trace, warn, Instrument};
+
+// Keep this many of the most recent best bids for display.
+const RECENT_BIDS_LIMIT: usize = 32;
 
 #[derive(Debug)]
 struct AuctionContext {
     slot: Slot,
+    proposer_public_key: BlsPublicKey,
+    value: U256,
     relays: Vec<Arc<Relay>>,
 }
 
+// Ensure the bid is for the requested auction so stale or mis-slotted bids are not selected
+fn validate_bid_for_auction(
+    bid: &SignedBuilderBid,
+    auction_request: &AuctionRequest,
+    context: &Context,
+) -> Result<(), Error> {
+    let parent_hash = bid.message.header().parent_hash();
+    if parent_hash != &auction_request.parent_hash {
+        return Err(BoostError::BidParentHashMismatch {
+            bid: parent_hash.clone(),
+            request: auction_request.parent_hash.clone(),
+        }
+        .into())
+    }
+    let expected_fork = context.fork_for(auction_request.slot);
+    let fork = bid.version();
+    if fork != expected_fork {
+        return Err(Error::InvalidFork { expected: expected_fork, provided: fork })
+    }
+    Ok(())
+}
+
 fn validate_bid(
     bid: &SignedBuilderBid,
     public_key: &BlsPublicKey,
-    context: &Context,
+    builder_domain: Domain,
 ) -> Result<(), Error> {
     let bid_public_key = bid.message.public_key();
     if bid_public_key != public_key {
@@ -48,8 +87,7 @@ fn validate_bid(
         }
         .into())
     }
-    verify_signed_builder_data(&bid.message, public_key, &bid.signature, context)
-        .map_err(Into::into)
+    verify_signed_data(&bid.message, &bid.signature, public_key, builder_domain).map_err(Into::into)
 }
 
 fn validate_payload(
@@ -109,105 +147,260 @@ impl Deref for RelayMux {
 }
 
 pub struct Inner {
-    relays: Vec<Arc<Relay>>,
+    relays: RwLock<Vec<Arc<Relay>>>,
     context: Arc<Context>,
+    // set once the service has resolved it, e.g. from the beacon node
+    genesis_time: OnceLock<u64>,
     state: Mutex<State>,
+    pub(crate) stats: RelayStats,
+    pub(crate) ledger: Ledger,
+    registration: RegistrationConfig,
+    timing: TimingConfig,
 }
 
-#[derive(Debug, Default)]
+type SharedBidRequest = Shared<BoxFuture<'static, Result<SignedBuilderBid, Arc<Error>>>>;
+
+#[derive(Default)]
 struct State {
     outstanding_bids: HashMap<Hash32, Arc<AuctionContext>>,
+    bid_requests: HashMap<AuctionRequest, SharedBidRequest>,
+    // keyed by proposal slot and parent block hash
+    expected_payload_fields: HashMap<(Slot, Hash32), ExpectedPayloadFields>,
+    recent_bids: VecDeque<(Hash32, Arc<AuctionContext>)>,
+}
+
+/// The best bid boost acquired for an auction
+#[derive(Debug)]
+pub struct BidSummary {
+    pub slot: Slot,
+    pub block_hash: Hash32,
+    pub value: U256,
+    pub relays: Vec<String>,
 }
 
 impl RelayMux {
-    pub fn new(relays: Vec<Relay>, context: Arc<Context>) -> Self {
+    pub fn new(
+        relays: Vec<Relay>,
+        context: Arc<Context>,
+        ledger: Ledger,
+        registration: RegistrationConfig,
+        timing: TimingConfig,
+    ) -> Self {
         let inner = Inner {
-            relays: relays.into_iter().map(Arc::new).collect(),
+            relays: RwLock::new(relays.into_iter().map(Arc::new).collect()),
             context,
+            genesis_time: Default::default(),
             state: Default::default(),
+            stats: Default::default(),
+            ledger,
+            registration,
+            timing,
         };
         Self(Arc::new(inner))
     }
 
+    pub(crate) fn relays(&self) -> Vec<Arc<Relay>> {
+        self.relays.read().clone()
+    }
+
+    /// Replaces the set of relays used for new auctions
+    ///
+    /// Every relay is replaced, even with an unchanged endpoint, so changes to its auth token or
+    /// client settings take effect. Outstanding bids keep a reference to the relays they came
+    /// from so they can still be opened.
+    pub fn update_relays(&self, relays: Vec<Relay>) {
+        let mut current_relays = self.relays.write();
+        let updated_relays = relays.into_iter().map(Arc::new).collect::<Vec<_>>();
+        info!(previous = ?*current_relays, current = ?updated_relays, "updated relays");
+        *current_relays = updated_relays;
+    }
+
+    /// Records the genesis time of the chain, once known
+    pub(crate) fn set_genesis_time(&self, genesis_time: u64) {
+        let _ = self.genesis_time.set(genesis_time);
+    }
+
+    // Checks the chain has started and at least one relay responded recently, probing the
+    // relays for their status if none have
+    async fn check_readiness(&self) -> Result<(), BoostError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let genesis_time =
+            self.genesis_time.get().copied().or_else(|| self.context.genesis_time().ok());
+        match genesis_time {
+            Some(genesis_time) if now >= genesis_time => {}
+            Some(_) => return Err(BoostError::NotReady("the chain has not started")),
+            None => return Err(BoostError::NotReady("the genesis time is not yet known")),
+        }
+
+        let relays = self.relays();
+        if relays.is_empty() {
+            return Err(BoostError::NotReady("no relays are configured"))
+        }
+        if relays.iter().any(|relay| self.stats.is_healthy(relay, now)) {
+            return Ok(())
+        }
+
+        let duration = Duration::from_millis(self.timing.get_header_timeout_ms);
+        let mut responses = relays
+            .into_iter()
+            .map(|relay| async move {
+                let result = timeout(duration, relay.check_status()).await;
+                (relay, result)
+            })
+            .collect::<FuturesUnordered<_>>();
+        while let Some((relay, result)) = responses.next().await {
+            match result {
+                Ok(Ok(())) => {
+                    self.stats.record_seen(&relay);
+                    return Ok(())
+                }
+                Ok(Err(err)) => warn!(%err, %relay, "relay failed status check"),
+                Err(_) => warn!(%relay, "timeout when checking relay status"),
+            }
+        }
+        Err(BoostError::NotReady("no relay is reachable"))
+    }
+
     pub fn on_slot(&self, slot: Slot) {
         debug!(slot, "processing");
-        let retain_slot = slot.checked_sub(AUCTION_LIFETIME).unwrap_or_default();
+        let retain_slot = slot.checked_sub(self.timing.auction_lifetime_slots).unwrap_or_default();
         let mut state = self.state.lock();
         state.outstanding_bids.retain(|_, auction| auction.slot >= retain_slot);
+        state.bid_requests.retain(|auction_request, _| auction_request.slot >= retain_slot);
+        state.expected_payload_fields.retain(|(slot, _), _| *slot >= retain_slot);
     }
 
-    fn get_context(&self, key: &Hash32) -> Result<Arc<AuctionContext>, Error> {
-        let state = self.state.lock();
+    /// Records the payload attributes for an upcoming proposal so bids can be checked against them
+    pub fn on_payload_attributes(&self, event: PayloadAttributesEvent) {
+        trace!(?event, "processing payload attributes");
+        let fields = ExpectedPayloadFields::from_attributes(&event.payload_attributes);
+        let mut state = self.state.lock();
         state
-            .outstanding_bids
-            .get(key)
-            .cloned()
-            .ok_or_else::<Error, _>(|| BoostError::MissingOpenBid(key.clone()).into())
+            .expected_payload_fields
+            .insert((event.proposal_slot, event.parent_block_hash), fields);
     }
-}
 
-#[async_trait]
-impl BlindedBlockProvider for RelayMux {
-    async fn register_validators(
+    fn get_expected_payload_fields(
         &self,
-        registrations: &[SignedValidatorRegistration],
-    ) -> Result<(), Error> {
-        let responses = stream::iter(self.relays.iter().cloned())
-            .map(|relay| async {
-                let request = relay.register_validators(registrations);
-                let duration = Duration::from_secs(VALIDATOR_REGISTRATION_TIME_OUT_SECS);
-                let result = timeout(duration, request).await;
-                (relay, result)
-            })
-            .buffer_unordered(self.relays.len())
-            .filter_map(|(relay, result)| async move {
-                match result {
-                    Ok(Ok(_)) => Some(()),
-                    Ok(Err(err)) => {
-                        warn!(%err, %relay, "failure when registering validator(s)");
-                        None
-                    }
-                    Err(_) => {
-                        warn!(%relay, "timeout when registering validator(s)");
-                        None
-                    }
-                }
+        auction_request: &AuctionRequest,
+    ) -> Option<ExpectedPayloadFields> {
+        let state = self.state.lock();
+        let key = (auction_request.slot, auction_request.parent_hash.clone());
+        state.expected_payload_fields.get(&key).cloned()
+    }
+
+    /// Returns the most recent best bids, most recent first
+    pub(crate) fn recent_bids(&self) -> Vec<BidSummary> {
+        let state = self.state.lock();
+        state
+            .recent_bids
+            .iter()
+            .rev()
+            .map(|(block_hash, auction)| BidSummary {
+                slot: auction.slot,
+                block_hash: block_hash.clone(),
+                value: auction.value,
+                relays: auction.relays.iter().map(|relay| relay.endpoint.to_string()).collect(),
             })
-            .collect::<Vec<_>>()
-            .await;
+            .collect()
+    }
 
-        if responses.is_empty() {
-            Err(BoostError::CouldNotRegister.into())
-        } else {
-            let count = registrations.len();
-            info!(count, "sent validator registrations");
-            Ok(())
+    // Sends one chunk of `registrations` to `relay`, returning `true` if it was accepted
+    async fn register_validators_with_relay(
+        &self,
+        relay: &Relay,
+        registrations: &EncodedRegistrations,
+    ) -> bool {
+        let request = relay.register_encoded_validators(registrations);
+        let duration = Duration::from_millis(self.timing.registration_timeout_ms);
+        let start = Instant::now();
+        let result = timeout(duration, request).await;
+        self.stats.record_latency(
+            relay,
+            RelayRequest::RegisterValidators,
+            start.elapsed(),
+            result.is_err(),
+        );
+        let count = registrations.len();
+        match result {
+            Ok(Ok(_)) => {
+                self.stats.record_seen(relay);
+                true
+            }
+            Ok(Err(err)) => {
+                warn!(%err, %relay, count, "failure when registering validator(s)");
+                false
+            }
+            Err(_) => {
+                warn!(%relay, count, "timeout when registering validator(s)");
+                false
+            }
         }
     }
 
-    async fn fetch_best_bid(
+    async fn fetch_best_bid_from_relays(
         &self,
         auction_request: &AuctionRequest,
     ) -> Result<SignedBuilderBid, Error> {
-        let bids = stream::iter(self.relays.iter().cloned())
+        let expected_payload_fields = self.get_expected_payload_fields(auction_request);
+        let expected_payload_fields = expected_payload_fields.as_ref();
+        let builder_domain = compute_builder_domain(&self.context)?;
+
+        let relays = self.relays();
+        let relay_count = relays.len();
+        let bids = stream::iter(relays)
             .map(|relay| async {
-                let request = relay.fetch_best_bid(auction_request);
-                let duration = Duration::from_secs(FETCH_BEST_BID_TIME_OUT_SECS);
+                let request = relay
+                    .fetch_best_bid(auction_request)
+                    .instrument(info_span!("fetch_best_bid", %relay));
+                let duration = Duration::from_millis(self.timing.get_header_timeout_ms);
+                let start = Instant::now();
                 let result = timeout(duration, request).await;
+                self.stats.record_latency(
+                    &relay,
+                    RelayRequest::GetHeader,
+                    start.elapsed(),
+                    result.is_err(),
+                );
                 (relay, result)
             })
-            .buffer_unordered(self.relays.len())
+            .buffer_unordered(relay_count)
             .filter_map(|(relay, result)| async {
                 match result {
                     Ok(Ok(bid)) => {
-                        if let Err(err) = validate_bid(&bid, &relay.public_key, &self.context) {
+                        self.stats.record_seen(&relay);
+                        if let Err(err) =
+                            validate_bid_for_auction(&bid, auction_request, &self.context)
+                        {
+                            self.stats.record_invalid_bid(&relay, InvalidBid::AuctionMismatch);
+                            warn!(%err, %relay, "builder bid does not match auction");
+                            None
+                        } else if let Some(Err(err)) = expected_payload_fields
+                            .map(|fields| fields.validate(bid.message.header()))
+                        {
+                            self.stats
+                                .record_invalid_bid(&relay, InvalidBid::PayloadAttributesMismatch);
+                            warn!(%err, %relay, "builder bid does not match payload attributes");
+                            None
+                        } else if let Err(err) =
+                            validate_bid(&bid, &relay.public_key, builder_domain)
+                        {
+                            let reason = match &err {
+                                Error::Boost(BoostError::BidPublicKeyMismatch { .. }) => {
+                                    InvalidBid::PublicKeyMismatch
+                                }
+                                _ => InvalidBid::InvalidSignature,
+                            };
+                            self.stats.record_invalid_bid(&relay, reason);
                             warn!(%err, %relay, "invalid signed builder bid");
                             None
                         } else {
+                            self.stats.record_bid(&relay);
                             Some((relay, bid))
                         }
                     }
                     Ok(Err(Error::NoBidPrepared(auction_request))) => {
+                        self.stats.record_seen(&relay);
                         debug!(%auction_request, %relay, "relay did not have a bid prepared");
                         None
                     }
@@ -216,7 +409,7 @@ impl BlindedBlockProvider for RelayMux {
                         None
                     }
                     Err(_) => {
-                        warn!(timeout_in_sec = FETCH_BEST_BID_TIME_OUT_SECS, %relay, "timeout when fetching bid");
+                        warn!(timeout_ms = self.timing.get_header_timeout_ms, %relay, "timeout when fetching bid");
                         None
                     }
                 }
@@ -262,13 +455,142 @@ impl BlindedBlockProvider for RelayMux {
 
         {
             let mut state = self.state.lock();
-            let auction_context = AuctionContext { slot, relays: best_relays };
-            state.outstanding_bids.insert(best_block_hash.clone(), Arc::new(auction_context));
+            let auction_context = AuctionContext {
+                slot,
+                proposer_public_key: auction_request.public_key.clone(),
+                value: best_bid.message.value(),
+                relays: best_relays,
+            };
+            let auction_context = Arc::new(auction_context);
+            state.outstanding_bids.insert(best_block_hash.clone(), auction_context.clone());
+            if state.recent_bids.len() == RECENT_BIDS_LIMIT {
+                state.recent_bids.pop_front();
+            }
+            state.recent_bids.push_back((best_block_hash.clone(), auction_context));
         }
 
         Ok(best_bid.clone())
     }
 
+    fn get_context(&self, key: &Hash32) -> Result<Arc<AuctionContext>, Error> {
+        let state = self.state.lock();
+        state
+            .outstanding_bids
+            .get(key)
+            .cloned()
+            .ok_or_else::<Error, _>(|| BoostError::MissingOpenBid(key.clone()).into())
+    }
+}
+
+#[async_trait]
+impl Introspect for RelayMux {
+    async fn state_summary(&self) -> serde_json::Value {
+        let relays =
+            self.relays().iter().map(|relay| relay.endpoint.to_string()).collect::<Vec<_>>();
+        let state = self.state.lock();
+        let outstanding_bids = state
+            .outstanding_bids
+            .iter()
+            .map(|(block_hash, auction)| {
+                let relays = auction
+                    .relays
+                    .iter()
+                    .map(|relay| relay.endpoint.to_string())
+                    .collect::<Vec<_>>();
+                json!({ "block_hash": block_hash, "slot": auction.slot, "relays": relays })
+            })
+            .collect::<Vec<_>>();
+        json!({ "relays": relays, "outstanding_bids": outstanding_bids })
+    }
+}
+
+#[async_trait]
+impl BlindedBlockProvider for RelayMux {
+    async fn check_status(&self) -> Result<(), Error> {
+        self.check_readiness().await.map_err(|err| {
+            debug!(%err, "not ready");
+            err.into()
+        })
+    }
+
+    async fn register_validators(
+        &self,
+        registrations: &[SignedValidatorRegistration],
+    ) -> Result<(), Error> {
+        let chunk_size = self.registration.chunk_size.max(1);
+        // NOTE: encode each chunk once up front, rather than once per relay
+        let chunks = registrations
+            .chunks(chunk_size)
+            .map(EncodedRegistrations::new)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| {
+                warn!(%err, "could not encode validator registrations");
+                BoostError::CouldNotRegister
+            })?;
+        let chunks = chunks.as_slice();
+
+        let relays = self.relays();
+        let relay_count = relays.len();
+        let max_concurrent_requests = self.registration.max_concurrent_requests.max(1);
+        let responses = stream::iter(relays)
+            .map(|relay| async move {
+                let results = stream::iter(chunks)
+                    .map(|chunk| self.register_validators_with_relay(&relay, chunk))
+                    .buffer_unordered(max_concurrent_requests)
+                    .collect::<Vec<_>>()
+                    .await;
+                results.into_iter().all(|registered| registered).then_some(())
+            })
+            .buffer_unordered(relay_count)
+            .filter_map(|response| async move { response })
+            .collect::<Vec<_>>()
+            .await;
+
+        if responses.is_empty() {
+            Err(BoostError::CouldNotRegister.into())
+        } else {
+            let count = registrations.len();
+            info!(count, "sent validator registrations");
+            Ok(())
+        }
+    }
+
+    async fn fetch_best_bid(
+        &self,
+        auction_request: &AuctionRequest,
+    ) -> Result<SignedBuilderBid, Error> {
+        // NOTE: concurrent requests for the same auction share a single request to each relay
+        let request = {
+            let mut state = self.state.lock();
+            state
+                .bid_requests
+                .entry(auction_request.clone())
+                .or_insert_with(|| {
+                    let relay_mux = self.clone();
+                    let auction_request = auction_request.clone();
+                    async move {
+                        relay_mux
+                            .fetch_best_bid_from_relays(&auction_request)
+                            .await
+                            .map_err(Arc::new)
+                    }
+                    .boxed()
+                    .shared()
+                })
+                .clone()
+        };
+        let bid = request.clone().await;
+        {
+            let mut state = self.state.lock();
+            if let Some(current) = state.bid_requests.get(auction_request) {
+                if current.ptr_eq(&request) {
+                    state.bid_requests.remove(auction_request);
+                }
+            }
+        }
+        bid.map_err(|err| Arc::try_unwrap(err).unwrap_or_else(Error::Shared))
+    }
+
     async fn open_bid(
         &self,
         signed_block: &SignedBlindedBeaconBlock,
@@ -279,44 +601,74 @@ impl BlindedBlockProvider for RelayMux {
         let expected_block_hash = body.execution_payload_header().block_hash().clone();
         let context = self.get_context(&expected_block_hash)?;
 
-        let responses = stream::iter(context.relays.iter().cloned())
-            .map(|relay| async move {
-                let request = relay.open_bid(signed_block);
-                let duration = Duration::from_secs(FETCH_PAYLOAD_TIME_OUT_SECS);
-                let result = timeout(duration, request).await;
-                (relay, result)
-            })
-            .buffer_unordered(self.relays.len())
-            .filter_map(|(relay, result)| async move {
-                match result {
-                    Ok(response) => Some((relay, response)),
-                    Err(_) => {
-                        warn!( %relay, "timeout when opening bid");
-                        None
+        // NOTE: requests are driven by `responses` and made `'static` so any still outstanding
+        // after the first valid payload can finish in the background
+        let shared_block = Arc::new(signed_block.clone());
+        let mut responses = context
+            .relays
+            .iter()
+            .cloned()
+            .map(|relay| {
+                let relay_mux = self.clone();
+                let signed_block = shared_block.clone();
+                async move {
+                    let request =
+                        relay.open_bid(&signed_block).instrument(info_span!("open_bid", %relay));
+                    let duration = Duration::from_millis(relay_mux.timing.get_payload_timeout_ms);
+                    let start = Instant::now();
+                    let result = timeout(duration, request).await;
+                    relay_mux.stats.record_latency(
+                        &relay,
+                        RelayRequest::GetPayload,
+                        start.elapsed(),
+                        result.is_err(),
+                    );
+                    if let Ok(Ok(_)) = &result {
+                        relay_mux.stats.record_seen(&relay);
                     }
+                    (relay, result)
                 }
             })
-            .collect::<Vec<_>>()
-            .await;
+            .collect::<FuturesUnordered<_>>();
 
-        for (relay, response) in responses.into_iter() {
-            match response {
-                Ok(auction_contents) => match validate_payload(
+        while let Some((relay, result)) = responses.next().await {
+            match result {
+                Ok(Ok(auction_contents)) => match validate_payload(
                     &auction_contents,
                     &expected_block_hash,
                     body.blob_kzg_commitments().map(|commitments| commitments.as_slice()),
                 ) {
                     Ok(_) => {
                         info!(%slot, block_hash = %expected_block_hash, %relay, "acquired payload");
+                        self.stats.record_win(&relay);
+                        self.ledger.record(DeliveredPayload {
+                            slot,
+                            block_hash: expected_block_hash.clone(),
+                            proposer_public_key: context.proposer_public_key.clone(),
+                            value: context.value,
+                            relays: context
+                                .relays
+                                .iter()
+                                .map(|relay| relay.endpoint.to_string())
+                                .collect(),
+                        });
+                        if !responses.is_empty() {
+                            tokio::spawn(request_id::in_current_request(
+                                responses.for_each(|_| async {}),
+                            ));
+                        }
                         return Ok(auction_contents)
                     }
                     Err(err) => {
                         warn!(?err, ?relay, "could not validate payload");
                     }
                 },
-                Err(err) => {
+                Ok(Err(err)) => {
                     warn!(%err, %relay, "error opening bid");
                 }
+                Err(_) => {
+                    warn!(%relay, "timeout when opening bid");
+                }
             }
         }
 
@@ -0,0 +1,275 @@
+use metrics::{counter, histogram};
+use mev_rs::relay::Relay;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+// Consider a relay healthy if it responded within this many seconds, i.e. one epoch.
+pub(crate) const HEALTHY_WITHIN_SECS: u64 = 384;
+
+// Upper bounds of the latency buckets tracked for each relay, in milliseconds.
+const LATENCY_BUCKETS_MS: [u64; 9] = [25, 50, 100, 250, 500, 1_000, 2_000, 4_000, 8_000];
+
+/// The requests boost makes to each relay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelayRequest {
+    RegisterValidators,
+    GetHeader,
+    GetPayload,
+}
+
+impl RelayRequest {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::RegisterValidators => "register_validators",
+            Self::GetHeader => "get_header",
+            Self::GetPayload => "get_payload",
+        }
+    }
+}
+
+/// Reasons boost refuses a bid returned by a relay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidBid {
+    /// The bid is for another parent or fork than the auction
+    AuctionMismatch,
+    /// The bid does not match the payload attributes from the consensus node
+    PayloadAttributesMismatch,
+    /// The bid is signed for a public key other than the relay's
+    PublicKeyMismatch,
+    InvalidSignature,
+}
+
+impl InvalidBid {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::AuctionMismatch => "auction_mismatch",
+            Self::PayloadAttributesMismatch => "payload_attributes_mismatch",
+            Self::PublicKeyMismatch => "public_key_mismatch",
+            Self::InvalidSignature => "invalid_signature",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LatencyBucket {
+    /// Upper bound of this bucket, or `None` for the bucket of all slower requests
+    pub le_ms: Option<u64>,
+    pub count: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LatencySummary {
+    pub count: u64,
+    pub timeouts: u64,
+    pub mean_ms: u64,
+    pub max_ms: u64,
+    pub buckets: Vec<LatencyBucket>,
+}
+
+#[derive(Debug, Default)]
+struct LatencyHistogram {
+    count: u64,
+    timeouts: u64,
+    total: Duration,
+    max: Duration,
+    // one count per entry in `LATENCY_BUCKETS_MS` with a final count for all slower requests
+    buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, elapsed: Duration, timed_out: bool) {
+        self.count += 1;
+        if timed_out {
+            self.timeouts += 1;
+        }
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+        let elapsed_ms = elapsed.as_millis() as u64;
+        let index = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| elapsed_ms <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.buckets[index] += 1;
+    }
+
+    fn summary(&self) -> LatencySummary {
+        let mean_ms = match self.count {
+            0 => 0,
+            count => (self.total.as_millis() / count as u128) as u64,
+        };
+        let buckets = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(index, count)| LatencyBucket {
+                le_ms: LATENCY_BUCKETS_MS.get(index).copied(),
+                count: *count,
+            })
+            .collect();
+        LatencySummary {
+            count: self.count,
+            timeouts: self.timeouts,
+            mean_ms,
+            max_ms: self.max.as_millis() as u64,
+            buckets,
+        }
+    }
+}
+
+/// Outcomes of the requests made to a relay
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct RelayCounters {
+    /// Number of valid bids returned
+    pub bids: u64,
+    /// Number of auctions won, counted when the relay delivers the payload of the winning bid
+    pub wins: u64,
+    /// Number of requests of any kind that timed out
+    pub timeouts: u64,
+    /// Number of bids with an invalid signature
+    pub invalid_signatures: u64,
+    /// Number of bids refused for any other reason, e.g. not matching the auction
+    pub invalid_bids: u64,
+    /// Unix timestamp, in seconds, of the last successful response
+    pub last_seen: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct RelayRecord {
+    counters: RelayCounters,
+    latencies: HashMap<RelayRequest, LatencyHistogram>,
+}
+
+/// Statistics on the requests made to each relay, also exported as metrics
+#[derive(Debug, Default)]
+pub struct RelayStats {
+    relays: Mutex<HashMap<String, RelayRecord>>,
+}
+
+fn relay_label(relay: &Relay) -> String {
+    relay.endpoint.to_string()
+}
+
+impl RelayStats {
+    fn update(&self, relay: &Relay, f: impl FnOnce(&mut RelayRecord)) {
+        let mut relays = self.relays.lock();
+        f(relays.entry(relay_label(relay)).or_default())
+    }
+
+    pub fn record_latency(
+        &self,
+        relay: &Relay,
+        request: RelayRequest,
+        elapsed: Duration,
+        timed_out: bool,
+    ) {
+        let labels = [("relay", relay_label(relay)), ("request", request.as_str().to_string())];
+        histogram!("mev_boost_relay_request_duration_seconds", &labels)
+            .record(elapsed.as_secs_f64());
+        if timed_out {
+            counter!("mev_boost_relay_request_timeouts_total", &labels).increment(1);
+        }
+
+        self.update(relay, |record| {
+            if timed_out {
+                record.counters.timeouts += 1;
+            }
+            record.latencies.entry(request).or_default().record(elapsed, timed_out);
+        });
+    }
+
+    /// Records a successful response from `relay`
+    pub fn record_seen(&self, relay: &Relay) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        self.update(relay, |record| record.counters.last_seen = Some(now));
+    }
+
+    pub fn record_bid(&self, relay: &Relay) {
+        counter!("mev_boost_relay_bids_total", "relay" => relay_label(relay)).increment(1);
+        self.update(relay, |record| record.counters.bids += 1);
+    }
+
+    pub fn record_invalid_bid(&self, relay: &Relay, reason: InvalidBid) {
+        counter!(
+            "mev_boost_relay_invalid_bids_total",
+            "relay" => relay_label(relay),
+            "reason" => reason.as_str()
+        )
+        .increment(1);
+        self.update(relay, |record| match reason {
+            InvalidBid::InvalidSignature => record.counters.invalid_signatures += 1,
+            _ => record.counters.invalid_bids += 1,
+        });
+    }
+
+    /// Records `relay` delivered the payload of the winning bid
+    pub fn record_win(&self, relay: &Relay) {
+        counter!("mev_boost_relay_wins_total", "relay" => relay_label(relay)).increment(1);
+        self.update(relay, |record| record.counters.wins += 1);
+    }
+
+    /// Whether `relay` responded successfully within [`HEALTHY_WITHIN_SECS`] of `now`
+    pub fn is_healthy(&self, relay: &Relay, now: u64) -> bool {
+        let relays = self.relays.lock();
+        relays
+            .get(&relay_label(relay))
+            .and_then(|record| record.counters.last_seen)
+            .map(|last_seen| now.saturating_sub(last_seen) <= HEALTHY_WITHIN_SECS)
+            .unwrap_or_default()
+    }
+
+    /// Returns the counters for `relay`
+    pub fn counters(&self, relay: &Relay) -> RelayCounters {
+        let relays = self.relays.lock();
+        relays.get(&relay_label(relay)).map(|record| record.counters.clone()).unwrap_or_default()
+    }
+
+    /// Returns a summary of the latencies of `request` to `relay`, if any were made
+    pub fn latency(&self, relay: &Relay, request: RelayRequest) -> Option<LatencySummary> {
+        let relays = self.relays.lock();
+        let record = relays.get(&relay_label(relay))?;
+        record.latencies.get(&request).map(LatencyHistogram::summary)
+    }
+
+    /// Returns a summary of the latencies of each type of request, keyed by relay
+    pub fn latencies(&self) -> HashMap<String, HashMap<RelayRequest, LatencySummary>> {
+        let relays = self.relays.lock();
+        relays
+            .iter()
+            .map(|(relay, record)| {
+                let requests = record
+                    .latencies
+                    .iter()
+                    .map(|(request, histogram)| (*request, histogram.summary()))
+                    .collect();
+                (relay.clone(), requests)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_histogram() {
+        let mut histogram = LatencyHistogram::default();
+        histogram.record(Duration::from_millis(10), false);
+        histogram.record(Duration::from_millis(25), false);
+        histogram.record(Duration::from_millis(300), false);
+        histogram.record(Duration::from_secs(10), true);
+
+        let summary = histogram.summary();
+        assert_eq!(summary.count, 4);
+        assert_eq!(summary.timeouts, 1);
+        assert_eq!(summary.max_ms, 10_000);
+        let counts = summary.buckets.iter().map(|bucket| bucket.count).collect::<Vec<_>>();
+        assert_eq!(counts, vec![2, 0, 0, 0, 1, 0, 0, 0, 0, 1]);
+        assert_eq!(summary.buckets.last().unwrap().le_ms, None);
+    }
+}
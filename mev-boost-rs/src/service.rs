@@ -1,33 +1,167 @@
-use crate::relay_mux::RelayMux;
-use ethereum_consensus::{networks::Network, state_transition::Context};
+use crate::{
+    admin, api, ledger::Ledger, payload_attributes::follow_payload_attributes, relay_mux::RelayMux,
+};
+use ethereum_consensus::{networks::Network, primitives::Slot, state_transition::Context};
 use futures_util::StreamExt;
 use mev_rs::{
     blinded_block_provider::Server as BlindedBlockProviderServer,
+    config::{AdminConfig, MetricsConfig, RelayClientConfig, ServerConfig},
     get_genesis_time,
-    relay::{parse_relay_endpoints, Relay},
+    relay::{create_relays, parse_relay_endpoints, Relay},
+    telemetry::install_metrics_exporter,
     Error,
 };
 use serde::Deserialize;
-use std::{future::Future, net::Ipv4Addr, pin::Pin, sync::Arc, task::Poll};
+use std::{
+    future::Future,
+    net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
+    pin::Pin,
+    sync::Arc,
+    task::Poll,
+};
 use tokio::task::{JoinError, JoinHandle};
 use tracing::{info, warn};
+use url::Url;
+
+#[cfg(not(feature = "minimal-preset"))]
+use beacon_api_client::mainnet::Client;
+#[cfg(feature = "minimal-preset")]
+use beacon_api_client::minimal::Client;
+
+// Default number of validator registrations sent to a relay in one request.
+const DEFAULT_REGISTRATION_CHUNK_SIZE: usize = 1_000;
+// Default number of concurrent registration requests to a single relay.
+const DEFAULT_MAX_CONCURRENT_REGISTRATION_REQUESTS: usize = 4;
+// Default number of slots to track an auction for.
+const DEFAULT_AUCTION_LIFETIME_SLOTS: Slot = 2;
+// Default time given to relays to process validator registrations.
+const DEFAULT_REGISTRATION_TIMEOUT_MS: u64 = 4_000;
+// Default time given to relays to return bids.
+const DEFAULT_GET_HEADER_TIMEOUT_MS: u64 = 1_000;
+// Default time given to relays to respond with a payload.
+const DEFAULT_GET_PAYLOAD_TIMEOUT_MS: u64 = 4_000;
+
+/// Settings for forwarding validator registrations to relays
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RegistrationConfig {
+    /// Split registrations into requests with at most this many registrations
+    pub chunk_size: usize,
+    /// Send at most this many requests to each relay at once
+    pub max_concurrent_requests: usize,
+}
+
+impl Default for RegistrationConfig {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_REGISTRATION_CHUNK_SIZE,
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REGISTRATION_REQUESTS,
+        }
+    }
+}
+
+/// Timing of the auctions boost runs against relays
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TimingConfig {
+    /// Number of slots to track an auction for, so its bid can still be opened
+    pub auction_lifetime_slots: Slot,
+    /// Time given to relays to process validator registrations
+    pub registration_timeout_ms: u64,
+    /// Time given to relays to return bids
+    pub get_header_timeout_ms: u64,
+    /// Time given to relays to respond with a payload
+    pub get_payload_timeout_ms: u64,
+}
+
+impl Default for TimingConfig {
+    fn default() -> Self {
+        Self {
+            auction_lifetime_slots: DEFAULT_AUCTION_LIFETIME_SLOTS,
+            registration_timeout_ms: DEFAULT_REGISTRATION_TIMEOUT_MS,
+            get_header_timeout_ms: DEFAULT_GET_HEADER_TIMEOUT_MS,
+            get_payload_timeout_ms: DEFAULT_GET_PAYLOAD_TIMEOUT_MS,
+        }
+    }
+}
+
+impl TimingConfig {
+    /// Checks the timing fits within the slots of the network described by `context`
+    pub fn validate(&self, context: &Context) -> Result<(), Error> {
+        if self.auction_lifetime_slots == 0 || self.auction_lifetime_slots > context.slots_per_epoch
+        {
+            return Err(Error::InvalidConfig(format!(
+                "`auction_lifetime_slots` must be between 1 and {} slots, the length of an epoch",
+                context.slots_per_epoch
+            )))
+        }
+        let slot_ms = context.seconds_per_slot * 1_000;
+        for (name, timeout_ms) in [
+            ("registration_timeout_ms", self.registration_timeout_ms),
+            ("get_header_timeout_ms", self.get_header_timeout_ms),
+            ("get_payload_timeout_ms", self.get_payload_timeout_ms),
+        ] {
+            if timeout_ms == 0 || timeout_ms > slot_ms {
+                return Err(Error::InvalidConfig(format!(
+                    "`{name}` must be between 1 and {slot_ms} ms, the length of a slot"
+                )))
+            }
+        }
+        Ok(())
+    }
+}
 
 #[derive(Debug, Deserialize)]
 pub struct Config {
-    pub host: Ipv4Addr,
+    #[serde(deserialize_with = "mev_rs::serde::ip_addr::deserialize")]
+    pub host: IpAddr,
     pub port: u16,
     pub relays: Vec<String>,
     pub beacon_node_url: Option<String>,
+    #[serde(default)]
+    pub server: ServerConfig,
+    #[serde(default)]
+    pub relay_client: RelayClientConfig,
+    #[serde(default)]
+    pub registration: RegistrationConfig,
+    /// Auction lifetime and relay timeouts, e.g. for devnets with unusual slot timing
+    #[serde(default)]
+    pub timing: TimingConfig,
+    /// Serve the admin API if provided
+    pub admin: Option<AdminConfig>,
+    /// Serve Prometheus metrics if provided
+    pub metrics: Option<MetricsConfig>,
+    /// File to record delivered payloads in, so they are kept across restarts
+    ///
+    /// The file is rotated to `{path}.1` once it grows past 64 MiB.
+    pub delivered_payloads_path: Option<PathBuf>,
+    /// Check bids against the payload attributes published by the beacon node
+    #[serde(default)]
+    pub verify_payload_attributes: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
-        Self { host: Ipv4Addr::UNSPECIFIED, port: 18550, relays: vec![], beacon_node_url: None }
+        Self {
+            host: Ipv4Addr::UNSPECIFIED.into(),
+            port: 18550,
+            relays: vec![],
+            beacon_node_url: None,
+            server: Default::default(),
+            relay_client: Default::default(),
+            registration: Default::default(),
+            timing: Default::default(),
+            admin: None,
+            metrics: None,
+            delivered_payloads_path: None,
+            verify_payload_attributes: false,
+        }
     }
 }
 
 pub struct Service {
-    host: Ipv4Addr,
+    host: IpAddr,
     port: u16,
     relays: Vec<Relay>,
     network: Network,
@@ -36,7 +170,7 @@ pub struct Service {
 
 impl Service {
     pub fn from(network: Network, config: Config) -> Self {
-        let relays = parse_relay_endpoints(&config.relays).into_iter().map(Relay::from).collect();
+        let relays = create_relays(parse_relay_endpoints(&config.relays), &config.relay_client);
 
         Self { host: config.host, port: config.port, relays, network, config }
     }
@@ -51,14 +185,45 @@ impl Service {
             info!(count, ?relays, "configured with relay(s)");
         }
 
+        if let Some(metrics_config) = config.metrics.as_ref() {
+            install_metrics_exporter(metrics_config);
+        }
+
         let context = Arc::new(Context::try_from(network)?);
-        let relay_mux = RelayMux::new(relays, context.clone());
+        config.timing.validate(&context)?;
+        let ledger = Ledger::new(config.delivered_payloads_path.clone());
+        let relay_mux = RelayMux::new(
+            relays,
+            context.clone(),
+            ledger,
+            config.registration.clone(),
+            config.timing.clone(),
+        );
+
+        if config.verify_payload_attributes {
+            match config.beacon_node_url.as_deref().map(Url::parse) {
+                Some(Ok(url)) => {
+                    let beacon_node = Client::new(url);
+                    std::mem::drop(tokio::spawn(follow_payload_attributes(
+                        beacon_node,
+                        relay_mux.clone(),
+                    )));
+                }
+                Some(Err(err)) => {
+                    warn!(%err, "could not parse beacon node url; not verifying payload attributes")
+                }
+                None => warn!("`verify_payload_attributes` requires a `beacon_node_url`"),
+            }
+        }
 
+        let server_config = config.server.clone();
+        let admin_config = config.admin.clone();
         let relay_mux_clone = relay_mux.clone();
         let relay_task = tokio::spawn(async move {
             let relay_mux = relay_mux_clone;
             let genesis_time =
                 get_genesis_time(&context, config.beacon_node_url.as_ref(), None).await;
+            relay_mux.set_genesis_time(genesis_time);
             let clock = context.clock_at(genesis_time);
             let mut slots = clock.clone().into_stream();
 
@@ -68,9 +233,31 @@ impl Service {
             }
         });
 
-        let server = BlindedBlockProviderServer::new(host, port, relay_mux).spawn();
+        if let Some(admin_config) = admin_config {
+            std::mem::drop(admin::spawn(&admin_config, relay_mux.clone())?);
+        }
+
+        let reloader = RelayReloader { relay_mux: relay_mux.clone() };
+        let routes = api::router(relay_mux.clone());
+        let server = BlindedBlockProviderServer::new(host, port, server_config, relay_mux)
+            .with_routes(routes)
+            .spawn();
+
+        Ok(ServiceHandle { relay_mux: relay_task, server, reloader })
+    }
+}
 
-        Ok(ServiceHandle { relay_mux: relay_task, server })
+/// Updates the relays of a running [`Service`] without interrupting open auctions
+#[derive(Clone)]
+pub struct RelayReloader {
+    relay_mux: RelayMux,
+}
+
+impl RelayReloader {
+    /// Replaces the relays in use with those in `config`
+    pub fn reload(&self, config: &Config) {
+        let relays = create_relays(parse_relay_endpoints(&config.relays), &config.relay_client);
+        self.relay_mux.update_relays(relays);
     }
 }
 
@@ -80,6 +267,13 @@ pub struct ServiceHandle {
     relay_mux: JoinHandle<()>,
     #[pin]
     server: JoinHandle<()>,
+    reloader: RelayReloader,
+}
+
+impl ServiceHandle {
+    pub fn relay_reloader(&self) -> RelayReloader {
+        self.reloader.clone()
+    }
 }
 
 impl Future for ServiceHandle {
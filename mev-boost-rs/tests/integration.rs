@@ -90,10 +90,10 @@ async fn test_end_to_end() {
     let secret_key = SecretKey::try_from(key_bytes).unwrap();
     let relay_public_key = secret_key.public_key();
 
-    let host = Ipv4Addr::LOCALHOST;
+    let host = Ipv4Addr::LOCALHOST.into();
     let port = 28545;
     let builder = IdentityBuilder::new(context.clone());
-    let relay = RelayServer::new(host, port, builder);
+    let relay = RelayServer::new(host, port, Default::default(), builder);
     std::mem::drop(relay.spawn());
 
     // start mux server
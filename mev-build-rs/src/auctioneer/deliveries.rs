@@ -0,0 +1,207 @@
+use crate::{
+    auctioneer::auction_schedule::RelayIndex,
+    bidder::{ClearingHistory, WinRates},
+    ledger::{BidRecord, Ledger},
+    service::ClockMessage,
+};
+use ethereum_consensus::primitives::Slot;
+use futures_util::future::join_all;
+use metrics::counter;
+use mev_rs::{blinded_block_relayer::DeliveredPayloadFilter, Error, Relay};
+use reth::primitives::revm_primitives::{B256, U256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    mpsc::{self, UnboundedReceiver, UnboundedSender},
+};
+use tracing::{info, trace, warn};
+
+// Number of slots to wait before asking relays which payload they delivered for a slot
+const DELIVERY_CHECK_DELAY_SLOTS: Slot = 2;
+
+/// A bid accepted by the relay at `relay_index`
+#[derive(Debug)]
+pub struct SubmittedBid {
+    pub relay_index: RelayIndex,
+    pub bid: BidRecord,
+}
+
+// Bids made to a relay for a slot
+#[derive(Debug, Default)]
+struct PendingBids {
+    block_hashes: HashSet<B256>,
+    best_revenue: U256,
+}
+
+// Result of asking a relay which payload it delivered for a slot
+struct DeliveryCheck {
+    slot: Slot,
+    relay_index: RelayIndex,
+    bids: PendingBids,
+    deliveries: Result<Vec<Delivery>, Error>,
+}
+
+// Payload a relay delivered for a slot
+struct Delivery {
+    block_hash: B256,
+    value: U256,
+}
+
+async fn fetch_deliveries(relay: &Relay, slot: Slot) -> Result<Vec<Delivery>, Error> {
+    let filters = DeliveredPayloadFilter { slot: Some(slot), ..Default::default() };
+    let payloads = relay.get_delivered_payloads(&filters).await?;
+    Ok(payloads
+        .iter()
+        .map(|payload| Delivery {
+            block_hash: B256::from_slice(payload.block_hash.as_ref()),
+            value: payload.value,
+        })
+        .collect())
+}
+
+/// Checks the payloads each relay delivered against the bids the builder made to it, tracking
+/// the builder's win rate at each relay and the value needed to win, and recording wins in the
+/// ledger, if any
+pub struct DeliveryTracker {
+    // NOTE: in the same order as the auctioneer's relays, so `RelayIndex` is shared
+    relays: Vec<Relay>,
+    ledger: Option<Ledger>,
+    win_rates: WinRates,
+    clearing_history: ClearingHistory,
+    bids: UnboundedReceiver<SubmittedBid>,
+    clock: broadcast::Receiver<ClockMessage>,
+    // Bids made to each relay by slot, awaiting a check for the delivered payload
+    pending: BTreeMap<Slot, HashMap<RelayIndex, PendingBids>>,
+    // NOTE: checks run in their own task so slow relays do not hold up receiving bids
+    checks_tx: UnboundedSender<Vec<DeliveryCheck>>,
+    checks: UnboundedReceiver<Vec<DeliveryCheck>>,
+}
+
+impl DeliveryTracker {
+    pub fn new(
+        relays: Vec<Relay>,
+        ledger: Option<Ledger>,
+        win_rates: WinRates,
+        clearing_history: ClearingHistory,
+        bids: UnboundedReceiver<SubmittedBid>,
+        clock: broadcast::Receiver<ClockMessage>,
+    ) -> Self {
+        let (checks_tx, checks) = mpsc::unbounded_channel();
+        Self {
+            relays,
+            ledger,
+            win_rates,
+            clearing_history,
+            bids,
+            clock,
+            pending: Default::default(),
+            checks_tx,
+            checks,
+        }
+    }
+
+    fn on_bid(&mut self, SubmittedBid { relay_index, bid }: SubmittedBid) {
+        let pending = self.pending.entry(bid.slot).or_default().entry(relay_index).or_default();
+        pending.block_hashes.insert(bid.block_hash);
+        pending.best_revenue = pending.best_revenue.max(bid.revenue);
+        if let Some(ledger) = self.ledger.as_ref() {
+            ledger.record_bid(bid);
+        }
+    }
+
+    fn record_outcome(&self, slot: Slot, relay: &Relay, delivered: Option<B256>) {
+        let labels = [("relay", relay.to_string())];
+        counter!("mev_builder_relay_slots_bid_total", &labels).increment(1);
+        if let Some(block_hash) = delivered {
+            counter!("mev_builder_relay_slots_won_total", &labels).increment(1);
+            if let Some(ledger) = self.ledger.as_ref() {
+                ledger.record_win(slot, relay.to_string(), block_hash);
+            }
+        }
+
+        let wins = self.win_rates.record(&relay.public_key, delivered.is_some());
+        info!(
+            slot,
+            %relay,
+            won = delivered.is_some(),
+            slots_won = wins.slots_won,
+            slots_bid = wins.slots_bid,
+            "checked payload delivered by relay"
+        );
+    }
+
+    // Records the value of the payload delivered for `slot` relative to the builder's revenue
+    fn record_clearing_ratio(&self, slot: Slot, delivered_value: U256, best_revenue: U256) {
+        if best_revenue.is_zero() {
+            return
+        }
+        let ratio = f64::from(delivered_value) / f64::from(best_revenue);
+        trace!(slot, ratio, "recorded clearing ratio");
+        self.clearing_history.record(ratio);
+    }
+
+    fn on_slot(&mut self, slot: Slot) {
+        let unsettled = self.pending.split_off(&slot.saturating_sub(DELIVERY_CHECK_DELAY_SLOTS));
+        let settled = std::mem::replace(&mut self.pending, unsettled);
+        for (slot, submissions) in settled {
+            let checks = submissions
+                .into_iter()
+                .filter_map(|(relay_index, bids)| {
+                    let relay = self.relays.get(relay_index)?.clone();
+                    Some(async move {
+                        let deliveries = fetch_deliveries(&relay, slot).await;
+                        DeliveryCheck { slot, relay_index, bids, deliveries }
+                    })
+                })
+                .collect::<Vec<_>>();
+            let checks_tx = self.checks_tx.clone();
+            tokio::spawn(async move {
+                // NOTE: the tracker owns the receiver so it only fails once the tracker has stopped
+                let _ = checks_tx.send(join_all(checks).await);
+            });
+        }
+    }
+
+    fn on_checks(&self, checks: Vec<DeliveryCheck>) {
+        let Some(slot) = checks.first().map(|check| check.slot) else { return };
+
+        let mut delivered_value = None;
+        let mut best_revenue = U256::ZERO;
+        for DeliveryCheck { relay_index, bids, deliveries, .. } in checks {
+            let relay = &self.relays[relay_index];
+            match deliveries {
+                Ok(deliveries) => {
+                    let won = deliveries
+                        .iter()
+                        .find(|delivery| bids.block_hashes.contains(&delivery.block_hash));
+                    self.record_outcome(slot, relay, won.map(|delivery| delivery.block_hash));
+                    if let Some(value) = deliveries.iter().map(|delivery| delivery.value).max() {
+                        delivered_value = delivered_value.max(Some(value));
+                        best_revenue = best_revenue.max(bids.best_revenue);
+                    }
+                }
+                // NOTE: the outcome is unknown so the slot does not count towards the win rate
+                Err(err) => {
+                    warn!(%err, slot, %relay, "could not fetch delivered payloads from relay")
+                }
+            }
+        }
+        if let Some(delivered_value) = delivered_value {
+            self.record_clearing_ratio(slot, delivered_value, best_revenue);
+        }
+    }
+
+    pub async fn spawn(mut self) {
+        loop {
+            tokio::select! {
+                Some(bid) = self.bids.recv() => self.on_bid(bid),
+                Some(checks) = self.checks.recv() => self.on_checks(checks),
+                message = self.clock.recv() => match message {
+                    Ok(ClockMessage::NewSlot(slot)) => self.on_slot(slot),
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+    }
+}
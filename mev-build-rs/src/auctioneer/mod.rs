@@ -1,4 +1,8 @@
 mod auction_schedule;
+mod deliveries;
 mod service;
+mod sync;
 
-pub use service::{AuctionContext, Config, Service};
+pub use deliveries::DeliveryTracker;
+pub use service::{AuctionContext, Config, Service, DEFAULT_MAX_HEAD_LAG_SLOTS};
+pub use sync::{SyncGate, SyncWatcher};
@@ -1,9 +1,20 @@
 use crate::{
-    auctioneer::auction_schedule::{AuctionSchedule, Proposals, Proposer, RelayIndex, RelaySet},
+    auctioneer::{
+        auction_schedule::{AuctionSchedule, Proposals, Proposer, RelayIndex, RelaySet},
+        deliveries::SubmittedBid,
+        sync::SyncGate,
+    },
     bidder::Service as Bidder,
-    compat::{to_blobs_bundle, to_bytes20, to_bytes32, to_execution_payload},
-    payload::attributes::{BuilderPayloadBuilderAttributes, ProposalAttributes},
+    compat::{to_bytes20, to_bytes32, to_execution_payload},
+    exex::ChainUpdate,
+    ledger::BidRecord,
+    payload::{
+        attributes::{BuilderPayloadBuilderAttributes, ProposalAttributes},
+        builder::FinalizedPayload,
+    },
+    rpc::{AuctioneerQuery, OpenAuction, RelayStatus, Submission},
     service::ClockMessage,
+    submission::{BridgeConfig, RelayTarget, SubmissionBridge},
     Error,
 };
 use ethereum_consensus::{
@@ -14,23 +25,28 @@ use ethereum_consensus::{
     Fork,
 };
 use mev_rs::{
-    relay::parse_relay_endpoints,
+    blinded_block_relayer::BlockSubmissionFilter,
+    config::RelayClientConfig,
+    relay::{create_relays, parse_relay_endpoints},
     signing::sign_builder_message,
-    types::{block_submission, BidTrace, SignedBidSubmission},
+    types::{block_submission, BidTrace, BlobsBundle, SignedBidSubmission},
     BlindedBlockRelayer, Relay,
 };
 use reth::{
     api::{EngineTypes, PayloadBuilderAttributes},
     payload::{EthBuiltPayload, Events, PayloadBuilder, PayloadBuilderHandle, PayloadId},
+    primitives::revm_primitives::{B256, U256},
 };
 use serde::Deserialize;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
+    path::PathBuf,
     sync::Arc,
+    time::{Duration, Instant},
 };
 use tokio::sync::{
     broadcast,
-    mpsc::{self, Receiver},
+    mpsc::{self, Receiver, UnboundedSender},
 };
 use tokio_stream::StreamExt;
 use tracing::{debug, error, info, trace, warn};
@@ -41,8 +57,19 @@ const PROPOSAL_SCHEDULE_INTERVAL: u64 = 2;
 
 const DEFAULT_BUILDER_BIDDER_CHANNEL_SIZE: usize = 16;
 
+// Minimum time between polls of a relay's data API for the top bid from other builders
+const TOP_BID_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// NOTE: the head trails the clock whenever proposers miss their slots, which happens several
+// slots in a row on mainnet, so only a larger lag indicates the node is syncing
+pub const DEFAULT_MAX_HEAD_LAG_SLOTS: Slot = 8;
+
+// Number of recent submissions retained for inspection over RPC
+const RECENT_SUBMISSIONS_LIMIT: usize = 64;
+
 fn prepare_submission(
     payload: &EthBuiltPayload,
+    blobs_bundle: BlobsBundle,
     signing_key: &SecretKey,
     public_key: &BlsPublicKey,
     auction_context: &AuctionContext,
@@ -80,7 +107,7 @@ fn prepare_submission(
         Fork::Deneb => SignedBidSubmission::Deneb(block_submission::deneb::SignedBidSubmission {
             message,
             execution_payload,
-            blobs_bundle: to_blobs_bundle(payload.sidecars())?,
+            blobs_bundle,
             signature,
         }),
         fork => return Err(Error::UnsupportedFork(fork)),
@@ -94,17 +121,32 @@ pub struct AuctionContext {
     pub attributes: BuilderPayloadBuilderAttributes,
     pub proposer: Proposer,
     pub relays: RelaySet,
+    // Index of the bidding overrides shared by `relays`, if any
+    pub bid_overrides: Option<usize>,
 }
 
 #[derive(Deserialize, Debug, Default, Clone)]
 pub struct Config {
-    /// Secret key used to sign builder messages to relay
+    /// Secret key used to sign builder messages to relay, given inline, as `file:/path` or as
+    /// `env:VAR`
+    #[serde(deserialize_with = "mev_rs::serde::secret::deserialize")]
     pub secret_key: SecretKey,
     #[serde(skip)]
     /// Public key corresponding to secret key
     pub public_key: BlsPublicKey,
     /// List of relays to submit bids
     pub relays: Vec<String>,
+    /// Settings for the HTTP client used to submit to relays
+    #[serde(default)]
+    pub relay_client: RelayClientConfig,
+    /// File to record submitted bids and their outcomes in, to account for the builder's profit
+    pub ledger_path: Option<PathBuf>,
+    /// Number of slots the node's head may trail the current slot by before the builder stops
+    /// building and bidding, if missing defaults to `DEFAULT_MAX_HEAD_LAG_SLOTS`
+    pub max_head_lag_slots: Option<Slot>,
+    /// Standalone submission service to hand submissions to instead of submitting to relays
+    /// directly
+    pub submission_bridge: Option<BridgeConfig>,
 }
 
 pub struct Service<
@@ -121,11 +163,26 @@ pub struct Service<
     // TODO consolidate this somewhere...
     genesis_time: u64,
     bidder: Bidder,
-    bids: Receiver<EthBuiltPayload>,
+    bids: Receiver<FinalizedPayload>,
+    queries: Receiver<AuctioneerQuery>,
+    chain_updates: Receiver<ChainUpdate>,
+    deliveries: UnboundedSender<SubmittedBid>,
+    sync: SyncGate,
+    bridge: Option<SubmissionBridge>,
 
     auction_schedule: AuctionSchedule,
     open_auctions: HashMap<PayloadId, Arc<AuctionContext>>,
+    // Hash of the last block submitted for each open auction
+    last_submitted_block_hashes: HashMap<PayloadId, B256>,
     processed_payload_attributes: HashMap<Slot, HashSet<PayloadId>>,
+    recent_submissions: VecDeque<Submission>,
+    // NOTE: indexed by `RelayIndex`
+    relay_statuses: Vec<RelayStatus>,
+    // NOTE: indexed by `RelayIndex`
+    relay_bid_overrides: Vec<Option<usize>>,
+    // Time the top bid was last polled from each relay with cancellations
+    // NOTE: indexed by `RelayIndex`
+    last_top_bid_polls: Vec<Option<Instant>>,
 }
 
 impl<
@@ -135,19 +192,37 @@ impl<
             > + 'static,
     > Service<Engine>
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         clock: broadcast::Receiver<ClockMessage>,
         builder: PayloadBuilderHandle<Engine>,
         bidder: Bidder,
-        bids: Receiver<EthBuiltPayload>,
+        bids: Receiver<FinalizedPayload>,
+        queries: Receiver<AuctioneerQuery>,
+        chain_updates: Receiver<ChainUpdate>,
+        deliveries: UnboundedSender<SubmittedBid>,
         mut config: Config,
         context: Arc<Context>,
         genesis_time: u64,
     ) -> Self {
-        let relays =
-            parse_relay_endpoints(&config.relays).into_iter().map(Relay::from).collect::<Vec<_>>();
+        let relays = create_relays(parse_relay_endpoints(&config.relays), &config.relay_client)
+            .into_iter()
+            .map(|relay| {
+                let cancellations = bidder.cancellations_enabled(&relay.public_key);
+                relay.with_cancellations(cancellations)
+            })
+            .collect::<Vec<_>>();
 
         config.public_key = config.secret_key.public_key();
+        let bridge = config.submission_bridge.clone().map(SubmissionBridge::new);
+
+        let relay_statuses = relays
+            .iter()
+            .map(|relay| RelayStatus { relay: relay.to_string(), ..Default::default() })
+            .collect();
+        let last_top_bid_polls = vec![None; relays.len()];
+        let relay_bid_overrides =
+            relays.iter().map(|relay| bidder.overrides_for(&relay.public_key)).collect();
 
         Self {
             clock,
@@ -158,12 +233,30 @@ impl<
             genesis_time,
             bidder,
             bids,
+            queries,
+            chain_updates,
+            deliveries,
+            sync: Default::default(),
+            bridge,
             auction_schedule: Default::default(),
             open_auctions: Default::default(),
+            last_submitted_block_hashes: Default::default(),
             processed_payload_attributes: Default::default(),
+            recent_submissions: Default::default(),
+            relay_statuses,
+            relay_bid_overrides,
+            last_top_bid_polls,
         }
     }
 
+    pub fn relays(&self) -> &[Relay] {
+        &self.relays
+    }
+
+    pub fn sync_gate(&self) -> SyncGate {
+        self.sync.clone()
+    }
+
     async fn fetch_proposer_schedules(&mut self) {
         // TODO: consider moving to new task on another thread, can do parallel fetch (join set)
         // and not block others at this interval
@@ -197,7 +290,10 @@ impl<
         let retain_slot = epoch * self.context.slots_per_epoch;
         self.auction_schedule.clear(retain_slot);
         self.open_auctions.retain(|_, auction| auction.slot >= retain_slot);
+        self.last_submitted_block_hashes
+            .retain(|payload_id, _| self.open_auctions.contains_key(payload_id));
         self.processed_payload_attributes.retain(|&slot, _| slot >= retain_slot);
+        self.bidder.top_bids().retain_from(retain_slot);
     }
 
     fn get_proposals(&self, slot: Slot) -> Option<Proposals> {
@@ -216,20 +312,23 @@ impl<
         slot: Slot,
         proposer: Proposer,
         relays: HashSet<RelayIndex>,
+        bid_overrides: Option<usize>,
         mut attributes: BuilderPayloadBuilderAttributes,
     ) -> Option<PayloadId> {
         let (bidder, revenue_updates) = mpsc::channel(DEFAULT_BUILDER_BIDDER_CHANNEL_SIZE);
         let proposal = ProposalAttributes {
             proposer_gas_limit: proposer.gas_limit,
             proposer_fee_recipient: proposer.fee_recipient,
+            bid_overrides,
             bidder,
+            final_bid_deadline: self.bidder.final_bid_deadline(),
         };
         attributes.attach_proposal(proposal);
 
         // TODO: can likely skip full attributes in `AuctionContext`
         // TODO: consider data layout here...
         // TODO: can likely refactor around auction schedule to skip some clones...
-        let auction = AuctionContext { slot, attributes, proposer, relays };
+        let auction = AuctionContext { slot, attributes, proposer, relays, bid_overrides };
 
         // TODO: work out cancellation discipline
         let auction = self.store_auction(auction);
@@ -244,6 +343,15 @@ impl<
         Some(payload_id)
     }
 
+    fn group_by_bid_overrides(&self, relays: RelaySet) -> HashMap<Option<usize>, RelaySet> {
+        let mut groups = HashMap::<_, RelaySet>::new();
+        for relay_index in relays {
+            let bid_overrides = self.relay_bid_overrides.get(relay_index).copied().flatten();
+            groups.entry(bid_overrides).or_default().insert(relay_index);
+        }
+        groups
+    }
+
     // Record `payload_id` as processed so that we can identify duplicate notifications.
     // Return value indicates if the `payload_id` has been observed before or not.
     fn observe_payload_id(&mut self, slot: Slot, payload_id: PayloadId) -> bool {
@@ -259,6 +367,11 @@ impl<
         )
         .expect("is past genesis");
 
+        if !self.sync.is_synced() {
+            debug!(slot, "node is not in sync; ignoring payload attributes");
+            return
+        }
+
         let is_new = self.observe_payload_id(slot, attributes.payload_id());
 
         if !is_new {
@@ -268,40 +381,91 @@ impl<
 
         if let Some(proposals) = self.get_proposals(slot) {
             for (proposer, relays) in proposals {
-                if let Some(payload_id) =
-                    self.open_auction(slot, proposer, relays, attributes.clone()).await
-                {
-                    self.observe_payload_id(slot, payload_id);
+                // NOTE: bids carry the payment to the proposer so relays with different bidding
+                // settings each get their own build
+                for (bid_overrides, relays) in self.group_by_bid_overrides(relays) {
+                    if let Some(payload_id) = self
+                        .open_auction(
+                            slot,
+                            proposer.clone(),
+                            relays,
+                            bid_overrides,
+                            attributes.clone(),
+                        )
+                        .await
+                    {
+                        self.observe_payload_id(slot, payload_id);
+                    }
                 }
             }
         }
     }
 
-    async fn submit_payload(&self, payload: EthBuiltPayload) {
-        let auction = self.open_auctions.get(&payload.id()).expect("has auction");
+    // Abort any auctions building on a parent block which can no longer become canonical
+    async fn on_chain_update(&mut self, update: ChainUpdate) {
+        let stale_auctions = self
+            .open_auctions
+            .iter()
+            .filter(|(_, auction)| update.is_stale(&auction.attributes.inner.parent))
+            .map(|(&payload_id, _)| payload_id)
+            .collect::<Vec<_>>();
+        for payload_id in stale_auctions {
+            self.last_submitted_block_hashes.remove(&payload_id);
+            if let Some(auction) = self.open_auctions.remove(&payload_id) {
+                info!(
+                    slot = auction.slot,
+                    %payload_id,
+                    parent_hash = %auction.attributes.inner.parent,
+                    "aborting auction with stale parent"
+                );
+            }
+            // NOTE: resolving the payload terminates the build job
+            let _ = self.builder.resolve(payload_id).await;
+        }
+    }
+
+    async fn submit_payload(&mut self, finalized: FinalizedPayload) {
+        let FinalizedPayload { payload, blobs_bundle, revenue, payment_gas_cost } = finalized;
+        let blob_count = blobs_bundle.blobs.len();
+        let Some(auction) = self.open_auctions.get(&payload.id()).cloned() else {
+            // NOTE: the auction may have been aborted while this payload was in flight
+            trace!(payload_id = %payload.id(), "ignoring payload for auction that is not open");
+            return
+        };
+        if !self.sync.is_synced() {
+            debug!(slot = auction.slot, "node is not in sync; skipping submission");
+            return
+        }
+        let block_hash = payload.block().hash();
+        if self.last_submitted_block_hashes.get(&payload.id()) == Some(&block_hash) {
+            trace!(slot = auction.slot, %block_hash, "skipping duplicate payload");
+            return
+        }
         let mut successful_relays_for_submission = Vec::with_capacity(auction.relays.len());
+        let mut relays_with_cancellations = vec![];
         match prepare_submission(
             &payload,
+            blobs_bundle,
             &self.config.secret_key,
             &self.config.public_key,
-            auction,
+            &auction,
             &self.context,
         ) {
             Ok(signed_submission) => {
-                // TODO: parallel dispatch
-                for &relay_index in &auction.relays {
-                    match self.relays.get(relay_index) {
-                        Some(relay) => {
-                            if let Err(err) = relay.submit_bid(&signed_submission).await {
-                                warn!(%err, ?relay, slot = auction.slot, "could not submit payload");
-                            } else {
-                                successful_relays_for_submission.push(relay_index);
-                            }
-                        }
-                        None => {
-                            // NOTE: this arm signals a violation of an internal invariant
-                            // Please fix if you see this error
-                            error!(relay_index, "could not dispatch to unknown relay");
+                let outcomes = self.dispatch_submission(&auction.relays, &signed_submission).await;
+                for (relay_index, outcome) in outcomes {
+                    let relay = &self.relays[relay_index];
+                    let status = &mut self.relay_statuses[relay_index];
+                    status.last_submitted_slot = Some(auction.slot);
+                    if let Err(err) = outcome {
+                        warn!(%err, ?relay, slot = auction.slot, "could not submit payload");
+                        status.failed_submissions += 1;
+                        status.last_error = Some(err);
+                    } else {
+                        status.successful_submissions += 1;
+                        successful_relays_for_submission.push(relay_index);
+                        if relay.cancellations() {
+                            relays_with_cancellations.push(relay_index);
                         }
                     }
                 }
@@ -310,7 +474,18 @@ impl<
                 warn!(%err, slot = auction.slot, "could not prepare submission")
             }
         }
+        for relay_index in relays_with_cancellations {
+            self.observe_top_bid(&auction, relay_index);
+        }
         if !successful_relays_for_submission.is_empty() {
+            self.last_submitted_block_hashes.insert(payload.id(), block_hash);
+            self.record_bids(
+                &auction,
+                &payload,
+                revenue,
+                payment_gas_cost,
+                &successful_relays_for_submission,
+            );
             let relay_set = successful_relays_for_submission
                 .into_iter()
                 .map(|index| format!("{0}", self.relays[index]))
@@ -321,11 +496,198 @@ impl<
                 block_hash = %payload.block().hash(),
                 parent_hash = %payload.block().header.header().parent_hash,
                 txn_count = %payload.block().body.transactions.len(),
-                blob_count = %payload.sidecars().iter().map(|s| s.blobs.len()).sum::<usize>(),
+                blob_count,
                 value = %payload.fees(),
                 relays=?relay_set,
                 "payload submitted"
             );
+            self.record_submission(&auction, &payload, relay_set);
+        }
+    }
+
+    // Submits to each of `relays`, either directly or through the submission bridge, returning
+    // the outcome at each relay
+    async fn dispatch_submission(
+        &mut self,
+        relays: &RelaySet,
+        signed_submission: &SignedBidSubmission,
+    ) -> Vec<(RelayIndex, Result<(), String>)> {
+        let relays = relays
+            .iter()
+            .filter_map(|&relay_index| match self.relays.get(relay_index) {
+                Some(relay) => Some((relay_index, relay)),
+                None => {
+                    // NOTE: this arm signals a violation of an internal invariant
+                    // Please fix if you see this error
+                    error!(relay_index, "could not dispatch to unknown relay");
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let Some(bridge) = self.bridge.as_mut() else {
+            // TODO: parallel dispatch
+            let mut outcomes = Vec::with_capacity(relays.len());
+            for (relay_index, relay) in relays {
+                let outcome =
+                    relay.submit_bid(signed_submission).await.map_err(|err| err.to_string());
+                outcomes.push((relay_index, outcome));
+            }
+            return outcomes
+        };
+
+        let targets = relays
+            .iter()
+            .map(|(_, relay)| RelayTarget {
+                public_key: relay.public_key.clone(),
+                cancellations: relay.cancellations(),
+            })
+            .collect();
+        match bridge.submit(targets, signed_submission).await {
+            Ok(outcomes) => {
+                relays.into_iter().map(|(relay_index, _)| relay_index).zip(outcomes).collect()
+            }
+            Err(err) => {
+                let err = format!("could not hand submission to submission service: {err}");
+                relays.into_iter().map(|(relay_index, _)| (relay_index, Err(err.clone()))).collect()
+            }
+        }
+    }
+
+    // Polls the data API of the relay for the top bid from other builders, to inform bidding at
+    // relays with cancellations
+    // NOTE: the proposer's `getHeader` is avoided as relays treat it as a proposer's request
+    fn observe_top_bid(&mut self, auction: &AuctionContext, relay_index: RelayIndex) {
+        let Some(last_poll) = self.last_top_bid_polls.get_mut(relay_index) else { return };
+        if last_poll.is_some_and(|last_poll| last_poll.elapsed() < TOP_BID_POLL_INTERVAL) {
+            return
+        }
+        *last_poll = Some(Instant::now());
+
+        let relay = self.relays[relay_index].clone();
+        let top_bids = self.bidder.top_bids();
+        let builder_public_key = self.config.public_key.clone();
+        let slot = auction.slot;
+        let parent_hash = to_bytes32(auction.attributes.parent());
+        let proposer_public_key = auction.proposer.public_key.clone();
+        tokio::spawn(async move {
+            let filters = BlockSubmissionFilter { slot: Some(slot), ..Default::default() };
+            match relay.get_block_submissions(&filters).await {
+                Ok(submissions) => {
+                    let top_bid = submissions
+                        .iter()
+                        .filter(|submission| {
+                            submission.builder_public_key != builder_public_key &&
+                                submission.parent_hash == parent_hash &&
+                                submission.proposer_public_key == proposer_public_key
+                        })
+                        .map(|submission| submission.value)
+                        .max();
+                    if let Some(value) = top_bid {
+                        top_bids.record(slot, parent_hash, value);
+                    }
+                }
+                Err(err) => trace!(%err, slot, %relay, "could not fetch top bid"),
+            }
+        });
+    }
+
+    fn record_bids(
+        &self,
+        auction: &AuctionContext,
+        payload: &EthBuiltPayload,
+        revenue: U256,
+        payment_gas_cost: U256,
+        relays: &[RelayIndex],
+    ) {
+        let block_hash = payload.block().hash();
+        let value = payload.fees();
+        for &relay_index in relays {
+            let bid = BidRecord {
+                slot: auction.slot,
+                relay: self.relays[relay_index].to_string(),
+                block_hash,
+                proposer_public_key: auction.proposer.public_key.clone(),
+                value,
+                revenue,
+                subsidy: value.saturating_sub(revenue),
+                payment_gas_cost,
+                won: false,
+            };
+            if let Err(err) = self.deliveries.send(SubmittedBid { relay_index, bid }) {
+                warn!(%err, slot = auction.slot, "could not send bid to delivery tracker");
+            }
+        }
+    }
+
+    fn record_submission(
+        &mut self,
+        auction: &AuctionContext,
+        payload: &EthBuiltPayload,
+        relays: Vec<String>,
+    ) {
+        if self.recent_submissions.len() == RECENT_SUBMISSIONS_LIMIT {
+            self.recent_submissions.pop_back();
+        }
+        let block = payload.block();
+        self.recent_submissions.push_front(Submission {
+            slot: auction.slot,
+            block_number: block.number,
+            block_hash: block.hash(),
+            parent_hash: block.header.header().parent_hash,
+            value: payload.fees(),
+            relays,
+        });
+    }
+
+    fn open_auctions_summary(&self) -> Vec<OpenAuction> {
+        let mut auctions = self
+            .open_auctions
+            .iter()
+            .map(|(&payload_id, auction)| OpenAuction {
+                slot: auction.slot,
+                payload_id,
+                parent_hash: auction.attributes.inner.parent,
+                proposer_public_key: auction.proposer.public_key.clone(),
+                proposer_fee_recipient: auction.proposer.fee_recipient,
+                gas_limit: auction.proposer.gas_limit,
+                relays: auction
+                    .relays
+                    .iter()
+                    .filter_map(|&index| self.relays.get(index).map(|relay| relay.to_string()))
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        auctions.sort_by_key(|auction| auction.slot);
+        auctions
+    }
+
+    fn process_query(&self, query: AuctioneerQuery) {
+        // NOTE: sending only fails if the caller has gone away, which is fine to ignore
+        match query {
+            AuctioneerQuery::OpenAuctions(reply) => {
+                let _ = reply.send(self.open_auctions_summary());
+            }
+            AuctioneerQuery::LastSubmissions(reply) => {
+                let _ = reply.send(self.recent_submissions.iter().cloned().collect());
+            }
+            AuctioneerQuery::RelayStatus(reply) => {
+                let win_rates = self.bidder.win_rates();
+                let statuses = self
+                    .relays
+                    .iter()
+                    .zip(&self.relay_statuses)
+                    .map(|(relay, status)| {
+                        let wins = win_rates.get(&relay.public_key);
+                        RelayStatus {
+                            slots_bid: wins.slots_bid,
+                            slots_won: wins.slots_won,
+                            ..status.clone()
+                        }
+                    })
+                    .collect();
+                let _ = reply.send(statuses);
+            }
         }
     }
 
@@ -365,6 +727,8 @@ impl<
                     Err(err) => warn!(%err, "error getting payload event"),
                 },
                 Some(payload) = self.bids.recv() => self.submit_payload(payload).await,
+                Some(query) = self.queries.recv() => self.process_query(query),
+                Some(update) = self.chain_updates.recv() => self.on_chain_update(update).await,
             }
         }
     }
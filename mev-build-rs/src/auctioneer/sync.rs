@@ -0,0 +1,104 @@
+use crate::service::ClockMessage;
+use ethereum_consensus::{clock::convert_timestamp_to_slot, primitives::Slot};
+use reth::{
+    providers::{BlockReaderIdExt, StageCheckpointReader},
+    stages::StageId,
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{info, warn};
+
+/// Shared switch indicating if the node is in sync, so the builder can build and bid
+///
+/// NOTE: starts out of sync until the node is first checked
+#[derive(Debug, Clone, Default)]
+pub struct SyncGate(Arc<AtomicBool>);
+
+impl SyncGate {
+    pub fn is_synced(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    // Returns the previous state of the gate
+    fn set(&self, synced: bool) -> bool {
+        self.0.swap(synced, Ordering::Relaxed)
+    }
+}
+
+/// Checks each slot that the node has executed the headers it has downloaded and that its head
+/// is recent according to the beacon clock
+pub struct SyncWatcher<Provider> {
+    provider: Provider,
+    genesis_time: u64,
+    seconds_per_slot: u64,
+    // Number of slots the head may trail the current slot by while the node is considered in sync
+    max_head_lag_slots: Slot,
+    sync: SyncGate,
+    clock: broadcast::Receiver<ClockMessage>,
+}
+
+impl<Provider: BlockReaderIdExt + StageCheckpointReader> SyncWatcher<Provider> {
+    pub fn new(
+        provider: Provider,
+        genesis_time: u64,
+        seconds_per_slot: u64,
+        max_head_lag_slots: Slot,
+        sync: SyncGate,
+        clock: broadcast::Receiver<ClockMessage>,
+    ) -> Self {
+        Self { provider, genesis_time, seconds_per_slot, max_head_lag_slots, sync, clock }
+    }
+
+    // Returns the reason the node is not in sync, if any
+    fn check_sync(&self, slot: Slot) -> Result<Option<String>, reth::providers::ProviderError> {
+        let checkpoint = |id| {
+            self.provider
+                .get_stage_checkpoint(id)
+                .map(|checkpoint| checkpoint.map(|checkpoint| checkpoint.block_number))
+        };
+        let headers = checkpoint(StageId::Headers)?.unwrap_or_default();
+        let executed = checkpoint(StageId::Finish)?.unwrap_or_default();
+        if executed < headers {
+            return Ok(Some(format!("executed up to block {executed} of {headers}")))
+        }
+
+        let Some(head) = self.provider.latest_header()? else {
+            return Ok(Some("missing head block".to_string()))
+        };
+        let head_slot =
+            convert_timestamp_to_slot(head.timestamp, self.genesis_time, self.seconds_per_slot)
+                .unwrap_or_default();
+        let lag = slot.saturating_sub(head_slot);
+        if lag > self.max_head_lag_slots {
+            return Ok(Some(format!("head is {lag} slots behind the clock")))
+        }
+
+        Ok(None)
+    }
+
+    fn on_slot(&self, slot: Slot) {
+        let reason = match self.check_sync(slot) {
+            Ok(reason) => reason,
+            Err(err) => Some(format!("could not read sync status: {err}")),
+        };
+        let was_synced = self.sync.set(reason.is_none());
+        if let Some(reason) = reason {
+            warn!(slot, %reason, "node is not in sync; skipping building and bidding");
+        } else if !was_synced {
+            info!(slot, "node is in sync; building and bidding");
+        }
+    }
+
+    pub async fn spawn(mut self) {
+        loop {
+            match self.clock.recv().await {
+                Ok(ClockMessage::NewSlot(slot)) => self.on_slot(slot),
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+}
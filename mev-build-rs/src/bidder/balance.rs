@@ -0,0 +1,113 @@
+use crate::service::ClockMessage;
+use reth::{
+    primitives::revm_primitives::{Address, U256},
+    providers::StateProviderFactory,
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{info, warn};
+
+/// Shared switch to turn subsidized bidding on or off
+#[derive(Debug, Clone)]
+pub struct SubsidySwitch(Arc<AtomicBool>);
+
+impl Default for SubsidySwitch {
+    fn default() -> Self {
+        Self(Arc::new(AtomicBool::new(true)))
+    }
+}
+
+impl SubsidySwitch {
+    pub fn is_enabled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    // Returns the previous state of the switch
+    fn set(&self, enabled: bool) -> bool {
+        self.0.swap(enabled, Ordering::Relaxed)
+    }
+}
+
+/// Monitors the balances of the wallets authoring payment transactions each slot, disabling
+/// subsidies while any of them cannot cover them
+pub struct BalanceWatcher<Provider> {
+    provider: Provider,
+    addresses: Vec<Address>,
+    subsidy_wei: U256,
+    warning_threshold_wei: Option<U256>,
+    subsidies: SubsidySwitch,
+    clock: broadcast::Receiver<ClockMessage>,
+}
+
+impl<Provider: StateProviderFactory> BalanceWatcher<Provider> {
+    pub fn new(
+        provider: Provider,
+        addresses: Vec<Address>,
+        subsidy_wei: U256,
+        warning_threshold_wei: Option<U256>,
+        subsidies: SubsidySwitch,
+        clock: broadcast::Receiver<ClockMessage>,
+    ) -> Self {
+        Self { provider, addresses, subsidy_wei, warning_threshold_wei, subsidies, clock }
+    }
+
+    fn fetch_balance(&self, address: Address) -> Option<U256> {
+        match self.provider.latest().and_then(|state| state.account_balance(address)) {
+            Ok(balance) => Some(balance.unwrap_or_default()),
+            Err(err) => {
+                warn!(%err, %address, "could not fetch balance of builder wallet");
+                None
+            }
+        }
+    }
+
+    fn check_balances(&self) {
+        let mut underfunded = vec![];
+        let mut is_complete = true;
+        for &address in &self.addresses {
+            let Some(balance) = self.fetch_balance(address) else {
+                is_complete = false;
+                continue
+            };
+
+            if let Some(threshold) = self.warning_threshold_wei {
+                if balance < threshold {
+                    warn!(%balance, %threshold, %address, "builder wallet balance is low");
+                }
+            }
+
+            if balance < self.subsidy_wei {
+                underfunded.push(address);
+            }
+        }
+
+        // NOTE: only enable subsidies once every balance is known to be sufficient
+        if underfunded.is_empty() && !is_complete {
+            return
+        }
+        let can_subsidize = underfunded.is_empty();
+        let was_enabled = self.subsidies.set(can_subsidize);
+        if was_enabled && !can_subsidize {
+            warn!(
+                subsidy = %self.subsidy_wei,
+                ?underfunded,
+                "insufficient funds in builder wallet(s); disabling subsidies"
+            );
+        } else if !was_enabled && can_subsidize {
+            info!(subsidy = %self.subsidy_wei, "builder wallet(s) funded; enabling subsidies");
+        }
+    }
+
+    pub async fn spawn(mut self) {
+        loop {
+            match self.clock.recv().await {
+                Ok(ClockMessage::NewSlot(_)) => self.check_balances(),
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, RwLock},
+};
+
+// Number of recent auctions to base bid shading on
+const CLEARING_HISTORY_LIMIT: usize = 1024;
+
+/// Shared record of the value of the payloads relays delivered in auctions the builder bid on,
+/// relative to the builder's own revenue for the slot
+///
+/// NOTE: in auctions the builder won the delivered value is the builder's own bid, which bounds
+/// the winning competitor bid from above
+#[derive(Debug, Clone, Default)]
+pub struct ClearingHistory(Arc<RwLock<VecDeque<f64>>>);
+
+impl ClearingHistory {
+    pub fn record(&self, clearing_ratio: f64) {
+        let mut ratios = self.0.write().expect("can lock");
+        if ratios.len() == CLEARING_HISTORY_LIMIT {
+            ratios.pop_front();
+        }
+        ratios.push_back(clearing_ratio);
+    }
+
+    /// Returns the smallest ratio of revenue that would have won at least `win_rate` of the
+    /// recorded auctions, if there are at least `min_samples`
+    pub fn ratio_for_win_rate(&self, win_rate: f64, min_samples: usize) -> Option<f64> {
+        let ratios = self.0.read().expect("can lock");
+        if ratios.is_empty() || ratios.len() < min_samples {
+            return None
+        }
+        let mut ratios = ratios.iter().copied().collect::<Vec<_>>();
+        ratios.sort_by(f64::total_cmp);
+        let index = (win_rate.clamp(0.0, 1.0) * ratios.len() as f64).ceil() as usize;
+        Some(ratios[index.saturating_sub(1)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ratio_for_win_rate() {
+        let history = ClearingHistory::default();
+        for ratio in [0.5, 0.9, 0.7, 0.6, 0.8] {
+            history.record(ratio);
+        }
+        assert_eq!(history.ratio_for_win_rate(0.8, 10), None);
+        assert_eq!(history.ratio_for_win_rate(0.8, 5), Some(0.8));
+        assert_eq!(history.ratio_for_win_rate(1.0, 5), Some(0.9));
+        assert_eq!(history.ratio_for_win_rate(0.0, 5), Some(0.5));
+    }
+}
@@ -1,5 +1,15 @@
+mod balance;
+mod history;
 mod service;
 pub mod strategies;
+mod top_bid_feed;
+mod top_bids;
+mod win_rates;
 
+pub use balance::{BalanceWatcher, SubsidySwitch};
+pub use history::ClearingHistory;
 pub use service::{RevenueUpdate, Service};
 pub use strategies::Config;
+pub use top_bid_feed::{TopBidFeed, TopBidFeedConfig};
+pub use top_bids::TopBids;
+pub use win_rates::{RelayWins, WinRates};
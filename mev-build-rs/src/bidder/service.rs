@@ -1,22 +1,85 @@
 use crate::{
     auctioneer::AuctionContext,
-    bidder::{strategies::BasicStrategy, Config},
+    bidder::{
+        strategies::{
+            BasicStrategy, CancellationStrategy, ShadingStrategy, Strategy, TopBidStrategy,
+        },
+        ClearingHistory, Config, SubsidySwitch, TopBids, WinRates,
+    },
 };
+use ethereum_consensus::primitives::BlsPublicKey;
 use reth::{primitives::revm_primitives::U256, tasks::TaskExecutor};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tokio::sync::{mpsc::Receiver, oneshot};
 use tracing::trace;
 
-pub type RevenueUpdate = (U256, oneshot::Sender<Option<U256>>);
+#[derive(Debug)]
+pub struct RevenueUpdate {
+    pub revenue: U256,
+    // Set once the auction reaches the final bid deadline, to bid the most the builder can
+    pub is_final: bool,
+    pub dispatch: oneshot::Sender<Option<U256>>,
+}
 
 pub struct Service {
     executor: TaskExecutor,
     config: Config,
+    subsidies: SubsidySwitch,
+    win_rates: WinRates,
+    clearing_history: ClearingHistory,
+    top_bids: TopBids,
 }
 
 impl Service {
     pub fn new(executor: TaskExecutor, config: Config) -> Self {
-        Self { executor, config }
+        Self {
+            executor,
+            config,
+            subsidies: Default::default(),
+            win_rates: Default::default(),
+            clearing_history: Default::default(),
+            top_bids: Default::default(),
+        }
+    }
+
+    pub fn subsidies(&self) -> SubsidySwitch {
+        self.subsidies.clone()
+    }
+
+    pub fn win_rates(&self) -> WinRates {
+        self.win_rates.clone()
+    }
+
+    pub fn clearing_history(&self) -> ClearingHistory {
+        self.clearing_history.clone()
+    }
+
+    pub fn top_bids(&self) -> TopBids {
+        self.top_bids.clone()
+    }
+
+    pub fn top_bid_feed_url(&self) -> Option<String> {
+        self.config.top_bid_feed.as_ref().map(|feed| feed.url.clone())
+    }
+
+    /// Indicates if bids to the relay are submitted with cancellations
+    pub fn cancellations_enabled(&self, relay_public_key: &BlsPublicKey) -> bool {
+        self.overrides_for(relay_public_key)
+            .map(|index| self.config.relay_overrides[index].cancellations)
+            .unwrap_or_default()
+    }
+
+    /// Returns how far into the slot to submit a final bid, if configured
+    pub fn final_bid_deadline(&self) -> Option<Duration> {
+        self.config.final_bid_deadline_ms.map(Duration::from_millis)
+    }
+
+    /// Returns the index of the bidding overrides configured for the relay, if any
+    pub fn overrides_for(&self, relay_public_key: &BlsPublicKey) -> Option<usize> {
+        self.config
+            .relay_overrides
+            .iter()
+            .position(|overrides| &overrides.relay_public_key == relay_public_key)
     }
 
     pub fn start_bid(
@@ -25,13 +88,44 @@ impl Service {
         mut revenue_updates: Receiver<RevenueUpdate>,
     ) {
         // TODO: make strategies configurable...
-        let mut strategy = BasicStrategy::new(&self.config);
+        let overrides =
+            auction.bid_overrides.and_then(|index| self.config.relay_overrides.get(index));
+        let basic = BasicStrategy::new(&self.config, overrides, self.subsidies.clone());
+        let config = &self.config;
+        let mut strategy = match (overrides, config.top_bid_feed.as_ref(), config.shading.as_ref())
+        {
+            (Some(overrides), _, _) if overrides.cancellations => {
+                Strategy::Cancellation(CancellationStrategy::new(
+                    basic,
+                    self.top_bids.clone(),
+                    Duration::from_millis(overrides.lower_bids_after_ms.unwrap_or_default()),
+                    overrides.top_bid_margin_wei.unwrap_or_default(),
+                ))
+            }
+            (_, Some(feed), _) => Strategy::TopBid(TopBidStrategy::new(
+                basic,
+                self.top_bids.clone(),
+                feed.margin_wei.unwrap_or_default(),
+            )),
+            (_, _, Some(shading)) => Strategy::Shading(ShadingStrategy::new(
+                basic,
+                shading,
+                self.clearing_history.clone(),
+            )),
+            _ => Strategy::Basic(basic),
+        };
         self.executor.spawn_blocking(async move {
             // NOTE: `revenue_updates` will be closed when the builder is done with new payloads for
             // this auction so we can just loop on `recv` and return naturally once the
             // channel is closed
-            while let Some((current_revenue, dispatch)) = revenue_updates.recv().await {
-                let value = strategy.run(&auction, current_revenue).await;
+            while let Some(RevenueUpdate { revenue, is_final, dispatch }) =
+                revenue_updates.recv().await
+            {
+                let value = if is_final {
+                    Some(strategy.max_value(revenue))
+                } else {
+                    strategy.run(&auction, revenue).await
+                };
                 if dispatch.send(value).is_err() {
                     trace!("channel closed; could not send bid value to builder");
                     break
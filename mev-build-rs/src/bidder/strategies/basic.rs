@@ -1,4 +1,8 @@
-use crate::auctioneer::AuctionContext;
+use crate::{
+    auctioneer::AuctionContext,
+    bidder::{strategies::ShadingConfig, SubsidySwitch, TopBidFeedConfig},
+};
+use ethereum_consensus::primitives::BlsPublicKey;
 use reth::primitives::revm_primitives::U256;
 use serde::Deserialize;
 
@@ -10,32 +14,122 @@ pub struct Config {
     // amount to add from the builder's wallet as a subsidy to the auction bid
     // if missing, defaults to 0
     pub subsidy_wei: Option<U256>,
+    // warn if the builder's wallet holds less than this amount
+    // subsidies are disabled while the wallet cannot cover them, regardless of this setting
+    pub balance_warning_threshold_wei: Option<U256>,
+    // bidding settings to use instead of the above for particular relays
+    #[serde(default)]
+    pub relay_overrides: Vec<RelayOverride>,
+    // time into the slot, in milliseconds, to submit a final bid of the full revenue plus any
+    // subsidy, regardless of the bids made so far
+    // if missing, no final bid is made
+    pub final_bid_deadline_ms: Option<u64>,
+    // if present, shade bids based on the payloads relays delivered in recent auctions
+    pub shading: Option<ShadingConfig>,
+    // if present, follow the top bid of a relay and bid just above the other builders
+    // cannot be set along with `shading`
+    pub top_bid_feed: Option<TopBidFeedConfig>,
+}
+
+impl Config {
+    /// Rejects settings selecting more than one bidding strategy
+    pub fn validate(&self) -> eyre::Result<()> {
+        if self.top_bid_feed.is_some() && self.shading.is_some() {
+            eyre::bail!("bidder config sets both `top_bid_feed` and `shading`; choose one strategy")
+        }
+        Ok(())
+    }
+
+    // Largest subsidy the builder may bid with, across all relays
+    pub fn max_subsidy_wei(&self) -> U256 {
+        self.relay_overrides
+            .iter()
+            .filter_map(|overrides| overrides.subsidy_wei)
+            .chain(self.subsidy_wei)
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RelayOverride {
+    // public key of the relay to apply these settings to, as given in its URL
+    pub relay_public_key: BlsPublicKey,
+    // if missing, defaults to the global `bid_percent`
+    pub bid_percent: Option<f64>,
+    // if missing, defaults to the global `subsidy_wei`
+    pub subsidy_wei: Option<U256>,
+    // submit bids with cancellations, bidding the most the builder can early in the slot and
+    // lowering bids to just above the relay's top bid later on
+    #[serde(default)]
+    pub cancellations: bool,
+    // time into the slot, in milliseconds, to start lowering bids when `cancellations` is set
+    // if missing, defaults to 0
+    pub lower_bids_after_ms: Option<u64>,
+    // amount in wei to bid above the relay's top bid when lowering bids
+    // if missing, defaults to 0
+    pub top_bid_margin_wei: Option<U256>,
 }
 
 /// `BasicStrategy` submits a bid for each built payload, with configurable options for:
 /// - percent of the revenue to bid
-/// - a "subsidy" to add
+/// - a "subsidy" to add, while the builder's wallet can cover it
 pub struct BasicStrategy {
     bid_percent: f64,
     subsidy_wei: U256,
+    subsidies: SubsidySwitch,
 }
 
 impl BasicStrategy {
-    pub fn new(config: &Config) -> Self {
+    pub fn new(
+        config: &Config,
+        overrides: Option<&RelayOverride>,
+        subsidies: SubsidySwitch,
+    ) -> Self {
+        let bid_percent =
+            overrides.and_then(|overrides| overrides.bid_percent).or(config.bid_percent);
+        let subsidy_wei =
+            overrides.and_then(|overrides| overrides.subsidy_wei).or(config.subsidy_wei);
         Self {
-            bid_percent: config.bid_percent.unwrap_or(1.0).clamp(0.0, 1.0),
-            subsidy_wei: config.subsidy_wei.unwrap_or_default(),
+            bid_percent: bid_percent.unwrap_or(1.0).clamp(0.0, 1.0),
+            subsidy_wei: subsidy_wei.unwrap_or_default(),
+            subsidies,
         }
     }
 
     fn compute_value(&self, current_revenue: U256) -> U256 {
         let mut value = current_revenue * U256::from(self.bid_percent * 100.0) / U256::from(100);
-        value += self.subsidy_wei;
+        if self.subsidies.is_enabled() {
+            value += self.subsidy_wei;
+        }
         value
     }
 
+    // Bid of the full revenue plus the subsidy, if enabled
+    pub fn max_value(&self, current_revenue: U256) -> U256 {
+        if self.subsidies.is_enabled() {
+            current_revenue + self.subsidy_wei
+        } else {
+            current_revenue
+        }
+    }
+
     pub async fn run(&mut self, _auction: &AuctionContext, current_revenue: U256) -> Option<U256> {
         let value = self.compute_value(current_revenue);
         Some(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_rejects_multiple_strategies() {
+        let mut config = Config { shading: Some(Default::default()), ..Default::default() };
+        assert!(config.validate().is_ok());
+        config.top_bid_feed =
+            Some(TopBidFeedConfig { url: "wss://".to_string(), margin_wei: None });
+        assert!(config.validate().is_err());
+    }
+}
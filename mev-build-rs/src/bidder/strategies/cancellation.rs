@@ -0,0 +1,52 @@
+use crate::{
+    auctioneer::AuctionContext,
+    bidder::{strategies::BasicStrategy, TopBids},
+    compat::to_bytes32,
+};
+use reth::{api::PayloadBuilderAttributes, primitives::revm_primitives::U256};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// `CancellationStrategy` is for relays accepting cancellations: it bids the most the builder can
+/// early in the slot, then lowers its bid to just above the top bid from other builders observed
+/// at the relay, or to the bid of `BasicStrategy` if there is none
+pub struct CancellationStrategy {
+    basic: BasicStrategy,
+    top_bids: TopBids,
+    lower_bids_after: Duration,
+    top_bid_margin_wei: U256,
+}
+
+impl CancellationStrategy {
+    pub fn new(
+        basic: BasicStrategy,
+        top_bids: TopBids,
+        lower_bids_after: Duration,
+        top_bid_margin_wei: U256,
+    ) -> Self {
+        Self { basic, top_bids, lower_bids_after, top_bid_margin_wei }
+    }
+
+    fn is_lowering(&self, auction: &AuctionContext) -> bool {
+        let lower_from = UNIX_EPOCH +
+            Duration::from_secs(auction.attributes.timestamp()) +
+            self.lower_bids_after;
+        SystemTime::now() >= lower_from
+    }
+
+    pub fn max_value(&self, current_revenue: U256) -> U256 {
+        self.basic.max_value(current_revenue)
+    }
+
+    pub async fn run(&mut self, auction: &AuctionContext, current_revenue: U256) -> Option<U256> {
+        let max_value = self.max_value(current_revenue);
+        if !self.is_lowering(auction) {
+            return Some(max_value)
+        }
+        let parent_hash = to_bytes32(auction.attributes.parent());
+        let value = match self.top_bids.get(auction.slot, &parent_hash) {
+            Some(top_bid) => top_bid + self.top_bid_margin_wei,
+            None => self.basic.run(auction, current_revenue).await?,
+        };
+        Some(value.min(max_value))
+    }
+}
@@ -1,3 +1,40 @@
 mod basic;
+mod cancellation;
+mod shading;
+mod top_bid;
 
-pub use basic::{BasicStrategy, Config};
+use crate::auctioneer::AuctionContext;
+pub use basic::{BasicStrategy, Config, RelayOverride};
+pub use cancellation::CancellationStrategy;
+use reth::primitives::revm_primitives::U256;
+pub use shading::{ShadingConfig, ShadingStrategy, DEFAULT_MIN_SAMPLES, DEFAULT_TARGET_WIN_RATE};
+pub use top_bid::TopBidStrategy;
+
+/// The strategy a bidder follows for an auction
+pub enum Strategy {
+    Basic(BasicStrategy),
+    Shading(ShadingStrategy),
+    Cancellation(CancellationStrategy),
+    TopBid(TopBidStrategy),
+}
+
+impl Strategy {
+    pub async fn run(&mut self, auction: &AuctionContext, current_revenue: U256) -> Option<U256> {
+        match self {
+            Self::Basic(strategy) => strategy.run(auction, current_revenue).await,
+            Self::Shading(strategy) => strategy.run(auction, current_revenue).await,
+            Self::Cancellation(strategy) => strategy.run(auction, current_revenue).await,
+            Self::TopBid(strategy) => strategy.run(auction, current_revenue).await,
+        }
+    }
+
+    /// The most the strategy will bid for `current_revenue`
+    pub fn max_value(&self, current_revenue: U256) -> U256 {
+        match self {
+            Self::Basic(strategy) => strategy.max_value(current_revenue),
+            Self::Shading(strategy) => strategy.max_value(current_revenue),
+            Self::Cancellation(strategy) => strategy.max_value(current_revenue),
+            Self::TopBid(strategy) => strategy.max_value(current_revenue),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{
+    auctioneer::AuctionContext,
+    bidder::{strategies::BasicStrategy, ClearingHistory},
+};
+use reth::primitives::revm_primitives::U256;
+use serde::Deserialize;
+
+/// Fraction of recent auctions a shaded bid would have won, if not configured
+pub const DEFAULT_TARGET_WIN_RATE: f64 = 0.9;
+/// Number of recent auctions required before bids are shaded, if not configured
+pub const DEFAULT_MIN_SAMPLES: usize = 32;
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct ShadingConfig {
+    // fraction of recent auctions a shaded bid would have won
+    // if missing, defaults to 0.9
+    pub target_win_rate: Option<f64>,
+    // number of recent auctions required before bids are shaded
+    // if missing, defaults to 32
+    pub min_samples: Option<usize>,
+}
+
+/// `ShadingStrategy` bids as `BasicStrategy` does, but no more than the share of the revenue
+/// that would have won the target fraction of recent auctions
+pub struct ShadingStrategy {
+    basic: BasicStrategy,
+    history: ClearingHistory,
+    target_win_rate: f64,
+    min_samples: usize,
+}
+
+impl ShadingStrategy {
+    pub fn new(basic: BasicStrategy, config: &ShadingConfig, history: ClearingHistory) -> Self {
+        Self {
+            basic,
+            history,
+            target_win_rate: config.target_win_rate.unwrap_or(DEFAULT_TARGET_WIN_RATE),
+            min_samples: config.min_samples.unwrap_or(DEFAULT_MIN_SAMPLES),
+        }
+    }
+
+    fn shade(&self, value: U256, current_revenue: U256) -> U256 {
+        let Some(ratio) = self.history.ratio_for_win_rate(self.target_win_rate, self.min_samples)
+        else {
+            return value
+        };
+        let shaded = current_revenue * U256::from(ratio * 10_000.0) / U256::from(10_000);
+        value.min(shaded)
+    }
+
+    pub fn max_value(&self, current_revenue: U256) -> U256 {
+        self.basic.max_value(current_revenue)
+    }
+
+    pub async fn run(&mut self, auction: &AuctionContext, current_revenue: U256) -> Option<U256> {
+        let value = self.basic.run(auction, current_revenue).await?;
+        Some(self.shade(value, current_revenue))
+    }
+}
@@ -0,0 +1,32 @@
+use crate::{
+    auctioneer::AuctionContext,
+    bidder::{strategies::BasicStrategy, TopBids},
+    compat::to_bytes32,
+};
+use reth::{api::PayloadBuilderAttributes, primitives::revm_primitives::U256};
+
+/// `TopBidStrategy` bids just above the top bid from other builders observed for the slot, up to
+/// the most the builder can, or as `BasicStrategy` does if no other bid has been observed
+pub struct TopBidStrategy {
+    basic: BasicStrategy,
+    top_bids: TopBids,
+    margin_wei: U256,
+}
+
+impl TopBidStrategy {
+    pub fn new(basic: BasicStrategy, top_bids: TopBids, margin_wei: U256) -> Self {
+        Self { basic, top_bids, margin_wei }
+    }
+
+    pub fn max_value(&self, current_revenue: U256) -> U256 {
+        self.basic.max_value(current_revenue)
+    }
+
+    pub async fn run(&mut self, auction: &AuctionContext, current_revenue: U256) -> Option<U256> {
+        let parent_hash = to_bytes32(auction.attributes.parent());
+        let Some(top_bid) = self.top_bids.get(auction.slot, &parent_hash) else {
+            return self.basic.run(auction, current_revenue).await
+        };
+        Some((top_bid + self.margin_wei).min(self.max_value(current_revenue)))
+    }
+}
@@ -0,0 +1,83 @@
+use crate::bidder::TopBids;
+use ethereum_consensus::{
+    primitives::{BlsPublicKey, ExecutionAddress, Hash32, Slot},
+    ssz::prelude::*,
+};
+use futures_util::StreamExt;
+use std::time::Duration;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{debug, info, warn};
+
+// Time to wait before reconnecting to a feed after the connection is lost
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct TopBidFeedConfig {
+    // websocket endpoint streaming the top bid of a relay, in the SSZ format served at
+    // `/ws/v1/top_bid` by the ultrasound relay
+    pub url: String,
+    // amount in wei to bid above the top bid from other builders
+    // if missing, defaults to 0
+    pub margin_wei: Option<U256>,
+}
+
+/// Update to the top bid of a relay
+#[derive(Debug, Default, SimpleSerialize)]
+pub struct TopBidUpdate {
+    pub timestamp: u64,
+    pub slot: Slot,
+    pub block_number: u64,
+    pub block_hash: Hash32,
+    pub parent_hash: Hash32,
+    pub builder_public_key: BlsPublicKey,
+    pub fee_recipient: ExecutionAddress,
+    pub value: U256,
+}
+
+/// Follows the top bid of a relay over a websocket, recording the bids of other builders
+pub struct TopBidFeed {
+    url: String,
+    // NOTE: used to ignore the builder's own bids
+    builder_public_key: BlsPublicKey,
+    top_bids: TopBids,
+}
+
+impl TopBidFeed {
+    pub fn new(url: String, builder_public_key: BlsPublicKey, top_bids: TopBids) -> Self {
+        Self { url, builder_public_key, top_bids }
+    }
+
+    fn on_message(&self, message: Message) {
+        let Message::Binary(data) = message else { return };
+
+        match deserialize::<TopBidUpdate>(&data) {
+            Ok(update) if update.builder_public_key != self.builder_public_key => {
+                debug!(slot = update.slot, parent_hash = %update.parent_hash, value = %update.value, "observed top bid");
+                self.top_bids.record(update.slot, update.parent_hash, update.value);
+            }
+            Ok(_) => {}
+            Err(err) => warn!(%err, url = %self.url, "could not decode top bid update"),
+        }
+    }
+
+    async fn follow(&self) -> Result<(), tokio_tungstenite::tungstenite::Error> {
+        let (mut stream, _) = connect_async(self.url.as_str()).await?;
+        info!(url = %self.url, "following top bid feed");
+        while let Some(message) = stream.next().await {
+            self.on_message(message?);
+        }
+        Ok(())
+    }
+
+    pub async fn spawn(self) {
+        loop {
+            match self.follow().await {
+                Ok(()) => warn!(url = %self.url, "top bid feed closed; reconnecting"),
+                Err(err) => {
+                    warn!(%err, url = %self.url, "error following top bid feed; reconnecting")
+                }
+            }
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    }
+}
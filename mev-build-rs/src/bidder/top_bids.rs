@@ -0,0 +1,29 @@
+use ethereum_consensus::primitives::{Hash32, Slot};
+use reth::primitives::revm_primitives::U256;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, RwLock},
+};
+
+/// Shared record of the highest bid from other builders observed at relays for each slot, by the
+/// parent block the bid builds on
+#[derive(Debug, Clone, Default)]
+pub struct TopBids(Arc<RwLock<BTreeMap<Slot, HashMap<Hash32, U256>>>>);
+
+impl TopBids {
+    pub fn record(&self, slot: Slot, parent_hash: Hash32, value: U256) {
+        let mut state = self.0.write().expect("can lock");
+        let top = state.entry(slot).or_default().entry(parent_hash).or_default();
+        *top = value.max(*top);
+    }
+
+    pub fn get(&self, slot: Slot, parent_hash: &Hash32) -> Option<U256> {
+        self.0.read().expect("can lock").get(&slot)?.get(parent_hash).copied()
+    }
+
+    /// Drops the bids observed for slots before `slot`
+    pub fn retain_from(&self, slot: Slot) {
+        let mut state = self.0.write().expect("can lock");
+        *state = state.split_off(&slot);
+    }
+}
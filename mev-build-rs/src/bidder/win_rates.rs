@@ -0,0 +1,43 @@
+use ethereum_consensus::primitives::BlsPublicKey;
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+/// Slots the builder bid on at a relay, and how many of those the relay delivered its payload for
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RelayWins {
+    pub slots_bid: u64,
+    pub slots_won: u64,
+}
+
+impl RelayWins {
+    pub fn win_rate(&self) -> Option<f64> {
+        if self.slots_bid == 0 {
+            None
+        } else {
+            Some(self.slots_won as f64 / self.slots_bid as f64)
+        }
+    }
+}
+
+/// Shared record of the builder's wins at each relay, keyed by the relay's public key
+#[derive(Debug, Clone, Default)]
+pub struct WinRates(Arc<RwLock<HashMap<BlsPublicKey, RelayWins>>>);
+
+impl WinRates {
+    /// Records the outcome of a slot the builder bid on at `relay`, returning the updated wins
+    pub fn record(&self, relay: &BlsPublicKey, won: bool) -> RelayWins {
+        let mut state = self.0.write().expect("can lock");
+        let wins = state.entry(relay.clone()).or_default();
+        wins.slots_bid += 1;
+        if won {
+            wins.slots_won += 1;
+        }
+        *wins
+    }
+
+    pub fn get(&self, relay: &BlsPublicKey) -> RelayWins {
+        self.0.read().expect("can lock").get(relay).copied().unwrap_or_default()
+    }
+}
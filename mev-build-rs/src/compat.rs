@@ -36,9 +36,15 @@ pub fn to_execution_payload(value: &SealedBlock, fork: Fork) -> Result<Execution
     let withdrawals = &value.body.withdrawals;
     match fork {
         Fork::Deneb => {
+            // NOTE: encode each transaction into the same buffer, rather than allocate for each
+            let mut encoding = Vec::new();
             let transactions = transactions
                 .iter()
-                .map(|t| deneb::Transaction::try_from(t.encoded_2718().as_ref()).unwrap())
+                .map(|t| {
+                    encoding.clear();
+                    t.encode_2718(&mut encoding);
+                    deneb::Transaction::try_from(encoding.as_slice()).unwrap()
+                })
                 .collect::<Vec<_>>();
             let withdrawals = withdrawals
                 .as_ref()
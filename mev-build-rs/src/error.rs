@@ -1,4 +1,4 @@
-use alloy::signers::local::LocalSignerError;
+use alloy::signers::{local::LocalSignerError, Error as RemoteSignerError};
 use ethereum_consensus::{Error as ConsensusError, Fork};
 use reth::payload::PayloadBuilderError;
 use thiserror::Error;
@@ -13,4 +13,8 @@ pub enum Error {
     PayloadBuilderError(#[from] PayloadBuilderError),
     #[error(transparent)]
     SignerError(#[from] LocalSignerError),
+    #[error(transparent)]
+    RemoteSignerError(#[from] RemoteSignerError),
+    #[error("invalid configuration: {0}")]
+    InvalidConfig(String),
 }
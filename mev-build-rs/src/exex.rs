@@ -0,0 +1,53 @@
+use futures_util::TryStreamExt;
+use reth::{api::FullNodeComponents, primitives::revm_primitives::B256};
+use reth_exex::{ExExContext, ExExEvent, ExExNotification};
+use std::collections::HashSet;
+use tokio::sync::mpsc::Sender;
+use tracing::warn;
+
+/// An update to the canonical chain, as observed by the node
+#[derive(Debug, Default)]
+pub struct ChainUpdate {
+    /// Hashes of the blocks removed from the canonical chain
+    pub reverted: HashSet<B256>,
+    /// Hashes of the parents of blocks added to the canonical chain
+    pub extended: HashSet<B256>,
+}
+
+impl ChainUpdate {
+    /// Indicates if a block built on `parent_hash` can no longer become canonical
+    pub fn is_stale(&self, parent_hash: &B256) -> bool {
+        self.reverted.contains(parent_hash) || self.extended.contains(parent_hash)
+    }
+}
+
+impl From<&ExExNotification> for ChainUpdate {
+    fn from(notification: &ExExNotification) -> Self {
+        let mut update = ChainUpdate::default();
+        if let Some(chain) = notification.reverted_chain() {
+            update.reverted.extend(chain.blocks_iter().map(|block| block.hash()));
+        }
+        if let Some(chain) = notification.committed_chain() {
+            update
+                .extended
+                .extend(chain.blocks_iter().map(|block| block.header.header().parent_hash));
+        }
+        update
+    }
+}
+
+/// Execution extension forwarding canonical chain updates to the auctioneer
+pub async fn chain_updates<Node: FullNodeComponents>(
+    mut ctx: ExExContext<Node>,
+    updates: Sender<ChainUpdate>,
+) -> eyre::Result<()> {
+    while let Some(notification) = ctx.notifications.try_next().await? {
+        if updates.send(ChainUpdate::from(&notification)).await.is_err() {
+            warn!("could not send chain update to auctioneer");
+        }
+        if let Some(chain) = notification.committed_chain() {
+            ctx.events.send(ExExEvent::FinishedHeight(chain.tip().num_hash()))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use ethereum_consensus::primitives::{BlsPublicKey, Slot};
+use mev_rs::json_lines::JsonLinesWriter;
+use reth::primitives::revm_primitives::{B256, U256};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::mpsc::TrySendError,
+};
+use tracing::{error, warn};
+
+/// A bid accepted by a relay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BidRecord {
+    pub slot: Slot,
+    pub relay: String,
+    pub block_hash: B256,
+    pub proposer_public_key: BlsPublicKey,
+    /// Amount paid to the proposer
+    pub value: U256,
+    /// Fees earned by the builder from the block's transactions
+    pub revenue: U256,
+    /// Part of `value` paid from the builder's wallet beyond `revenue`
+    pub subsidy: U256,
+    /// Cost of the gas used by the payment transaction
+    pub payment_gas_cost: U256,
+    /// Set once the relay reports it delivered this bid's payload
+    #[serde(default)]
+    pub won: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Entry {
+    Bid(BidRecord),
+    Won { slot: Slot, relay: String, block_hash: B256 },
+}
+
+// Number of entries that may wait to be written before further entries are dropped
+const MAX_PENDING_ENTRIES: usize = 4096;
+
+/// Record of the bids made by the builder, persisted to a file with one JSON entry per line
+///
+/// Entries are written from a dedicated thread so recording a bid never blocks the caller on
+/// file I/O. Entries still queued when the ledger is dropped are written before it returns.
+pub struct Ledger {
+    writer: Option<JsonLinesWriter<Entry>>,
+}
+
+impl Ledger {
+    pub fn new(path: PathBuf) -> Self {
+        let writer = match JsonLinesWriter::spawn(
+            "mev-build-ledger",
+            path.clone(),
+            MAX_PENDING_ENTRIES,
+            None,
+        ) {
+            Ok(writer) => Some(writer),
+            Err(err) => {
+                error!(%err, path = %path.display(), "could not open ledger");
+                None
+            }
+        };
+        Self { writer }
+    }
+
+    fn append(&self, entry: Entry) {
+        let Some(writer) = self.writer.as_ref() else { return };
+
+        match writer.append(entry) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => warn!("ledger writer is behind; dropping entry"),
+            Err(TrySendError::Disconnected(_)) => {
+                warn!("ledger writer has stopped; dropping entry")
+            }
+        }
+    }
+
+    pub fn record_bid(&self, bid: BidRecord) {
+        self.append(Entry::Bid(bid));
+    }
+
+    pub fn record_win(&self, slot: Slot, relay: String, block_hash: B256) {
+        self.append(Entry::Won { slot, relay, block_hash });
+    }
+}
+
+/// Bids read back from a ledger file, with their winning status resolved
+pub fn load(path: &Path) -> io::Result<Vec<BidRecord>> {
+    let data = fs::read_to_string(path)?;
+    let mut bids = vec![];
+    let mut wins = vec![];
+    for line in data.lines().filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str(line) {
+            Ok(Entry::Bid(bid)) => bids.push(bid),
+            Ok(Entry::Won { slot, relay, block_hash }) => wins.push((slot, relay, block_hash)),
+            Err(err) => warn!(%err, path = %path.display(), "skipping invalid ledger entry"),
+        }
+    }
+    for (slot, relay, block_hash) in wins {
+        for bid in bids
+            .iter_mut()
+            .filter(|bid| bid.slot == slot && bid.relay == relay && bid.block_hash == block_hash)
+        {
+            bid.won = true;
+        }
+    }
+    Ok(bids)
+}
+
+/// Aggregate outcome of a set of bids
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Totals {
+    pub bids: usize,
+    pub wins: usize,
+    /// Fees earned by winning bids
+    pub revenue: U256,
+    /// Subsidies paid by winning bids
+    pub subsidy: U256,
+    /// Gas paid for the payment transactions of winning bids
+    pub payment_gas_cost: U256,
+    /// Amount paid to proposers by winning bids
+    pub value: U256,
+}
+
+impl Totals {
+    fn add(&mut self, bid: &BidRecord) {
+        self.bids += 1;
+        if bid.won {
+            self.wins += 1;
+            self.revenue += bid.revenue;
+            self.subsidy += bid.subsidy;
+            self.payment_gas_cost += bid.payment_gas_cost;
+            self.value += bid.value;
+        }
+    }
+
+    /// Net profit of the winning bids, and whether it is a loss
+    pub fn profit(&self) -> (U256, bool) {
+        let cost = self.value + self.payment_gas_cost;
+        if self.revenue >= cost {
+            (self.revenue - cost, false)
+        } else {
+            (cost - self.revenue, true)
+        }
+    }
+}
+
+/// Totals of `bids` for each relay
+pub fn totals_by_relay(bids: &[BidRecord]) -> BTreeMap<String, Totals> {
+    let mut totals = BTreeMap::<_, Totals>::new();
+    for bid in bids {
+        totals.entry(bid.relay.clone()).or_default().add(bid);
+    }
+    totals
+}
+
+/// Totals of `bids` for each slot
+pub fn totals_by_slot(bids: &[BidRecord]) -> BTreeMap<Slot, Totals> {
+    let mut totals = BTreeMap::<_, Totals>::new();
+    for bid in bids {
+        totals.entry(bid.slot).or_default().add(bid);
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bid(slot: Slot, relay: &str, revenue: u64, value: u64) -> BidRecord {
+        BidRecord {
+            slot,
+            relay: relay.to_string(),
+            block_hash: B256::with_last_byte(slot as u8),
+            proposer_public_key: Default::default(),
+            value: U256::from(value),
+            revenue: U256::from(revenue),
+            subsidy: U256::from(value.saturating_sub(revenue)),
+            payment_gas_cost: U256::from(1),
+            won: false,
+        }
+    }
+
+    #[test]
+    fn test_ledger_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("mev-build-ledger-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let ledger = Ledger::new(path.clone());
+        ledger.record_bid(bid(1, "a", 10, 8));
+        ledger.record_bid(bid(1, "b", 10, 8));
+        ledger.record_bid(bid(2, "a", 10, 12));
+        ledger.record_win(1, "a".to_string(), B256::with_last_byte(1));
+        ledger.record_win(2, "a".to_string(), B256::with_last_byte(2));
+        // NOTE: waits for the writer to persist the entries
+        drop(ledger);
+
+        let bids = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(bids.iter().filter(|bid| bid.won).count(), 2);
+
+        let totals = totals_by_relay(&bids);
+        let relay = &totals["a"];
+        assert_eq!((relay.bids, relay.wins), (2, 2));
+        assert_eq!(relay.subsidy, U256::from(2));
+        // 20 earned, 20 paid and 2 of gas
+        assert_eq!(relay.profit(), (U256::from(2), true));
+        assert_eq!(totals["b"].wins, 0);
+
+        let totals = totals_by_slot(&bids);
+        assert_eq!(totals[&1].profit(), (U256::from(1), false));
+    }
+}
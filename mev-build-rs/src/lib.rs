@@ -2,9 +2,17 @@ mod auctioneer;
 mod bidder;
 mod compat;
 mod error;
+mod exex;
+pub mod ledger;
 mod node;
 mod payload;
+mod rpc;
 mod service;
+mod submission;
 
 pub use crate::error::Error;
+pub use auctioneer::DEFAULT_MAX_HEAD_LAG_SLOTS;
+pub use bidder::strategies::{DEFAULT_MIN_SAMPLES, DEFAULT_TARGET_WIN_RATE};
+pub use payload::builder::{DEFAULT_PAYMENT_SIGNER_COUNT, PAYMENT_TO_CONTRACT_GAS_LIMIT};
 pub use service::{launch, Config};
+pub use submission::{Config as SubmitterConfig, Service as Submitter};
@@ -12,7 +12,7 @@ use reth::{
     rpc::types::engine::PayloadAttributes,
 };
 use sha2::Digest;
-use std::convert::Infallible;
+use std::{convert::Infallible, time::Duration};
 use tokio::sync::mpsc::Sender;
 
 pub fn payload_id(parent: &B256, attributes: &PayloadAttributes) -> PayloadId {
@@ -41,6 +41,9 @@ pub fn mix_proposal_into_payload_id(payload_id: B64, proposal: &ProposalAttribut
 
     hasher.update(proposal.proposer_gas_limit.to_be_bytes());
     hasher.update(proposal.proposer_fee_recipient.as_slice());
+    if let Some(index) = proposal.bid_overrides {
+        hasher.update((index as u64).to_be_bytes());
+    }
 
     let out = hasher.finalize();
     PayloadId::new(out.as_slice()[..8].try_into().expect("sufficient length"))
@@ -50,7 +53,11 @@ pub fn mix_proposal_into_payload_id(payload_id: B64, proposal: &ProposalAttribut
 pub struct ProposalAttributes {
     pub proposer_gas_limit: u64,
     pub proposer_fee_recipient: Address,
+    // Distinguishes builds for the same proposal bid with different settings
+    pub bid_overrides: Option<usize>,
     pub bidder: Sender<RevenueUpdate>,
+    // Time into the slot to make a final bid of the most the bidder allows
+    pub final_bid_deadline: Option<Duration>,
 }
 
 #[derive(Debug, Clone)]
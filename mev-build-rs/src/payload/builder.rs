@@ -1,14 +1,20 @@
-use crate::payload::{attributes::BuilderPayloadBuilderAttributes, job::PayloadFinalizerConfig};
-use alloy::signers::{local::PrivateKeySigner, SignerSync};
+use crate::{
+    compat::to_blobs_bundle,
+    payload::{
+        attributes::BuilderPayloadBuilderAttributes, job::PayloadFinalizerConfig,
+        signer::PaymentSigner,
+    },
+};
 use alloy_consensus::TxEip1559;
-use mev_rs::compute_preferred_gas_limit;
+use mev_rs::{compute_preferred_gas_limit, types::BlobsBundle};
 use reth::{
     api::PayloadBuilderAttributes,
     chainspec::{ChainSpec, EthereumHardforks},
     payload::{EthBuiltPayload, PayloadBuilderError, PayloadId},
     primitives::{
         constants::{
-            eip4844::MAX_DATA_GAS_PER_BLOCK, BEACON_NONCE, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS,
+            eip4844::{DATA_GAS_PER_BLOB, MAX_DATA_GAS_PER_BLOCK},
+            BEACON_NONCE, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS,
         },
         proofs,
         revm_primitives::{
@@ -35,10 +41,14 @@ use reth_basic_payload_builder::{
 };
 use reth_evm::{system_calls::SystemCaller, ConfigureEvm, ConfigureEvmEnv, NextBlockEnvAttributes};
 use reth_node_ethereum::EthEvmConfig;
+use serde::Deserialize;
 use std::{
     collections::HashMap,
     ops::Deref,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
 };
 use thiserror::Error;
 use tokio::sync::mpsc::Sender;
@@ -48,14 +58,86 @@ use tracing::{debug, trace, warn};
 pub enum Error {
     #[error("block gas used {gas_used} exceeded block gas limit {gas_limit}")]
     BlockGasLimitExceeded { gas_used: u64, gas_limit: u64 },
+    #[error("payment to {recipient} reverted after using {gas_used} of {gas_limit} gas")]
+    PaymentReverted { recipient: Address, gas_used: u64, gas_limit: u64 },
+    #[error("payment signer {signer} has balance {balance} but the payment needs {required}")]
+    InsufficientSignerBalance { signer: Address, balance: U256, required: U256 },
 }
 
 pub const BASE_TX_GAS_LIMIT: u64 = 21000;
 
 pub const PAYMENT_TO_CONTRACT_GAS_LIMIT: u64 = 100_000;
 
-fn make_payment_transaction(
-    signer: &PrivateKeySigner,
+/// Number of accounts derived from the execution mnemonic to author payments, if not configured
+pub const DEFAULT_PAYMENT_SIGNER_COUNT: u32 = 1;
+
+/// How the signer of each payment transaction is chosen when several are configured
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentSignerRotation {
+    /// Rotate with each block height, so all builds for a given slot share a signer
+    PerSlot,
+    /// Use the next signer for each new payload job, so the builds of concurrent jobs do not
+    /// share a nonce; every build within a job uses the job's signer
+    #[default]
+    RoundRobin,
+}
+
+impl PaymentSignerRotation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PerSlot => "per_slot",
+            Self::RoundRobin => "round_robin",
+        }
+    }
+}
+
+/// Limits on the blobs included in built payloads, to trade blob revenue against the risk of
+/// slower propagation
+#[derive(Deserialize, Debug, Default, Clone, Copy)]
+pub struct BlobPolicy {
+    /// Number of blobs to include regardless of `min_blob_fee_wei`
+    pub target_blobs: Option<u64>,
+    /// Most blobs to include in a payload, if lower than the protocol's limit
+    pub max_blobs: Option<u64>,
+    /// Least fee paid to the builder per blob for blobs beyond `target_blobs`
+    pub min_blob_fee_wei: Option<U256>,
+}
+
+impl BlobPolicy {
+    fn max_blob_gas(&self) -> u64 {
+        self.max_blobs
+            .map(|max_blobs| max_blobs.saturating_mul(DATA_GAS_PER_BLOB))
+            .unwrap_or(MAX_DATA_GAS_PER_BLOCK)
+            .min(MAX_DATA_GAS_PER_BLOCK)
+    }
+
+    // Indicates if a transaction with `blob_count` blobs paying the builder `fee` should be
+    // included in a payload already holding `included_blobs`
+    fn accepts(&self, included_blobs: u64, blob_count: u64, fee: U256) -> bool {
+        let Some(min_blob_fee) = self.min_blob_fee_wei else { return true };
+        if included_blobs + blob_count <= self.target_blobs.unwrap_or_default() {
+            return true
+        }
+        fee >= min_blob_fee * U256::from(blob_count)
+    }
+}
+
+/// A payload finalized with the payment to the proposer, along with the accounting of its bid
+#[derive(Debug, Clone)]
+pub struct FinalizedPayload {
+    // NOTE: carries no blob sidecars, see `blobs_bundle`
+    pub payload: EthBuiltPayload,
+    /// Blobs of the payload's transactions, converted once from the built payload's sidecars
+    pub blobs_bundle: BlobsBundle,
+    /// Fees earned by the builder from the block's transactions
+    pub revenue: U256,
+    /// Cost of the gas used by the payment transaction
+    pub payment_gas_cost: U256,
+}
+
+async fn make_payment_transaction(
+    signer: &PaymentSigner,
     config: &PayloadFinalizerConfig,
     chain_id: ChainId,
     nonce: u64,
@@ -75,7 +157,10 @@ fn make_payment_transaction(
         input: Default::default(),
     });
     let signature_hash = tx.signature_hash();
-    let signature = signer.sign_hash_sync(&signature_hash).expect("can sign");
+    let signature = signer
+        .sign_hash(&signature_hash)
+        .await
+        .map_err(|err| PayloadBuilderError::Other(Box::new(err)))?;
     let signed_transaction = TransactionSigned::from_transaction_and_signature(
         tx,
         Signature::new(signature.r(), signature.s(), Parity::Parity(signature.v().y_parity())),
@@ -83,15 +168,15 @@ fn make_payment_transaction(
     Ok(TransactionSignedEcRecovered::from_signed_transaction(signed_transaction, signer.address()))
 }
 
-fn append_payment<Client: StateProviderFactory>(
+async fn append_payment<Client: StateProviderFactory>(
     client: Client,
     execution_outcome: ExecutionOutcome,
-    signer: &PrivateKeySigner,
+    signer: &PaymentSigner,
     config: &PayloadFinalizerConfig,
     chain_id: ChainId,
     block: SealedBlock,
     value: U256,
-) -> Result<SealedBlock, PayloadBuilderError> {
+) -> Result<(SealedBlock, U256), PayloadBuilderError> {
     let state_provider = client.state_by_block_hash(block.header.header().parent_hash)?;
     let state = StateProviderDatabase::new(&state_provider);
     // TODO: use cached reads
@@ -103,7 +188,10 @@ fn append_payment<Client: StateProviderFactory>(
         .build();
 
     let signer_account = db.load_cache_account(signer.address())?;
-    let nonce = signer_account.account_info().map(|account| account.nonce).unwrap_or_default();
+    let (nonce, balance) = signer_account
+        .account_info()
+        .map(|account| (account.nonce, account.balance))
+        .unwrap_or_default();
 
     let proposer_fee_recipient_account = db.load_cache_account(config.proposer_fee_recipient)?;
     let is_empty_code_hash = proposer_fee_recipient_account
@@ -114,10 +202,19 @@ fn append_payment<Client: StateProviderFactory>(
     // Use a fixed gas limit for the payment transaction reflecting the recipient's status
     // as smart contract or EOA.
     let gas_limit =
-        if is_empty_code_hash { BASE_TX_GAS_LIMIT } else { PAYMENT_TO_CONTRACT_GAS_LIMIT };
+        if is_empty_code_hash { BASE_TX_GAS_LIMIT } else { config.contract_payment_gas_limit };
 
     // SAFETY: cast to bigger type always succeeds
     let max_fee_per_gas = block.header().base_fee_per_gas.unwrap_or_default() as u128;
+    // NOTE: each signer is funded separately, so check this one can cover the bid value
+    let required = value + U256::from(gas_limit) * U256::from(max_fee_per_gas);
+    if balance < required {
+        return Err(PayloadBuilderError::Other(Box::new(Error::InsufficientSignerBalance {
+            signer: signer.address(),
+            balance,
+            required,
+        })))
+    }
     let payment_tx = make_payment_transaction(
         signer,
         config,
@@ -126,7 +223,8 @@ fn append_payment<Client: StateProviderFactory>(
         gas_limit,
         max_fee_per_gas,
         value,
-    )?;
+    )
+    .await?;
 
     // TODO: skip clones here
     let mut tx_env = TxEnv::default();
@@ -144,6 +242,17 @@ fn append_payment<Client: StateProviderFactory>(
         evm.transact().map_err(PayloadBuilderError::EvmExecutionError)?;
 
     drop(evm);
+
+    // NOTE: a block with a failed payment does not pay the proposer the bid value,
+    // so it should not be submitted at all
+    if !result.is_success() {
+        return Err(PayloadBuilderError::Other(Box::new(Error::PaymentReverted {
+            recipient: config.proposer_fee_recipient,
+            gas_used: result.gas_used(),
+            gas_limit,
+        })))
+    }
+
     db.commit(state);
 
     let Block { mut header, mut body } = block.unseal();
@@ -157,6 +266,7 @@ fn append_payment<Client: StateProviderFactory>(
             gas_limit: header.gas_limit,
         })))
     }
+    let payment_gas_cost = U256::from(result.gas_used()) * U256::from(max_fee_per_gas);
     let receipt = Receipt {
         tx_type: payment_tx.tx_type(),
         success: result.is_success(),
@@ -193,7 +303,7 @@ fn append_payment<Client: StateProviderFactory>(
 
     let block = Block { header, body };
 
-    Ok(block.seal_slow())
+    Ok((block.seal_slow(), payment_gas_cost))
 }
 
 #[derive(Debug, Clone)]
@@ -209,32 +319,79 @@ impl Deref for PayloadBuilder {
 
 #[derive(Debug)]
 pub struct Inner {
-    bids: Sender<EthBuiltPayload>,
-    signer: PrivateKeySigner,
+    bids: Sender<FinalizedPayload>,
+    signers: Vec<PaymentSigner>,
+    signer_rotation: PaymentSignerRotation,
+    next_signer: AtomicUsize,
     fee_recipient: Address,
     chain_id: ChainId,
+    contract_payment_gas_limit: u64,
+    contract_payment_gas_limits: HashMap<Address, u64>,
+    max_gas_limit: Option<u64>,
+    blob_policy: BlobPolicy,
     execution_outcomes: Mutex<HashMap<PayloadId, ExecutionOutcome>>,
     evm_config: EthEvmConfig,
 }
 
 impl PayloadBuilder {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        bids: Sender<EthBuiltPayload>,
-        signer: PrivateKeySigner,
+        bids: Sender<FinalizedPayload>,
+        signers: Vec<PaymentSigner>,
+        signer_rotation: PaymentSignerRotation,
         fee_recipient: Address,
         chain_id: ChainId,
         chain_spec: Arc<ChainSpec>,
-    ) -> Self {
+        contract_payment_gas_limit: Option<u64>,
+        contract_payment_gas_limits: HashMap<Address, u64>,
+        max_gas_limit: Option<u64>,
+        blob_policy: BlobPolicy,
+    ) -> Result<Self, crate::Error> {
+        if signers.is_empty() {
+            return Err(crate::Error::InvalidConfig(
+                "at least one payment signer is required".to_string(),
+            ))
+        }
         let evm_config = EthEvmConfig::new(chain_spec);
         let inner = Inner {
             bids,
-            signer,
+            signers,
+            signer_rotation,
+            next_signer: Default::default(),
             fee_recipient,
             chain_id,
+            contract_payment_gas_limit: contract_payment_gas_limit
+                .unwrap_or(PAYMENT_TO_CONTRACT_GAS_LIMIT),
+            contract_payment_gas_limits,
+            max_gas_limit,
+            blob_policy,
             execution_outcomes: Default::default(),
             evm_config,
         };
-        Self(Arc::new(inner))
+        Ok(Self(Arc::new(inner)))
+    }
+
+    /// Index of the signer to author payment transactions for a new payload job building
+    /// `block_number`
+    pub fn select_payment_signer(&self, block_number: u64) -> usize {
+        let count = self.signers.len();
+        match self.signer_rotation {
+            PaymentSignerRotation::PerSlot => (block_number % count as u64) as usize,
+            PaymentSignerRotation::RoundRobin => {
+                self.next_signer.fetch_add(1, Ordering::Relaxed) % count
+            }
+        }
+    }
+
+    /// Gas limit of the payment transaction to `fee_recipient`, should it be a contract
+    pub fn contract_payment_gas_limit(&self, fee_recipient: &Address) -> u64 {
+        let gas_limit = self
+            .contract_payment_gas_limits
+            .get(fee_recipient)
+            .copied()
+            .unwrap_or(self.contract_payment_gas_limit);
+        // NOTE: any payment needs at least the gas of a plain transfer
+        gas_limit.max(BASE_TX_GAS_LIMIT)
     }
 
     pub fn cfg_and_block_env(
@@ -252,15 +409,24 @@ impl PayloadBuilder {
 
         // if there is a proposal attributes present, then set the gas limit and fee recipient
         if let Some(ref proposal_attributes) = payload_config.attributes.proposal {
+            // NOTE: the builder may target less gas than the proposer registered for, but the
+            // limit can still only move from the parent's within the protocol's bounds
+            let preferred_gas_limit = match self.max_gas_limit {
+                Some(max_gas_limit) => proposal_attributes.proposer_gas_limit.min(max_gas_limit),
+                None => proposal_attributes.proposer_gas_limit,
+            };
             let gas_limit = compute_preferred_gas_limit(
-                proposal_attributes.proposer_gas_limit,
+                preferred_gas_limit,
                 payload_config.parent_block.gas_limit,
             );
             // NOTE: reserve enough gas for the final payment transaction,
             // regardless of EOA or smart contract
             // TODO: check recipient ahead of time to determine this here, rather than leave some
             // gas on the table
-            block_env.gas_limit = U256::from(gas_limit) - U256::from(PAYMENT_TO_CONTRACT_GAS_LIMIT);
+            let payment_gas_limit =
+                self.contract_payment_gas_limit(&proposal_attributes.proposer_fee_recipient);
+            block_env.gas_limit =
+                U256::from(gas_limit).saturating_sub(U256::from(payment_gas_limit));
         }
         block_env.coinbase = self.0.fee_recipient;
 
@@ -279,16 +445,21 @@ impl PayloadBuilder {
         payment_amount: U256,
         config: &PayloadFinalizerConfig,
     ) {
-        let blob_sidecars = payload.sidecars().to_vec();
-        match self.finalize_payload(
-            payload.id(),
-            client,
-            payload.block().clone(),
-            payment_amount,
-            config,
-        ) {
-            Ok(mut payload) => {
-                payload.extend_sidecars(blob_sidecars);
+        // NOTE: the payment transaction carries no blobs, so the built payload's are final
+        let blobs_bundle = match to_blobs_bundle(payload.sidecars()) {
+            Ok(blobs_bundle) => blobs_bundle,
+            Err(err) => {
+                warn!(%err, "could not convert blobs of payload for auction");
+                return
+            }
+        };
+        let revenue = payload.fees();
+        match self
+            .finalize_payload(payload.id(), client, payload.block().clone(), payment_amount, config)
+            .await
+        {
+            Ok((payload, payment_gas_cost)) => {
+                let payload = FinalizedPayload { payload, blobs_bundle, revenue, payment_gas_cost };
                 if let Err(err) = self.bids.send(payload).await {
                     let payload = err.0;
                     warn!(?payload, "could not send payload to auctioneer");
@@ -300,27 +471,28 @@ impl PayloadBuilder {
         }
     }
 
-    pub fn finalize_payload<Client: StateProviderFactory>(
+    pub async fn finalize_payload<Client: StateProviderFactory>(
         &self,
         payload_id: PayloadId,
         client: Client,
         block: SealedBlock,
         payment_amount: U256,
         config: &PayloadFinalizerConfig,
-    ) -> Result<EthBuiltPayload, PayloadBuilderError> {
+    ) -> Result<(EthBuiltPayload, U256), PayloadBuilderError> {
         let execution_outcome = self
             .get_build_execution_outcome(payload_id)
             .ok_or_else(|| PayloadBuilderError::Other("missing build state for payload".into()))?;
-        let block = append_payment(
+        let (block, payment_gas_cost) = append_payment(
             client,
             execution_outcome,
-            &self.signer,
+            &self.signers[config.payment_signer],
             config,
             self.chain_id,
             block,
             payment_amount,
-        )?;
-        Ok(EthBuiltPayload::new(payload_id, block, payment_amount, None))
+        )
+        .await?;
+        Ok((EthBuiltPayload::new(payload_id, block, payment_amount, None), payment_gas_cost))
     }
 }
 
@@ -338,8 +510,13 @@ where
     ) -> Result<BuildOutcome<Self::BuiltPayload>, PayloadBuilderError> {
         let payload_id = args.config.payload_id();
         let (cfg_env, block_env) = self.cfg_and_block_env(&args.config);
-        let (outcome, bundle) =
-            default_ethereum_payload_builder(self.evm_config.clone(), cfg_env, block_env, args)?;
+        let (outcome, bundle) = default_ethereum_payload_builder(
+            self.evm_config.clone(),
+            cfg_env,
+            block_env,
+            &self.blob_policy,
+            args,
+        )?;
         if let Some(bundle) = bundle {
             let mut execution_outcomes = self.execution_outcomes.lock().expect("can lock");
             execution_outcomes.insert(payload_id, bundle);
@@ -468,6 +645,7 @@ pub fn default_ethereum_payload_builder<Pool, Client>(
     evm_config: EthEvmConfig,
     cfg_env: CfgEnvWithHandlerCfg,
     block_env: BlockEnv,
+    blob_policy: &BlobPolicy,
     args: BuildArguments<Pool, Client, BuilderPayloadBuilderAttributes, EthBuiltPayload>,
 ) -> Result<(BuildOutcome<EthBuiltPayload>, Option<ExecutionOutcome>), PayloadBuilderError>
 where
@@ -487,6 +665,7 @@ where
     debug!(target: "payload_builder", id=%attributes.payload_id(), parent_hash = ?parent_block.hash(), parent_number = parent_block.number, "building new payload");
     let mut cumulative_gas_used = 0;
     let mut sum_blob_gas_used = 0;
+    let max_blob_gas = blob_policy.max_blob_gas();
     let block_gas_limit: u64 = block_env.gas_limit.try_into().unwrap_or(u64::MAX);
     let base_fee = block_env.basefee.to::<u64>();
 
@@ -537,7 +716,7 @@ where
         // the EIP-4844 can still fit in the block
         if let Some(blob_tx) = tx.transaction.as_eip4844() {
             let tx_blob_gas = blob_tx.blob_gas();
-            if sum_blob_gas_used + tx_blob_gas > MAX_DATA_GAS_PER_BLOCK {
+            if sum_blob_gas_used + tx_blob_gas > max_blob_gas {
                 // we can't fit this _blob_ transaction into the block, so we mark it as
                 // invalid, which removes its dependent transactions from
                 // the iterator. This is similar to the gas limit condition
@@ -583,6 +762,23 @@ where
         };
         // drop evm so db is released.
         drop(evm);
+
+        let gas_used = result.gas_used();
+        let miner_fee = tx
+            .effective_tip_per_gas(Some(base_fee))
+            .expect("fee is always valid; execution succeeded");
+
+        // skip blob transactions not paying enough to be worth the extra blobs
+        if let Some(blob_tx) = tx.transaction.as_eip4844() {
+            let blob_count = blob_tx.blob_versioned_hashes.len() as u64;
+            let fee = U256::from(miner_fee) * U256::from(gas_used);
+            if !blob_policy.accepts(sum_blob_gas_used / DATA_GAS_PER_BLOB, blob_count, fee) {
+                trace!(target: "payload_builder", tx=?tx.hash, %fee, blob_count, "skipping blob transaction paying less than the minimum blob fee");
+                best_txs.mark_invalid(&pool_tx);
+                continue
+            }
+        }
+
         // commit changes
         db.commit(state);
 
@@ -592,13 +788,11 @@ where
             sum_blob_gas_used += tx_blob_gas;
 
             // if we've reached the max data gas per block, we can skip blob txs entirely
-            if sum_blob_gas_used == MAX_DATA_GAS_PER_BLOCK {
+            if sum_blob_gas_used >= max_blob_gas {
                 best_txs.skip_blobs();
             }
         }
 
-        let gas_used = result.gas_used();
-
         // add gas used by the transaction to cumulative gas used, before creating the receipt
         cumulative_gas_used += gas_used;
 
@@ -613,9 +807,6 @@ where
         }));
 
         // update add to total fees
-        let miner_fee = tx
-            .effective_tip_per_gas(Some(base_fee))
-            .expect("fee is always valid; execution succeeded");
         total_fees += U256::from(miner_fee) * U256::from(gas_used);
 
         // append transaction to the list of executed transactions
@@ -1,6 +1,14 @@
-use crate::payload::{attributes::BuilderPayloadBuilderAttributes, builder::PayloadBuilder};
+use crate::{
+    bidder::RevenueUpdate,
+    payload::{
+        attributes::BuilderPayloadBuilderAttributes,
+        builder::PayloadBuilder,
+        worker_pool::{BuildPriority, WorkerPool},
+    },
+};
 use futures_util::{Future, FutureExt};
 use reth::{
+    api::PayloadBuilderAttributes,
     payload::{
         self, database::CachedReads, EthBuiltPayload, KeepPayloadJobAlive, PayloadBuilderError,
     },
@@ -10,8 +18,8 @@ use reth::{
     transaction_pool::TransactionPool,
 };
 use reth_basic_payload_builder::{
-    BuildArguments, BuildOutcome, Cancelled, PayloadBuilder as _, PayloadConfig, PayloadTaskGuard,
-    PendingPayload, ResolveBestPayload,
+    BuildArguments, BuildOutcome, Cancelled, PayloadBuilder as _, PayloadConfig, PendingPayload,
+    ResolveBestPayload,
 };
 use std::{
     pin::Pin,
@@ -26,6 +34,10 @@ use tracing::{debug, error, trace, warn};
 #[derive(Debug)]
 pub struct PayloadFinalizerConfig {
     pub proposer_fee_recipient: Address,
+    // Index of the signer authoring the payment transaction
+    pub payment_signer: usize,
+    // Gas limit of the payment transaction if `proposer_fee_recipient` is a contract
+    pub contract_payment_gas_limit: u64,
     // TODO: store with payload builder?
     pub cfg_env: CfgEnvWithHandlerCfg,
     // TODO: store with payload builder?
@@ -41,11 +53,48 @@ pub struct PayloadJob<Client, Pool, Tasks> {
     pub interval: Interval,
     pub best_payload: Option<EthBuiltPayload>,
     pub pending_block: Option<PendingPayload<EthBuiltPayload>>,
-    pub payload_task_guard: PayloadTaskGuard,
+    pub worker_pool: WorkerPool,
+    // Number of builds completed for this job, to share the workers with concurrent jobs
+    pub builds: u64,
     pub cached_reads: Option<CachedReads>,
     // TODO: consider moving shared state here, rather than builder
     pub builder: PayloadBuilder,
     pub pending_bid_update: Option<BidUpdate>,
+    pub payment_signer: usize,
+    // Fires when the final bid of the auction is due
+    pub final_bid: Option<Pin<Box<Sleep>>>,
+    // Set once the final bid is due, so that every bid from then on is the most the bidder allows
+    pub bid_all_in: bool,
+    // Set once a bid has been made after the final bid was due
+    pub final_bid_sent: bool,
+}
+
+impl<Client, Pool, Tasks> PayloadJob<Client, Pool, Tasks>
+where
+    Tasks: TaskSpawner,
+{
+    // Asks the bidder for the value to bid for `payload`
+    fn request_bid(&mut self, payload: EthBuiltPayload) {
+        let Some(proposal) = self.config.attributes.proposal.as_ref() else { return };
+
+        let (value_tx, value_rx) = oneshot::channel();
+        let update = RevenueUpdate {
+            revenue: payload.fees(),
+            is_final: self.bid_all_in,
+            dispatch: value_tx,
+        };
+        self.final_bid_sent |= self.bid_all_in;
+        let bidder = proposal.bidder.clone();
+        self.executor.spawn(Box::pin(async move {
+            if bidder.is_closed() {
+                return
+            }
+            if bidder.send(update).await.is_err() {
+                warn!("could not send fees to bidder");
+            }
+        }));
+        self.pending_bid_update = Some(BidUpdate { value_rx, payload: Some(payload) });
+    }
 }
 
 impl<Client, Pool, Tasks> payload::PayloadJob for PayloadJob<Client, Pool, Tasks>
@@ -144,6 +193,14 @@ where
             return Poll::Ready(Ok(()))
         }
 
+        if let Some(final_bid) = this.final_bid.as_mut() {
+            if final_bid.as_mut().poll(cx).is_ready() {
+                debug!(target: "payload_builder", "final bid deadline reached");
+                this.final_bid = None;
+                this.bid_all_in = true;
+            }
+        }
+
         // poll for pending bids
         if let Some(mut fut) = this.pending_bid_update.take() {
             match fut.poll_unpin(cx) {
@@ -157,6 +214,10 @@ where
                             let (cfg_env, block_env) = this.builder.cfg_and_block_env(&this.config);
                             let config = PayloadFinalizerConfig {
                                 proposer_fee_recipient: proposal.proposer_fee_recipient,
+                                payment_signer: this.payment_signer,
+                                contract_payment_gas_limit: this
+                                    .builder
+                                    .contract_payment_gas_limit(&proposal.proposer_fee_recipient),
                                 cfg_env,
                                 block_env,
                             };
@@ -183,6 +244,13 @@ where
             }
         }
 
+        // make the final bid with the best payload so far, once any pending bid has resolved
+        if this.bid_all_in && !this.final_bid_sent && this.pending_bid_update.is_none() {
+            if let Some(payload) = this.best_payload.clone() {
+                this.request_bid(payload);
+            }
+        }
+
         // check if the interval is reached
         while this.interval.poll_tick(cx).is_ready() {
             // start a new job if there is no pending block and we haven't reached the deadline
@@ -193,14 +261,19 @@ where
                 let pool = this.pool.clone();
                 let cancel = Cancelled::default();
                 let _cancel = cancel.clone();
-                let guard = this.payload_task_guard.clone();
                 let payload_config = this.config.clone();
                 let best_payload = this.best_payload.clone();
                 let cached_reads = this.cached_reads.take().unwrap_or_default();
                 let builder = this.builder.clone();
-                this.executor.spawn_blocking(Box::pin(async move {
-                    // acquire the permit for executing the task
-                    let _permit = guard.acquire().await;
+                let priority = BuildPriority {
+                    timestamp: this.config.attributes.timestamp(),
+                    builds: this.builds,
+                };
+                this.worker_pool.spawn(priority, move || {
+                    // NOTE: skip the build if the job finished while it was queued
+                    if tx.is_closed() {
+                        return
+                    }
                     let args = BuildArguments {
                         client,
                         pool,
@@ -211,7 +284,7 @@ where
                     };
                     let result = builder.try_build(args);
                     let _ = tx.send(result);
-                }));
+                });
 
                 this.pending_block = Some(PendingPayload::new(_cancel, rx));
             }
@@ -221,6 +294,7 @@ where
         if let Some(mut fut) = this.pending_block.take() {
             match fut.poll_unpin(cx) {
                 Poll::Ready(Ok(outcome)) => {
+                    this.builds += 1;
                     this.interval.reset();
                     match outcome {
                         BuildOutcome::Better { payload, cached_reads } => {
@@ -229,22 +303,7 @@ where
                             // TODO: consider reworking this code path...
                             // If it stays, then at least skip clone here...
                             this.best_payload = Some(payload.clone());
-
-                            if let Some(proposal) = this.config.attributes.proposal.as_ref() {
-                                let (value_tx, value_rx) = oneshot::channel();
-                                let fees = payload.fees();
-                                let bidder = proposal.bidder.clone();
-                                this.executor.spawn(Box::pin(async move {
-                                    if bidder.is_closed() {
-                                        return
-                                    }
-                                    if bidder.send((fees, value_tx)).await.is_err() {
-                                        warn!("could not send fees to bidder");
-                                    }
-                                }));
-                                this.pending_bid_update =
-                                    Some(BidUpdate { value_rx, payload: Some(payload) });
-                            }
+                            this.request_bid(payload);
                         }
                         BuildOutcome::Aborted { fees, cached_reads } => {
                             this.cached_reads = Some(cached_reads);
@@ -256,6 +315,7 @@ where
                     }
                 }
                 Poll::Ready(Err(error)) => {
+                    this.builds += 1;
                     // job failed, but we simply try again next interval
                     debug!(target: "payload_builder", %error, "payload build attempt failed");
                 }
@@ -1,4 +1,4 @@
-use crate::payload::{builder::PayloadBuilder, job::PayloadJob};
+use crate::payload::{builder::PayloadBuilder, job::PayloadJob, worker_pool::WorkerPool};
 use ethereum_consensus::clock::duration_until;
 use reth::{
     api::PayloadBuilderAttributes,
@@ -11,8 +11,11 @@ use reth::{
     tasks::TaskSpawner,
     transaction_pool::TransactionPool,
 };
-use reth_basic_payload_builder::{PayloadConfig, PayloadTaskGuard, PrecachedState};
-use std::{sync::Arc, time::Duration};
+use reth_basic_payload_builder::{PayloadConfig, PrecachedState};
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 #[derive(Debug, Clone)]
 pub struct PayloadJobGeneratorConfig {
@@ -21,6 +24,7 @@ pub struct PayloadJobGeneratorConfig {
     pub _max_gas_limit: u64,
     pub interval: Duration,
     pub deadline: Duration,
+    // Number of workers running payload builds
     pub max_payload_tasks: usize,
 }
 
@@ -30,7 +34,7 @@ pub struct PayloadJobGenerator<Client, Pool, Tasks> {
     pool: Pool,
     executor: Tasks,
     config: PayloadJobGeneratorConfig,
-    payload_task_guard: PayloadTaskGuard,
+    worker_pool: WorkerPool,
     builder: PayloadBuilder,
     pre_cached: Option<PrecachedState>,
 }
@@ -47,7 +51,7 @@ impl<Client, Pool, Tasks> PayloadJobGenerator<Client, Pool, Tasks> {
             client,
             pool,
             executor,
-            payload_task_guard: PayloadTaskGuard::new(config.max_payload_tasks),
+            worker_pool: WorkerPool::new(config.max_payload_tasks),
             config,
             builder,
             pre_cached: None,
@@ -69,6 +73,13 @@ impl<Client, Pool, Tasks> PayloadJobGenerator<Client, Pool, Tasks> {
         tokio::time::Instant::now() + self.max_job_duration(unix_timestamp)
     }
 
+    // Instant `offset` into the slot starting at `unix_timestamp`
+    fn instant_into_slot(unix_timestamp: u64, offset: Duration) -> tokio::time::Instant {
+        let target = UNIX_EPOCH + Duration::from_secs(unix_timestamp) + offset;
+        let remaining = target.duration_since(SystemTime::now()).unwrap_or_default();
+        tokio::time::Instant::now() + remaining
+    }
+
     fn maybe_pre_cached(&self, parent: B256) -> Option<CachedReads> {
         self.pre_cached.as_ref().filter(|pc| pc.block == parent).map(|pc| pc.cached.clone())
     }
@@ -109,11 +120,19 @@ where
             tokio::time::Instant::now()
         };
         let deadline = Box::pin(tokio::time::sleep_until(until));
+        let final_bid =
+            attributes.proposal.as_ref().and_then(|proposal| proposal.final_bid_deadline).map(
+                |offset| {
+                    let at = Self::instant_into_slot(attributes.timestamp(), offset);
+                    Box::pin(tokio::time::sleep_until(at))
+                },
+            );
 
         let config =
             PayloadConfig::new(Arc::new(parent_block), self.config.extradata.clone(), attributes);
 
         let cached_reads = self.maybe_pre_cached(config.parent_block.hash());
+        let payment_signer = self.builder.select_payment_signer(config.parent_block.number + 1);
 
         Ok(PayloadJob {
             config,
@@ -125,9 +144,14 @@ where
             best_payload: None,
             pending_block: None,
             cached_reads,
-            payload_task_guard: self.payload_task_guard.clone(),
+            worker_pool: self.worker_pool.clone(),
+            builds: 0,
             builder: self.builder.clone(),
             pending_bid_update: None,
+            payment_signer,
+            final_bid,
+            bid_all_in: false,
+            final_bid_sent: false,
         })
     }
 
@@ -5,3 +5,5 @@ pub mod builder;
 pub mod job;
 pub mod job_generator;
 pub mod service_builder;
+pub mod signer;
+pub mod worker_pool;
@@ -1,8 +1,12 @@
 use crate::{
     node::BuilderEngineTypes,
     payload::{
-        builder::PayloadBuilder,
+        builder::{
+            BlobPolicy, FinalizedPayload, PayloadBuilder, PaymentSignerRotation,
+            DEFAULT_PAYMENT_SIGNER_COUNT,
+        },
         job_generator::{PayloadJobGenerator, PayloadJobGeneratorConfig},
+        signer::{kms_signer, PaymentSigner},
     },
     service::BuilderConfig as Config,
     Error,
@@ -12,32 +16,85 @@ use reth::{
     builder::{node::FullNodeTypes, BuilderContext, NodeTypesWithEngine},
     chainspec::ChainSpec,
     cli::config::PayloadBuilderConfig,
-    payload::{EthBuiltPayload, PayloadBuilderHandle, PayloadBuilderService},
+    payload::{PayloadBuilderHandle, PayloadBuilderService},
     primitives::revm_primitives::{Address, Bytes},
     providers::CanonStateSubscriptions,
     transaction_pool::TransactionPool,
 };
+use std::collections::HashMap;
 use tokio::sync::mpsc::Sender;
 
-fn signer_from_mnemonic(mnemonic: &str) -> Result<PrivateKeySigner, Error> {
-    MnemonicBuilder::<English>::default().phrase(mnemonic).build().map_err(Into::into)
+// Derives `count` signers from the accounts at the first indices of `mnemonic`
+fn signers_from_mnemonic(mnemonic: &str, count: u32) -> Result<Vec<PaymentSigner>, Error> {
+    (0..count.max(1))
+        .map(|index| {
+            let signer: PrivateKeySigner =
+                MnemonicBuilder::<English>::default().phrase(mnemonic).index(index)?.build()?;
+            Ok::<_, Error>(PaymentSigner::Local(signer))
+        })
+        .collect()
+}
+
+async fn signers_from_config(config: &Config) -> Result<Vec<PaymentSigner>, Error> {
+    if config.payment_kms_key_ids.is_empty() {
+        if config.execution_mnemonic.is_empty() {
+            return Err(Error::InvalidConfig(
+                "one of `execution_mnemonic` or `payment_kms_key_ids` must be set".to_string(),
+            ))
+        }
+        return signers_from_mnemonic(
+            &config.execution_mnemonic,
+            config.payment_signer_count.unwrap_or(DEFAULT_PAYMENT_SIGNER_COUNT),
+        )
+    }
+
+    let mut signers = Vec::with_capacity(config.payment_kms_key_ids.len());
+    for key_id in &config.payment_kms_key_ids {
+        signers.push(kms_signer(key_id.clone()).await?);
+    }
+    Ok(signers)
 }
 
 #[derive(Debug, Clone)]
 pub struct PayloadServiceBuilder {
     extra_data: Option<Bytes>,
-    signer: PrivateKeySigner,
+    extra_data_by_network: HashMap<String, Bytes>,
+    signers: Vec<PaymentSigner>,
+    signer_rotation: PaymentSignerRotation,
     fee_recipient: Address,
-    bid_tx: Sender<EthBuiltPayload>,
+    contract_payment_gas_limit: Option<u64>,
+    contract_payment_gas_limits: HashMap<Address, u64>,
+    max_gas_limit: Option<u64>,
+    blob_policy: BlobPolicy,
+    build_workers: Option<usize>,
+    bid_tx: Sender<FinalizedPayload>,
 }
 
-impl TryFrom<(&Config, Sender<EthBuiltPayload>)> for PayloadServiceBuilder {
-    type Error = Error;
+impl PayloadServiceBuilder {
+    pub async fn from_config(
+        value: &Config,
+        bid_tx: Sender<FinalizedPayload>,
+    ) -> Result<Self, Error> {
+        let signers = signers_from_config(value).await?;
+        let fee_recipient = value.fee_recipient.unwrap_or_else(|| signers[0].address());
+        Ok(Self {
+            extra_data: value.extra_data.clone(),
+            extra_data_by_network: value.extra_data_by_network.clone(),
+            signers,
+            signer_rotation: value.payment_signer_rotation,
+            fee_recipient,
+            contract_payment_gas_limit: value.contract_payment_gas_limit,
+            contract_payment_gas_limits: value.contract_payment_gas_limits.clone(),
+            max_gas_limit: value.max_gas_limit,
+            blob_policy: value.blob_policy,
+            build_workers: value.build_workers,
+            bid_tx,
+        })
+    }
 
-    fn try_from((value, bid_tx): (&Config, Sender<EthBuiltPayload>)) -> Result<Self, Self::Error> {
-        let signer = signer_from_mnemonic(&value.execution_mnemonic)?;
-        let fee_recipient = value.fee_recipient.unwrap_or_else(|| signer.address());
-        Ok(Self { extra_data: value.extra_data.clone(), signer, fee_recipient, bid_tx })
+    /// Addresses of the wallets authoring payment transactions
+    pub fn payment_addresses(&self) -> Vec<Address> {
+        self.signers.iter().map(|signer| signer.address()).collect()
     }
 }
 
@@ -54,34 +111,43 @@ where
         ctx: &BuilderContext<Node>,
         pool: Pool,
     ) -> eyre::Result<PayloadBuilderHandle<<Node::Types as NodeTypesWithEngine>::Engine>> {
-        let chain_id = ctx.chain_spec().chain().id();
+        let chain = ctx.chain_spec().chain();
+        let chain_id = chain.id();
         let conf = ctx.payload_builder_config();
 
-        let extradata = if let Some(extra_data) = self.extra_data {
-            extra_data
-        } else {
-            conf.extradata_bytes()
-        };
+        let extradata = self
+            .extra_data_by_network
+            .get(&chain.to_string())
+            .or_else(|| self.extra_data_by_network.get(&chain_id.to_string()))
+            .or(self.extra_data.as_ref())
+            .cloned()
+            .unwrap_or_else(|| conf.extradata_bytes());
         let payload_job_config = PayloadJobGeneratorConfig {
             extradata,
             _max_gas_limit: conf.max_gas_limit(),
             interval: conf.interval(),
             deadline: conf.deadline(),
-            max_payload_tasks: conf.max_payload_tasks(),
+            max_payload_tasks: self.build_workers.unwrap_or_else(|| conf.max_payload_tasks()),
         };
 
+        let payload_builder = PayloadBuilder::new(
+            self.bid_tx,
+            self.signers,
+            self.signer_rotation,
+            self.fee_recipient,
+            chain_id,
+            ctx.chain_spec().clone(),
+            self.contract_payment_gas_limit,
+            self.contract_payment_gas_limits,
+            self.max_gas_limit,
+            self.blob_policy,
+        )?;
         let payload_generator = PayloadJobGenerator::with_builder(
             ctx.provider().clone(),
             pool,
             ctx.task_executor().clone(),
             payload_job_config,
-            PayloadBuilder::new(
-                self.bid_tx,
-                self.signer,
-                self.fee_recipient,
-                chain_id,
-                ctx.chain_spec().clone(),
-            ),
+            payload_builder,
         );
 
         let (payload_service, payload_builder) =
@@ -93,3 +159,21 @@ where
         Ok(payload_builder)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_signers_require_a_key_source() {
+        let config: Config = serde_json::from_value(serde_json::json!({})).unwrap();
+        let result = signers_from_config(&config).await;
+        assert!(matches!(result, Err(Error::InvalidConfig(..))));
+
+        let config: Config = serde_json::from_value(serde_json::json!({
+            "execution_mnemonic": "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        }))
+        .unwrap();
+        assert_eq!(signers_from_config(&config).await.unwrap().len(), 1);
+    }
+}
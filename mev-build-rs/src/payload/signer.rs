@@ -0,0 +1,37 @@
+use alloy::signers::{
+    aws::AwsSigner, local::PrivateKeySigner, Error as SignerError, Signature, Signer, SignerSync,
+};
+use reth::primitives::revm_primitives::{Address, B256};
+
+/// Signs the payment transaction of each bid
+#[derive(Debug, Clone)]
+pub enum PaymentSigner {
+    /// Key held in memory by the builder
+    Local(PrivateKeySigner),
+    /// Key held by AWS KMS, so it never lives on the builder host
+    Kms(AwsSigner),
+}
+
+impl PaymentSigner {
+    pub fn address(&self) -> Address {
+        match self {
+            Self::Local(signer) => signer.address(),
+            Self::Kms(signer) => signer.address(),
+        }
+    }
+
+    pub async fn sign_hash(&self, hash: &B256) -> Result<Signature, SignerError> {
+        match self {
+            Self::Local(signer) => signer.sign_hash_sync(hash),
+            Self::Kms(signer) => signer.sign_hash(hash).await,
+        }
+    }
+}
+
+pub async fn kms_signer(key_id: String) -> Result<PaymentSigner, SignerError> {
+    let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+    let client = aws_sdk_kms::Client::new(&config);
+    // NOTE: the chain id is set on the payment transaction directly, so is not needed here
+    let signer = AwsSigner::new(client, key_id, None).await.map_err(SignerError::other)?;
+    Ok(PaymentSigner::Kms(signer))
+}
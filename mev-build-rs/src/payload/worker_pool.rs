@@ -0,0 +1,157 @@
+use std::{
+    cmp::Ordering,
+    collections::BinaryHeap,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Condvar, Mutex},
+    thread,
+};
+use tracing::{error, trace};
+
+/// Order in which queued builds are run, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BuildPriority {
+    /// Timestamp of the payload being built, so auctions ending sooner are built for first
+    pub timestamp: u64,
+    /// Builds already completed for the auction, so auctions for the same slot take turns
+    pub builds: u64,
+}
+
+type Work = Box<dyn FnOnce() + Send>;
+
+struct Task {
+    priority: BuildPriority,
+    // NOTE: breaks ties in the order tasks were queued
+    sequence: u64,
+    work: Work,
+}
+
+impl Task {
+    fn key(&self) -> (BuildPriority, u64) {
+        (self.priority, self.sequence)
+    }
+}
+
+impl PartialEq for Task {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Task {}
+
+impl PartialOrd for Task {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Task {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // NOTE: reversed so the heap yields the lowest key first
+        other.key().cmp(&self.key())
+    }
+}
+
+#[derive(Default)]
+struct Queue {
+    tasks: BinaryHeap<Task>,
+    next_sequence: u64,
+}
+
+#[derive(Default)]
+struct Shared {
+    queue: Mutex<Queue>,
+    available: Condvar,
+}
+
+/// Dedicated threads running payload builds, so builds for concurrent auctions do not contend
+/// with the node's other blocking tasks and are run in order of their priority
+#[derive(Clone)]
+pub struct WorkerPool {
+    shared: Arc<Shared>,
+}
+
+impl std::fmt::Debug for WorkerPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkerPool").finish_non_exhaustive()
+    }
+}
+
+impl WorkerPool {
+    pub fn new(workers: usize) -> Self {
+        let shared = Arc::new(Shared::default());
+        for index in 0..workers.max(1) {
+            let shared = shared.clone();
+            let spawned = thread::Builder::new()
+                .name(format!("mev-builder-worker-{index}"))
+                .spawn(move || run_worker(&shared));
+            if let Err(err) = spawned {
+                error!(%err, index, "could not spawn payload build worker");
+            }
+        }
+        Self { shared }
+    }
+
+    /// Queues `work` to run on the next free worker, ahead of any work of a higher `priority`
+    pub fn spawn(&self, priority: BuildPriority, work: impl FnOnce() + Send + 'static) {
+        let mut queue = self.shared.queue.lock().expect("can lock");
+        let sequence = queue.next_sequence;
+        queue.next_sequence += 1;
+        queue.tasks.push(Task { priority, sequence, work: Box::new(work) });
+        trace!(?priority, queued = queue.tasks.len(), "queued payload build");
+        drop(queue);
+        self.shared.available.notify_one();
+    }
+}
+
+fn run_worker(shared: &Shared) {
+    loop {
+        let task = {
+            let mut queue = shared.queue.lock().expect("can lock");
+            loop {
+                if let Some(task) = queue.tasks.pop() {
+                    break task
+                }
+                queue = shared.available.wait(queue).expect("can lock");
+            }
+        };
+        // NOTE: a panicking build would otherwise take its worker down with it, leaving the pool
+        // short of a thread for the rest of the process
+        if let Err(panic) = panic::catch_unwind(AssertUnwindSafe(task.work)) {
+            let reason = panic
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("unknown");
+            error!(priority = ?task.priority, reason, "payload build panicked");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_order() {
+        let mut tasks = BinaryHeap::new();
+        let priorities = [(2, 0), (1, 3), (1, 1), (1, 1)];
+        for (sequence, (timestamp, builds)) in priorities.into_iter().enumerate() {
+            let priority = BuildPriority { timestamp, builds };
+            tasks.push(Task { priority, sequence: sequence as u64, work: Box::new(|| {}) });
+        }
+        let order =
+            std::iter::from_fn(|| tasks.pop()).map(|task| task.sequence).collect::<Vec<_>>();
+        assert_eq!(order, vec![2, 3, 1, 0]);
+    }
+
+    #[test]
+    fn test_worker_survives_panic() {
+        let pool = WorkerPool::new(1);
+        let priority = BuildPriority { timestamp: 0, builds: 0 };
+        pool.spawn(priority, || panic!("build failed"));
+        let (tx, rx) = std::sync::mpsc::channel();
+        pool.spawn(priority, move || tx.send(()).unwrap());
+        rx.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+    }
+}
@@ -0,0 +1,130 @@
+use ethereum_consensus::primitives::{BlsPublicKey, Slot};
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    proc_macros::rpc,
+    types::{error::INTERNAL_ERROR_CODE, ErrorObject, ErrorObjectOwned},
+};
+use mev_rs::admin::Introspect;
+use reth::{
+    payload::PayloadId,
+    primitives::revm_primitives::{Address, B256, U256},
+};
+use serde::Serialize;
+use serde_json::json;
+use tokio::sync::{mpsc::Sender, oneshot};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenAuction {
+    pub slot: Slot,
+    pub payload_id: PayloadId,
+    pub parent_hash: B256,
+    pub proposer_public_key: BlsPublicKey,
+    pub proposer_fee_recipient: Address,
+    pub gas_limit: u64,
+    pub relays: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Submission {
+    pub slot: Slot,
+    pub block_number: u64,
+    pub block_hash: B256,
+    pub parent_hash: B256,
+    pub value: U256,
+    /// Relays which accepted the submission
+    pub relays: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayStatus {
+    pub relay: String,
+    pub successful_submissions: u64,
+    pub failed_submissions: u64,
+    pub last_submitted_slot: Option<Slot>,
+    pub last_error: Option<String>,
+    /// Slots bid on at the relay whose delivered payload has been checked
+    pub slots_bid: u64,
+    /// Slots the relay delivered the builder's payload for
+    pub slots_won: u64,
+}
+
+/// Requests for the auctioneer to report on its state
+#[derive(Debug)]
+pub enum AuctioneerQuery {
+    OpenAuctions(oneshot::Sender<Vec<OpenAuction>>),
+    LastSubmissions(oneshot::Sender<Vec<Submission>>),
+    RelayStatus(oneshot::Sender<Vec<RelayStatus>>),
+}
+
+/// Inspect the state of the builder's auctions via the `mev` RPC namespace
+#[rpc(server, namespace = "mev")]
+pub trait MevApi {
+    /// Auctions the builder is currently building for
+    #[method(name = "openAuctions")]
+    async fn open_auctions(&self) -> RpcResult<Vec<OpenAuction>>;
+
+    /// Most recent submissions made to relays, newest first
+    #[method(name = "lastSubmissions")]
+    async fn last_submissions(&self) -> RpcResult<Vec<Submission>>;
+
+    /// Submission outcomes for each configured relay
+    #[method(name = "relayStatus")]
+    async fn relay_status(&self) -> RpcResult<Vec<RelayStatus>>;
+}
+
+#[derive(Clone)]
+pub struct MevRpc {
+    auctioneer: Sender<AuctioneerQuery>,
+}
+
+impl MevRpc {
+    pub fn new(auctioneer: Sender<AuctioneerQuery>) -> Self {
+        Self { auctioneer }
+    }
+
+    async fn query<T>(
+        &self,
+        query: impl FnOnce(oneshot::Sender<T>) -> AuctioneerQuery,
+    ) -> RpcResult<T> {
+        let (tx, rx) = oneshot::channel();
+        self.auctioneer.send(query(tx)).await.map_err(|_| auctioneer_unavailable())?;
+        rx.await.map_err(|_| auctioneer_unavailable())
+    }
+}
+
+fn auctioneer_unavailable() -> ErrorObjectOwned {
+    ErrorObject::owned(INTERNAL_ERROR_CODE, "auctioneer is not available", None::<()>)
+}
+
+#[async_trait]
+impl MevApiServer for MevRpc {
+    async fn open_auctions(&self) -> RpcResult<Vec<OpenAuction>> {
+        self.query(AuctioneerQuery::OpenAuctions).await
+    }
+
+    async fn last_submissions(&self) -> RpcResult<Vec<Submission>> {
+        self.query(AuctioneerQuery::LastSubmissions).await
+    }
+
+    async fn relay_status(&self) -> RpcResult<Vec<RelayStatus>> {
+        self.query(AuctioneerQuery::RelayStatus).await
+    }
+}
+
+#[async_trait]
+impl Introspect for MevRpc {
+    async fn state_summary(&self) -> serde_json::Value {
+        // NOTE: any part the auctioneer could not report on is left as `null`
+        let open_auctions = self.query(AuctioneerQuery::OpenAuctions).await.ok();
+        let last_submissions = self.query(AuctioneerQuery::LastSubmissions).await.ok();
+        let relays = self.query(AuctioneerQuery::RelayStatus).await.ok();
+        json!({
+            "open_auctions": open_auctions,
+            "last_submissions": last_submissions,
+            "relays": relays,
+        })
+    }
+}
@@ -1,10 +1,20 @@
 use crate::{
-    auctioneer::{Config as AuctioneerConfig, Service as Auctioneer},
-    bidder::{Config as BidderConfig, Service as Bidder},
+    auctioneer::{
+        Config as AuctioneerConfig, DeliveryTracker, Service as Auctioneer, SyncGate, SyncWatcher,
+        DEFAULT_MAX_HEAD_LAG_SLOTS,
+    },
+    bidder::{
+        BalanceWatcher, Config as BidderConfig, Service as Bidder, SubsidySwitch, TopBidFeed,
+    },
+    exex::{chain_updates, ChainUpdate},
+    ledger::Ledger,
     node::BuilderNode,
     payload::{
-        attributes::BuilderPayloadBuilderAttributes, service_builder::PayloadServiceBuilder,
+        attributes::BuilderPayloadBuilderAttributes,
+        builder::{BlobPolicy, FinalizedPayload, PaymentSignerRotation},
+        service_builder::PayloadServiceBuilder,
     },
+    rpc::{AuctioneerQuery, MevApiServer, MevRpc},
 };
 use ethereum_consensus::{
     clock::SystemClock,
@@ -13,7 +23,7 @@ use ethereum_consensus::{
     state_transition::Context,
 };
 use eyre::OptionExt;
-use mev_rs::{get_genesis_time, Error};
+use mev_rs::{admin, config::AdminConfig, get_genesis_time, Error};
 use reth::{
     api::EngineTypes,
     builder::{NodeBuilder, WithLaunchContext},
@@ -24,8 +34,8 @@ use reth::{
 };
 use reth_db::DatabaseEnv;
 use reth_node_ethereum::node::EthereumAddOns;
-use serde::Deserialize;
-use std::{path::PathBuf, sync::Arc};
+use serde::{de::Error as _, Deserialize, Deserializer};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 use tokio::sync::{
     broadcast::{self, Sender},
     mpsc,
@@ -33,13 +43,78 @@ use tokio::sync::{
 use tokio_stream::StreamExt;
 use tracing::warn;
 
+#[cfg(not(feature = "minimal-preset"))]
+use ethereum_consensus::bellatrix::mainnet::MAX_EXTRA_DATA_BYTES;
+#[cfg(feature = "minimal-preset")]
+use ethereum_consensus::bellatrix::minimal::MAX_EXTRA_DATA_BYTES;
+
 pub const DEFAULT_COMPONENT_CHANNEL_SIZE: usize = 16;
 
 #[derive(Deserialize, Debug, Default, Clone)]
 pub struct BuilderConfig {
     pub fee_recipient: Option<Address>,
+    /// Extra data written into built payloads, unless overridden for the network in
+    /// `extra_data_by_network`
+    #[serde(default, deserialize_with = "deserialize_extra_data")]
     pub extra_data: Option<Bytes>,
+    /// Extra data for particular networks, keyed by chain name (e.g. `holesky`) or chain id
+    #[serde(default, deserialize_with = "deserialize_extra_data_by_network")]
+    pub extra_data_by_network: HashMap<String, Bytes>,
+    /// Mnemonic given inline, as `file:/path` or as `env:VAR`
+    #[serde(default, deserialize_with = "mev_rs::serde::secret::deserialize")]
     pub execution_mnemonic: String,
+    /// Keys held by AWS KMS to author payment transactions, instead of `execution_mnemonic`
+    #[serde(default)]
+    pub payment_kms_key_ids: Vec<String>,
+    /// Number of accounts derived from `execution_mnemonic` to author payment transactions
+    pub payment_signer_count: Option<u32>,
+    /// How to pick between the payment signers for each build
+    #[serde(default)]
+    pub payment_signer_rotation: PaymentSignerRotation,
+    /// Gas limit of the payment transaction when the proposer's fee recipient is a contract
+    pub contract_payment_gas_limit: Option<u64>,
+    /// Overrides of `contract_payment_gas_limit` for particular fee recipients
+    #[serde(default)]
+    pub contract_payment_gas_limits: HashMap<Address, u64>,
+    /// Gas limit to target for built blocks when below the proposer's registered limit
+    pub max_gas_limit: Option<u64>,
+    /// Limits on the blobs included in built payloads
+    #[serde(default)]
+    pub blob_policy: BlobPolicy,
+    /// Number of dedicated threads running payload builds, shared by concurrent auctions, if
+    /// missing defaults to the node's `--builder.max-tasks`
+    pub build_workers: Option<usize>,
+}
+
+fn validate_extra_data<E: serde::de::Error>(extra_data: &Bytes) -> Result<(), E> {
+    if extra_data.len() > MAX_EXTRA_DATA_BYTES {
+        return Err(E::custom(format!(
+            "extra data of {} bytes exceeds the maximum of {MAX_EXTRA_DATA_BYTES} bytes",
+            extra_data.len()
+        )))
+    }
+    Ok(())
+}
+
+fn deserialize_extra_data<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Bytes>, D::Error> {
+    let extra_data = Option::<Bytes>::deserialize(deserializer)?;
+    if let Some(extra_data) = &extra_data {
+        validate_extra_data(extra_data)?;
+    }
+    Ok(extra_data)
+}
+
+fn deserialize_extra_data_by_network<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<HashMap<String, Bytes>, D::Error> {
+    let extra_data = HashMap::<String, Bytes>::deserialize(deserializer)?;
+    for (network, extra_data) in &extra_data {
+        validate_extra_data(extra_data)
+            .map_err(|err: D::Error| D::Error::custom(format!("network {network}: {err}")))?;
+    }
+    Ok(extra_data)
 }
 
 #[derive(Deserialize, Debug, Default, Clone)]
@@ -50,6 +125,8 @@ pub struct Config {
 
     // Used to get genesis time, if one can't be found without a network call
     pub beacon_node_url: Option<String>,
+    /// Serve the admin API if provided
+    pub admin: Option<AdminConfig>,
 }
 
 pub struct Services<
@@ -59,8 +136,20 @@ pub struct Services<
     >,
 > {
     pub auctioneer: Auctioneer<Engine>,
+    pub deliveries: DeliveryTracker,
+    pub top_bid_feed: Option<TopBidFeed>,
     pub clock: SystemClock,
     pub clock_tx: Sender<ClockMessage>,
+    pub subsidies: SubsidySwitch,
+    pub sync: SyncWatcherConfig,
+}
+
+/// Parameters for the `SyncWatcher`, which needs the node's provider to be spawned
+pub struct SyncWatcherConfig {
+    pub gate: SyncGate,
+    pub genesis_time: u64,
+    pub seconds_per_slot: u64,
+    pub max_head_lag_slots: Slot,
 }
 
 pub async fn construct_services<
@@ -73,7 +162,9 @@ pub async fn construct_services<
     config: Config,
     task_executor: TaskExecutor,
     payload_builder: PayloadBuilderHandle<Engine>,
-    bid_rx: mpsc::Receiver<EthBuiltPayload>,
+    bid_rx: mpsc::Receiver<FinalizedPayload>,
+    query_rx: mpsc::Receiver<AuctioneerQuery>,
+    chain_rx: mpsc::Receiver<ChainUpdate>,
 ) -> Result<Services<Engine>, Error> {
     let context = Arc::new(Context::try_from(network)?);
 
@@ -84,17 +175,47 @@ pub async fn construct_services<
     let (clock_tx, clock_rx) = broadcast::channel(DEFAULT_COMPONENT_CHANNEL_SIZE);
 
     let bidder = Bidder::new(task_executor, config.bidder);
+    let subsidies = bidder.subsidies();
+    let win_rates = bidder.win_rates();
+    let clearing_history = bidder.clearing_history();
+    let ledger = config.auctioneer.ledger_path.clone().map(Ledger::new);
+    let max_head_lag_slots =
+        config.auctioneer.max_head_lag_slots.unwrap_or(DEFAULT_MAX_HEAD_LAG_SLOTS);
+    let seconds_per_slot = context.seconds_per_slot;
+    let top_bid_feed = bidder.top_bid_feed_url().map(|url| {
+        TopBidFeed::new(url, config.auctioneer.secret_key.public_key(), bidder.top_bids())
+    });
+    // NOTE: unbounded so no bid is lost from the ledger; the tracker drains it as bids arrive
+    let (delivery_tx, delivery_rx) = mpsc::unbounded_channel();
     let auctioneer = Auctioneer::new(
         clock_rx,
         payload_builder,
         bidder,
         bid_rx,
+        query_rx,
+        chain_rx,
+        delivery_tx,
         config.auctioneer,
         context,
         genesis_time,
     );
+    let deliveries = DeliveryTracker::new(
+        auctioneer.relays().to_vec(),
+        ledger,
+        win_rates,
+        clearing_history,
+        delivery_rx,
+        clock_tx.subscribe(),
+    );
 
-    Ok(Services { auctioneer, clock, clock_tx })
+    let sync = SyncWatcherConfig {
+        gate: auctioneer.sync_gate(),
+        genesis_time,
+        seconds_per_slot,
+        max_head_lag_slots,
+    };
+
+    Ok(Services { auctioneer, deliveries, top_bid_feed, clock, clock_tx, subsidies, sync })
 }
 
 fn custom_network_from_config_directory(path: PathBuf) -> Network {
@@ -108,13 +229,31 @@ pub async fn launch(
     custom_chain_config_directory: Option<PathBuf>,
     config: Config,
 ) -> eyre::Result<()> {
+    config.bidder.validate()?;
+
     let (bid_tx, bid_rx) = mpsc::channel(DEFAULT_COMPONENT_CHANNEL_SIZE);
-    let payload_builder = PayloadServiceBuilder::try_from((&config.builder, bid_tx))?;
+    let payload_builder = PayloadServiceBuilder::from_config(&config.builder, bid_tx).await?;
+    let payment_addresses = payload_builder.payment_addresses();
+    let (query_tx, query_rx) = mpsc::channel(DEFAULT_COMPONENT_CHANNEL_SIZE);
+    let (chain_tx, chain_rx) = mpsc::channel(DEFAULT_COMPONENT_CHANNEL_SIZE);
+    let mev_rpc = MevRpc::new(query_tx);
+    if let Some(admin_config) = config.admin.as_ref() {
+        let router = admin::router().with_state(mev_rpc.clone());
+        std::mem::drop(admin::spawn(admin_config, router)?);
+    }
 
     let handle = node_builder
         .with_types::<BuilderNode>()
         .with_components(BuilderNode::components_with(payload_builder))
         .with_add_ons(EthereumAddOns::default())
+        .extend_rpc_modules(move |ctx| {
+            ctx.modules.merge_configured(mev_rpc.into_rpc())?;
+            Ok(())
+        })
+        .install_exex(
+            "mev-builder/chain",
+            move |ctx| async move { Ok(chain_updates(ctx, chain_tx)) },
+        )
         .launch()
         .await?;
 
@@ -136,12 +275,47 @@ pub async fn launch(
         custom_network_from_config_directory(path)
     };
 
+    let bidder_config = config.bidder.clone();
     let task_executor = handle.node.task_executor.clone();
     let payload_builder = handle.node.payload_builder.clone();
-    let Services { auctioneer, clock, clock_tx } =
-        construct_services(network, config, task_executor, payload_builder, bid_rx).await?;
+    let Services { auctioneer, deliveries, top_bid_feed, clock, clock_tx, subsidies, sync } =
+        construct_services(
+            network,
+            config,
+            task_executor,
+            payload_builder,
+            bid_rx,
+            query_rx,
+            chain_rx,
+        )
+        .await?;
 
+    let sync_watcher = SyncWatcher::new(
+        handle.node.provider.clone(),
+        sync.genesis_time,
+        sync.seconds_per_slot,
+        sync.max_head_lag_slots,
+        sync.gate,
+        clock_tx.subscribe(),
+    );
+    handle.node.task_executor.spawn("mev-builder/sync-watcher", sync_watcher.spawn());
     handle.node.task_executor.spawn_critical_blocking("mev-builder/auctioneer", auctioneer.spawn());
+    handle.node.task_executor.spawn("mev-builder/deliveries", deliveries.spawn());
+    if let Some(top_bid_feed) = top_bid_feed {
+        handle.node.task_executor.spawn("mev-builder/top-bid-feed", top_bid_feed.spawn());
+    }
+    let subsidy_wei = bidder_config.max_subsidy_wei();
+    if !subsidy_wei.is_zero() || bidder_config.balance_warning_threshold_wei.is_some() {
+        let balance_watcher = BalanceWatcher::new(
+            handle.node.provider.clone(),
+            payment_addresses,
+            subsidy_wei,
+            bidder_config.balance_warning_threshold_wei,
+            subsidies,
+            clock_tx.subscribe(),
+        );
+        handle.node.task_executor.spawn("mev-builder/balance-watcher", balance_watcher.spawn());
+    }
     handle.node.task_executor.spawn_critical("mev-builder/clock", async move {
         let mut slots = clock.clone().into_stream();
 
@@ -184,3 +358,32 @@ pub enum ClockMessage {
     NewSlot(Slot),
     NewEpoch(Epoch),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extra_data_length() {
+        let at_limit = format!("0x{}", "ab".repeat(MAX_EXTRA_DATA_BYTES));
+        let over_limit = format!("0x{}", "ab".repeat(MAX_EXTRA_DATA_BYTES + 1));
+
+        let config: BuilderConfig = serde_json::from_value(serde_json::json!({
+            "extra_data": at_limit,
+            "extra_data_by_network": { "holesky": "0x01" },
+        }))
+        .unwrap();
+        assert_eq!(config.extra_data.unwrap().len(), MAX_EXTRA_DATA_BYTES);
+        assert_eq!(config.extra_data_by_network["holesky"].as_ref(), &[1]);
+
+        let result = serde_json::from_value::<BuilderConfig>(
+            serde_json::json!({ "extra_data": over_limit }),
+        );
+        assert!(result.is_err());
+
+        let result = serde_json::from_value::<BuilderConfig>(serde_json::json!({
+            "extra_data_by_network": { "mainnet": over_limit },
+        }));
+        assert!(result.unwrap_err().to_string().contains("network mainnet"));
+    }
+}
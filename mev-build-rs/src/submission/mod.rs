@@ -0,0 +1,244 @@
+//! Bridge to hand signed submissions from the builder to a standalone service submitting them
+//! to relays, so submissions can be made from hosts close to the relays
+mod service;
+
+pub use service::{Config, Service};
+
+use ethereum_consensus::primitives::BlsPublicKey;
+use mev_rs::types::SignedBidSubmission;
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, fmt, io, net::SocketAddr, path::PathBuf, time::Duration};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    net::{TcpStream, UnixStream},
+    time::timeout,
+};
+use tracing::warn;
+
+// Time to wait for the submission service to report the outcome of a submission
+const SUBMISSION_TIMEOUT: Duration = Duration::from_secs(4);
+
+const UNIX_PREFIX: &str = "unix:";
+
+/// Address the submission service listens on, if not configured
+pub const DEFAULT_LISTEN_ADDRESS: &str = "127.0.0.1:28600";
+
+/// Address of the submission service, given as `host:port` for TCP or `unix:/path` for a Unix
+/// domain socket
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "String")]
+pub enum BridgeEndpoint {
+    Tcp(String),
+    Unix(PathBuf),
+}
+
+impl From<String> for BridgeEndpoint {
+    fn from(value: String) -> Self {
+        match value.strip_prefix(UNIX_PREFIX) {
+            Some(path) => Self::Unix(PathBuf::from(path)),
+            None => Self::Tcp(value.strip_prefix("tcp://").unwrap_or(&value).to_string()),
+        }
+    }
+}
+
+impl fmt::Display for BridgeEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(address) => write!(f, "{address}"),
+            Self::Unix(path) => write!(f, "{UNIX_PREFIX}{}", path.display()),
+        }
+    }
+}
+
+trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Stream for T {}
+
+impl BridgeEndpoint {
+    // Whether the endpoint is only reachable from this host
+    fn is_local(&self) -> bool {
+        match self {
+            Self::Tcp(address) => match address.parse::<SocketAddr>() {
+                Ok(address) => address.ip().is_loopback(),
+                Err(_) => address.rsplit_once(':').is_some_and(|(host, _)| host == "localhost"),
+            },
+            Self::Unix(_) => true,
+        }
+    }
+
+    async fn connect(&self) -> io::Result<Box<dyn Stream>> {
+        let stream: Box<dyn Stream> = match self {
+            Self::Tcp(address) => {
+                let stream = TcpStream::connect(address).await?;
+                stream.set_nodelay(true)?;
+                Box::new(stream)
+            }
+            Self::Unix(path) => Box::new(UnixStream::connect(path).await?),
+        };
+        Ok(stream)
+    }
+}
+
+/// Settings for handing submissions to a standalone submission service
+#[derive(Debug, Clone, Deserialize)]
+pub struct BridgeConfig {
+    /// Address of the submission service, as `host:port` or `unix:/path`
+    pub endpoint: BridgeEndpoint,
+    /// Secret shared with the submission service to authenticate the builder, given inline, as
+    /// `file:/path` or as `env:VAR`
+    ///
+    /// The secret is sent in the clear, so TCP endpoints are only safe on a trusted network
+    #[serde(deserialize_with = "mev_rs::serde::secret::deserialize")]
+    pub secret: String,
+}
+
+/// Sent by the builder as the first message on each connection, as a single line of JSON
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Handshake {
+    pub secret: String,
+}
+
+/// A relay to submit to, identified by its public key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayTarget {
+    pub public_key: BlsPublicKey,
+    /// Whether the submission should be made with cancellations
+    pub cancellations: bool,
+}
+
+/// Sent by the builder for each submission, as a single line of JSON
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmissionRequest<'a> {
+    pub relays: Vec<RelayTarget>,
+    pub submission: Cow<'a, SignedBidSubmission>,
+}
+
+/// Sent by the submission service in reply to each request, as a single line of JSON
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SubmissionResponse {
+    /// Error from each relay in the order of the request, or `None` if the relay accepted the
+    /// submission
+    pub errors: Vec<Option<String>>,
+}
+
+// Writes `message` to `stream` as a single line of JSON
+async fn write_message<T: Serialize>(
+    stream: &mut (impl AsyncWrite + Unpin),
+    message: &T,
+) -> io::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    stream.write_all(&line).await?;
+    stream.flush().await
+}
+
+// Reads the next line of JSON from `stream`, or `None` if it was closed
+async fn read_message<T: for<'de> Deserialize<'de>>(
+    stream: &mut (impl AsyncBufReadExt + Unpin),
+    line: &mut String,
+) -> io::Result<Option<T>> {
+    line.clear();
+    if stream.read_line(line).await? == 0 {
+        return Ok(None)
+    }
+    Ok(Some(serde_json::from_str(line)?))
+}
+
+/// Client of a remote submission service, keeping a connection open across submissions
+pub struct SubmissionBridge {
+    config: BridgeConfig,
+    connection: Option<BufReader<Box<dyn Stream>>>,
+    line: String,
+}
+
+impl SubmissionBridge {
+    pub fn new(config: BridgeConfig) -> Self {
+        Self { config, connection: None, line: String::new() }
+    }
+
+    async fn connect(&self) -> io::Result<BufReader<Box<dyn Stream>>> {
+        let mut stream = self.config.endpoint.connect().await?;
+        let handshake = Handshake { secret: self.config.secret.clone() };
+        write_message(&mut stream, &handshake).await?;
+        Ok(BufReader::new(stream))
+    }
+
+    async fn exchange(
+        &mut self,
+        request: &SubmissionRequest<'_>,
+    ) -> io::Result<Option<SubmissionResponse>> {
+        if self.connection.is_none() {
+            self.connection = Some(self.connect().await?);
+        }
+        let connection = self.connection.as_mut().expect("is connected");
+        write_message(connection, request).await?;
+        read_message(connection, &mut self.line).await
+    }
+
+    /// Submits `submission` to `relays` through the submission service, returning the outcome
+    /// at each relay in the same order
+    pub async fn submit(
+        &mut self,
+        relays: Vec<RelayTarget>,
+        submission: &SignedBidSubmission,
+    ) -> io::Result<Vec<Result<(), String>>> {
+        let count = relays.len();
+        let request = SubmissionRequest { relays, submission: Cow::Borrowed(submission) };
+        let response = match timeout(SUBMISSION_TIMEOUT, self.exchange(&request)).await {
+            Ok(Ok(Some(response))) => Ok(response),
+            Ok(Ok(None)) => {
+                Err(io::Error::new(io::ErrorKind::UnexpectedEof, "submission service hung up"))
+            }
+            Ok(Err(err)) => Err(err),
+            Err(_) => {
+                Err(io::Error::new(io::ErrorKind::TimedOut, "submission service did not respond"))
+            }
+        }
+        .inspect_err(|_| {
+            // NOTE: the connection is left in an unknown state, so reconnect on the next submission
+            self.connection = None;
+        })?;
+        if response.errors.len() != count {
+            warn!(
+                expected = count,
+                received = response.errors.len(),
+                "submission service replied with unexpected number of outcomes"
+            );
+        }
+        let mut errors = response.errors.into_iter();
+        Ok((0..count)
+            .map(|_| match errors.next() {
+                Some(None) => Ok(()),
+                Some(Some(err)) => Err(err),
+                None => Err("missing outcome from submission service".to_string()),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_endpoint() {
+        let endpoint = BridgeEndpoint::from("unix:/tmp/mev.sock".to_string());
+        assert_eq!(endpoint, BridgeEndpoint::Unix(PathBuf::from("/tmp/mev.sock")));
+        assert_eq!(endpoint.to_string(), "unix:/tmp/mev.sock");
+
+        let endpoint = BridgeEndpoint::from("tcp://10.0.0.2:28600".to_string());
+        assert_eq!(endpoint, BridgeEndpoint::Tcp("10.0.0.2:28600".to_string()));
+        assert_eq!(BridgeEndpoint::from("10.0.0.2:28600".to_string()), endpoint);
+    }
+
+    #[test]
+    fn test_endpoint_is_local() {
+        for endpoint in ["127.0.0.1:28600", "[::1]:28600", "localhost:28600", "unix:/tmp/mev.sock"]
+        {
+            assert!(BridgeEndpoint::from(endpoint.to_string()).is_local(), "{endpoint}");
+        }
+        for endpoint in ["10.0.0.2:28600", "0.0.0.0:28600", "relay.example.com:28600"] {
+            assert!(!BridgeEndpoint::from(endpoint.to_string()).is_local(), "{endpoint}");
+        }
+    }
+}
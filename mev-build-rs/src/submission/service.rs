@@ -0,0 +1,227 @@
+use crate::submission::{
+    read_message, write_message, BridgeEndpoint, Handshake, RelayTarget, Stream, SubmissionRequest,
+    SubmissionResponse, DEFAULT_LISTEN_ADDRESS,
+};
+use ethereum_consensus::primitives::BlsPublicKey;
+use futures_util::future::join_all;
+use mev_rs::{
+    config::RelayClientConfig,
+    relay::{create_relays, parse_relay_endpoints},
+    types::SignedBidSubmission,
+    BlindedBlockRelayer, Relay,
+};
+use serde::Deserialize;
+use std::{collections::HashMap, fs, io, sync::Arc};
+use subtle::ConstantTimeEq;
+use tokio::{
+    io::BufReader,
+    net::{TcpListener, UnixListener},
+};
+use tracing::{debug, info, warn};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Config {
+    /// Where to accept submissions from builders, as `host:port` or `unix:/path`, if missing
+    /// defaults to `DEFAULT_LISTEN_ADDRESS`
+    ///
+    /// Builders send `secret` in the clear, so only listen beyond loopback on a trusted network
+    #[serde(default = "default_listen")]
+    pub listen: BridgeEndpoint,
+    /// Secret builders must present to submit, given inline, as `file:/path` or as `env:VAR`
+    #[serde(deserialize_with = "mev_rs::serde::secret::deserialize")]
+    pub secret: String,
+    /// List of relays to submit to, matched to the builder's relays by public key
+    pub relays: Vec<String>,
+    /// Settings for the HTTP client used to submit to relays
+    #[serde(default)]
+    pub relay_client: RelayClientConfig,
+}
+
+fn default_listen() -> BridgeEndpoint {
+    BridgeEndpoint::Tcp(DEFAULT_LISTEN_ADDRESS.to_string())
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    Unix(UnixListener),
+}
+
+impl Listener {
+    async fn bind(endpoint: &BridgeEndpoint) -> io::Result<Self> {
+        let listener = match endpoint {
+            BridgeEndpoint::Tcp(address) => Self::Tcp(TcpListener::bind(address).await?),
+            BridgeEndpoint::Unix(path) => {
+                // NOTE: remove any socket left behind by a previous run
+                if path.exists() {
+                    fs::remove_file(path)?;
+                }
+                Self::Unix(UnixListener::bind(path)?)
+            }
+        };
+        Ok(listener)
+    }
+
+    async fn accept(&self) -> io::Result<Box<dyn Stream>> {
+        let stream: Box<dyn Stream> = match self {
+            Self::Tcp(listener) => {
+                let (stream, _) = listener.accept().await?;
+                stream.set_nodelay(true)?;
+                Box::new(stream)
+            }
+            Self::Unix(listener) => Box::new(listener.accept().await?.0),
+        };
+        Ok(stream)
+    }
+}
+
+/// Standalone service submitting the payloads handed over by builders to relays
+pub struct Service {
+    config: Config,
+    relays: Arc<HashMap<BlsPublicKey, Relay>>,
+}
+
+impl Service {
+    pub fn new(config: Config) -> Self {
+        let relays = create_relays(parse_relay_endpoints(&config.relays), &config.relay_client)
+            .into_iter()
+            .map(|relay| (relay.public_key.clone(), relay))
+            .collect();
+        Self { config, relays: Arc::new(relays) }
+    }
+
+    pub async fn spawn(self) -> io::Result<()> {
+        if self.relays.is_empty() {
+            warn!("no valid relays provided; submissions will fail");
+        }
+        if !self.config.listen.is_local() {
+            warn!(endpoint = %self.config.listen, "accepting submissions from outside this host; the secret is sent in the clear so only listen on a trusted network");
+        }
+        let listener = Listener::bind(&self.config.listen).await?;
+        info!(endpoint = %self.config.listen, relays = self.relays.len(), "accepting submissions");
+        self.run(listener).await
+    }
+
+    async fn run(self, listener: Listener) -> io::Result<()> {
+        loop {
+            match listener.accept().await {
+                Ok(stream) => {
+                    let relays = self.relays.clone();
+                    let secret = self.config.secret.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = serve(stream, relays, &secret).await {
+                            warn!(%err, "builder connection failed");
+                        }
+                    });
+                }
+                Err(err) => warn!(%err, "could not accept builder connection"),
+            }
+        }
+    }
+}
+
+async fn submit(
+    relays: &HashMap<BlsPublicKey, Relay>,
+    target: &RelayTarget,
+    submission: &SignedBidSubmission,
+) -> Option<String> {
+    let Some(relay) = relays.get(&target.public_key) else {
+        return Some(format!("unknown relay {}", target.public_key))
+    };
+    let relay = relay.clone().with_cancellations(target.cancellations);
+    match relay.submit_bid(submission).await {
+        Ok(()) => None,
+        Err(err) => {
+            warn!(%err, ?relay, "could not submit payload");
+            Some(err.to_string())
+        }
+    }
+}
+
+// Handles the requests from a single builder connection until it closes, once the builder
+// presents the shared secret
+async fn serve(
+    stream: Box<dyn Stream>,
+    relays: Arc<HashMap<BlsPublicKey, Relay>>,
+    secret: &str,
+) -> io::Result<()> {
+    let mut stream = BufReader::new(stream);
+    let mut line = String::new();
+    let Some(handshake) = read_message::<Handshake>(&mut stream, &mut line).await? else {
+        return Ok(())
+    };
+    // NOTE: compare in constant time so the response time does not reveal the secret
+    if !bool::from(handshake.secret.as_bytes().ct_eq(secret.as_bytes())) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "builder presented an invalid secret",
+        ))
+    }
+    while let Some(request) =
+        read_message::<SubmissionRequest<'static>>(&mut stream, &mut line).await?
+    {
+        let submission = &request.submission;
+        debug!(
+            slot = submission.message().slot,
+            relays = request.relays.len(),
+            "received submission"
+        );
+        let errors =
+            join_all(request.relays.iter().map(|target| submit(&relays, target, submission))).await;
+        write_message(&mut stream, &SubmissionResponse { errors }).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::submission::{BridgeConfig, SubmissionBridge};
+    use mev_rs::types::{block_submission::capella, ExecutionPayload};
+
+    async fn spawn_service(name: &str) -> BridgeEndpoint {
+        let path =
+            std::env::temp_dir().join(format!("mev-submit-{name}-{}.sock", std::process::id()));
+        let listen = BridgeEndpoint::Unix(path);
+        let config = Config {
+            listen: listen.clone(),
+            secret: "secret".to_string(),
+            relays: vec![],
+            relay_client: Default::default(),
+        };
+        let listener = Listener::bind(&listen).await.unwrap();
+        tokio::spawn(Service::new(config).run(listener));
+        listen
+    }
+
+    fn submission() -> SignedBidSubmission {
+        SignedBidSubmission::Capella(capella::SignedBidSubmission {
+            message: Default::default(),
+            execution_payload: ExecutionPayload::Capella(Default::default()),
+            signature: Default::default(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_submission_round_trip() {
+        let endpoint = spawn_service("round-trip").await;
+        let mut bridge =
+            SubmissionBridge::new(BridgeConfig { endpoint, secret: "secret".to_string() });
+        let target = RelayTarget { public_key: Default::default(), cancellations: false };
+
+        // NOTE: the service knows no relays, so it reports the target as unknown
+        for _ in 0..2 {
+            let outcomes = bridge.submit(vec![target.clone()], &submission()).await.unwrap();
+            assert_eq!(outcomes.len(), 1);
+            assert!(outcomes[0].as_ref().unwrap_err().starts_with("unknown relay"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submission_requires_secret() {
+        let endpoint = spawn_service("secret").await;
+        let mut bridge =
+            SubmissionBridge::new(BridgeConfig { endpoint, secret: "wrong".to_string() });
+        let target = RelayTarget { public_key: Default::default(), cancellations: false };
+        assert!(bridge.submit(vec![target], &submission()).await.is_err());
+    }
+}
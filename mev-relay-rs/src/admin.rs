@@ -0,0 +1,107 @@
+use crate::relay::Relay;
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+    routing::{get, post, put},
+};
+use ethereum_consensus::primitives::BlsPublicKey;
+use futures::{Stream, StreamExt};
+use mev_rs::{
+    admin,
+    config::AdminConfig,
+    types::block_submission::data_api::{PayloadTrace, SubmissionTrace},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+async fn handle_get_builders(State(relay): State<Relay>) -> Json<Vec<BlsPublicKey>> {
+    Json(relay.builder_registry.builders())
+}
+
+async fn handle_add_builder(
+    State(relay): State<Relay>,
+    Path(public_key): Path<BlsPublicKey>,
+) -> StatusCode {
+    match relay.builder_registry.insert(public_key.clone()) {
+        Ok(true) => {
+            info!(%public_key, "added accepted builder");
+            StatusCode::CREATED
+        }
+        Ok(false) => StatusCode::OK,
+        Err(err) => {
+            warn!(%err, %public_key, "could not persist accepted builders");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+async fn handle_remove_builder(
+    State(relay): State<Relay>,
+    Path(public_key): Path<BlsPublicKey>,
+) -> StatusCode {
+    match relay.builder_registry.remove(&public_key) {
+        Ok(true) => {
+            info!(%public_key, "removed accepted builder");
+            StatusCode::OK
+        }
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(err) => {
+            warn!(%err, %public_key, "could not persist accepted builders");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Traces from the data API of another relay to import
+#[derive(Debug, Deserialize)]
+struct BackfillRequest {
+    #[serde(default)]
+    delivered_payloads: Vec<PayloadTrace>,
+    #[serde(default)]
+    block_submissions: Vec<SubmissionTrace>,
+}
+
+/// Number of new traces imported by a [`BackfillRequest`]
+#[derive(Debug, Serialize)]
+struct BackfillResponse {
+    delivered_payloads: usize,
+    block_submissions: usize,
+}
+
+async fn handle_backfill(
+    State(relay): State<Relay>,
+    Json(request): Json<BackfillRequest>,
+) -> Json<BackfillResponse> {
+    let (delivered_payloads, block_submissions) =
+        relay.import_traces(request.delivered_payloads, request.block_submissions);
+    info!(delivered_payloads, block_submissions, "imported traces");
+    Json(BackfillResponse { delivered_payloads, block_submissions })
+}
+
+/// Streams the lifecycle events of the relay's auctions as server-sent events
+async fn handle_get_events(
+    State(relay): State<Relay>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let events = relay
+        .events
+        .subscribe()
+        .map(|event| Event::default().event(event.name()).json_data(&event));
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+/// Spawns the admin API server for `relay` on a new task returning the handle for it
+pub fn spawn(config: &AdminConfig, relay: Relay) -> Result<JoinHandle<()>, Error> {
+    let router = admin::router()
+        .route("/admin/v1/builders", get(handle_get_builders))
+        .route(
+            "/admin/v1/builders/:public_key",
+            put(handle_add_builder).delete(handle_remove_builder),
+        )
+        .route("/admin/v1/backfill", post(handle_backfill))
+        .route("/admin/v1/events", get(handle_get_events))
+        .with_state(relay);
+    admin::spawn(config, router)
+}
@@ -2,17 +2,20 @@ use ethereum_consensus::{
     primitives::{BlsPublicKey, U256},
     ssz::prelude::*,
     state_transition::Context,
+    Fork,
 };
 use mev_rs::{
     signing::{sign_builder_message, SecretKey},
     types::{
         auction_contents, builder_bid, AuctionContents, BidTrace, BlobsBundle, BuilderBid,
         ExecutionPayload, ExecutionPayloadHeader, SignedBidSubmission, SignedBuilderBid,
+        SignedHeaderSubmission,
     },
-    Error,
+    Error, RelayError,
 };
 use std::{
     hash::{Hash, Hasher},
+    sync::Arc,
     time::Duration,
 };
 
@@ -35,8 +38,8 @@ pub mod bellatrix {
         pub builder_public_key: BlsPublicKey,
         pub bid_trace: BidTrace,
         pub receive_duration: Duration,
-        pub signed_builder_bid: SignedBuilderBid,
-        pub execution_payload: ExecutionPayload,
+        pub signed_builder_bid: Arc<SignedBuilderBid>,
+        pub execution_payload: Arc<ExecutionPayload>,
         pub value: U256,
     }
 
@@ -66,10 +69,10 @@ pub mod deneb {
         pub builder_public_key: BlsPublicKey,
         pub bid_trace: BidTrace,
         pub receive_duration: Duration,
-        pub signed_builder_bid: SignedBuilderBid,
-        pub execution_payload: ExecutionPayload,
+        pub signed_builder_bid: Arc<SignedBuilderBid>,
+        pub execution_payload: Arc<ExecutionPayload>,
         pub value: U256,
-        pub blobs_bundle: BlobsBundle,
+        pub blobs_bundle: Arc<BlobsBundle>,
     }
 
     impl Hash for AuctionContext {
@@ -89,6 +92,10 @@ pub mod deneb {
     }
 }
 
+/// A bid submitted with its execution payload
+///
+/// NOTE: the payload, bid and any blobs are held behind `Arc`s so they can be shared without
+/// copying them
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub enum AuctionContext {
     Bellatrix(bellatrix::AuctionContext),
@@ -98,7 +105,7 @@ pub enum AuctionContext {
 
 impl AuctionContext {
     pub fn new(
-        signed_submission: SignedBidSubmission,
+        signed_submission: &SignedBidSubmission,
         receive_duration: Duration,
         relay_public_key: BlsPublicKey,
         relay_secret_key: &SecretKey,
@@ -106,8 +113,7 @@ impl AuctionContext {
     ) -> Result<Self, Error> {
         let builder_public_key = signed_submission.message().builder_public_key.clone();
 
-        let execution_payload = signed_submission.payload().clone();
-        let execution_payload_header = to_header(&execution_payload)?;
+        let execution_payload_header = to_header(signed_submission.payload())?;
 
         let value = signed_submission.message().value;
 
@@ -126,7 +132,7 @@ impl AuctionContext {
                     public_key: relay_public_key,
                 })
             }
-            SignedBidSubmission::Deneb(ref submission) => {
+            SignedBidSubmission::Deneb(submission) => {
                 BuilderBid::Deneb(builder_bid::deneb::BuilderBid {
                     header: execution_payload_header,
                     blob_kzg_commitments: submission.blobs_bundle.commitments.clone(),
@@ -137,22 +143,23 @@ impl AuctionContext {
         };
 
         let signature = sign_builder_message(&bid, relay_secret_key, context)?;
-        let signed_builder_bid = SignedBuilderBid { message: bid, signature };
+        let signed_builder_bid = Arc::new(SignedBuilderBid { message: bid, signature });
+        // NOTE: the only copy of the payload made for the auction
+        let execution_payload = Arc::new(signed_submission.payload().clone());
+        let bid_trace = signed_submission.message().clone();
 
         let auction_context = match signed_submission {
-            SignedBidSubmission::Bellatrix(submission) => {
-                Self::Bellatrix(bellatrix::AuctionContext {
-                    builder_public_key,
-                    bid_trace: submission.message,
-                    receive_duration,
-                    signed_builder_bid,
-                    execution_payload,
-                    value,
-                })
-            }
-            SignedBidSubmission::Capella(submission) => Self::Capella(capella::AuctionContext {
+            SignedBidSubmission::Bellatrix(_) => Self::Bellatrix(bellatrix::AuctionContext {
+                builder_public_key,
+                bid_trace,
+                receive_duration,
+                signed_builder_bid,
+                execution_payload,
+                value,
+            }),
+            SignedBidSubmission::Capella(_) => Self::Capella(capella::AuctionContext {
                 builder_public_key,
-                bid_trace: submission.message,
+                bid_trace,
                 receive_duration,
                 signed_builder_bid,
                 execution_payload,
@@ -160,12 +167,12 @@ impl AuctionContext {
             }),
             SignedBidSubmission::Deneb(submission) => Self::Deneb(deneb::AuctionContext {
                 builder_public_key,
-                bid_trace: submission.message,
+                bid_trace,
                 receive_duration,
                 signed_builder_bid,
                 execution_payload,
                 value,
-                blobs_bundle: submission.blobs_bundle,
+                blobs_bundle: Arc::new(submission.blobs_bundle.clone()),
             }),
         };
 
@@ -196,7 +203,7 @@ impl AuctionContext {
         }
     }
 
-    pub fn signed_builder_bid(&self) -> &SignedBuilderBid {
+    pub fn signed_builder_bid(&self) -> &Arc<SignedBuilderBid> {
         match self {
             Self::Bellatrix(context) => &context.signed_builder_bid,
             Self::Capella(context) => &context.signed_builder_bid,
@@ -204,7 +211,7 @@ impl AuctionContext {
         }
     }
 
-    pub fn execution_payload(&self) -> &ExecutionPayload {
+    pub fn execution_payload(&self) -> &Arc<ExecutionPayload> {
         match self {
             Self::Bellatrix(context) => &context.execution_payload,
             Self::Capella(context) => &context.execution_payload,
@@ -212,7 +219,7 @@ impl AuctionContext {
         }
     }
 
-    pub fn blobs_bundle(&self) -> Option<&BlobsBundle> {
+    pub fn blobs_bundle(&self) -> Option<&Arc<BlobsBundle>> {
         match self {
             Self::Bellatrix(_) => None,
             Self::Capella(_) => None,
@@ -231,15 +238,71 @@ impl AuctionContext {
     pub fn to_auction_contents(&self) -> AuctionContents {
         match self {
             Self::Bellatrix(context) => {
-                AuctionContents::Bellatrix(context.execution_payload.clone())
+                AuctionContents::Bellatrix(context.execution_payload.as_ref().clone())
+            }
+            Self::Capella(context) => {
+                AuctionContents::Capella(context.execution_payload.as_ref().clone())
             }
-            Self::Capella(context) => AuctionContents::Capella(context.execution_payload.clone()),
             Self::Deneb(context) => {
                 AuctionContents::Deneb(auction_contents::deneb::AuctionContents {
-                    execution_payload: context.execution_payload.clone(),
-                    blobs_bundle: context.blobs_bundle.clone(),
+                    execution_payload: context.execution_payload.as_ref().clone(),
+                    blobs_bundle: context.blobs_bundle.as_ref().clone(),
                 })
             }
         }
     }
 }
+
+/// A bid submitted without its execution payload, kept until the payload arrives
+#[derive(Debug)]
+pub struct HeaderBid {
+    pub bid_trace: BidTrace,
+    pub receive_duration: Duration,
+    pub signed_builder_bid: SignedBuilderBid,
+}
+
+impl HeaderBid {
+    pub fn new(
+        signed_submission: SignedHeaderSubmission,
+        receive_duration: Duration,
+        relay_public_key: BlsPublicKey,
+        relay_secret_key: &SecretKey,
+        context: &Context,
+    ) -> Result<Self, Error> {
+        let SignedHeaderSubmission {
+            message: bid_trace,
+            execution_payload_header: header,
+            blob_kzg_commitments,
+            ..
+        } = signed_submission;
+        let value = bid_trace.value;
+        let public_key = relay_public_key;
+        let bid = match header.version() {
+            Fork::Bellatrix => BuilderBid::Bellatrix(builder_bid::bellatrix::BuilderBid {
+                header,
+                value,
+                public_key,
+            }),
+            Fork::Capella => {
+                BuilderBid::Capella(builder_bid::capella::BuilderBid { header, value, public_key })
+            }
+            Fork::Deneb => {
+                let blob_kzg_commitments =
+                    blob_kzg_commitments.ok_or(RelayError::MissingBlobCommitments)?;
+                BuilderBid::Deneb(builder_bid::deneb::BuilderBid {
+                    header,
+                    blob_kzg_commitments,
+                    value,
+                    public_key,
+                })
+            }
+            fork => return Err(Error::UnsupportedFork(fork)),
+        };
+        let signed_builder_bid = bid.sign(relay_secret_key, context)?;
+        Ok(Self { bid_trace, receive_duration, signed_builder_bid })
+    }
+
+    pub fn value(&self) -> U256 {
+        self.bid_trace.value
+    }
+}
@@ -0,0 +1,176 @@
+use ethereum_consensus::primitives::{Slot, U256};
+use metrics::{counter, gauge};
+use mev_rs::{Error, RelayError};
+use parking_lot::Mutex;
+use std::{collections::BTreeMap, time::Duration};
+
+// Activity seen in the auctions of a single slot
+#[derive(Debug, Default)]
+struct SlotRecord {
+    submissions: u64,
+    rejections: u64,
+    best_bid_value: Option<U256>,
+    // time the best bid was received, since the unix epoch
+    best_bid_time: Option<Duration>,
+    delivered: bool,
+}
+
+/// Exports metrics on the auctions of each slot, so operators can alert on empty auctions or
+/// missed deliveries
+///
+/// The `mev_relay_auction_*` gauges track the most recent slot with an open auction, while the
+/// `mev_relay_auctions_total` counter tallies the outcome of each slot once it has passed. Only
+/// slots the relay opened an auction for are tracked, so submissions for arbitrary slots can
+/// neither move the gauges nor grow the state.
+#[derive(Debug, Default)]
+pub struct AuctionMetrics {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    // slot whose outcome has yet to be tallied, along with any later slots
+    current_slot: Slot,
+    slots: BTreeMap<Slot, SlotRecord>,
+}
+
+impl AuctionMetrics {
+    fn update(&self, slot: Slot, f: impl FnOnce(&mut SlotRecord)) {
+        let mut state = self.state.lock();
+        let slots = &mut state.slots;
+        // NOTE: ignore activity for slots without an open auction, including those already tallied
+        let Some(record) = slots.get_mut(&slot) else { return };
+        f(record);
+        if slots.last_key_value().map(|(latest, _)| *latest == slot).unwrap_or_default() {
+            let record = &slots[&slot];
+            gauge!("mev_relay_auction_slot").set(slot as f64);
+            gauge!("mev_relay_auction_submissions").set(record.submissions as f64);
+            gauge!("mev_relay_auction_rejected_submissions").set(record.rejections as f64);
+            gauge!("mev_relay_auction_best_bid_value_wei")
+                .set(record.best_bid_value.map(f64::from).unwrap_or_default());
+            gauge!("mev_relay_auction_best_bid_timestamp_ms")
+                .set(record.best_bid_time.map(|time| time.as_millis() as f64).unwrap_or_default());
+            gauge!("mev_relay_auction_delivered").set(if record.delivered { 1.0 } else { 0.0 });
+        }
+    }
+
+    pub fn record_auction_opened(&self, slot: Slot) {
+        {
+            let mut state = self.state.lock();
+            // NOTE: ignore late auctions for slots that have already been tallied
+            if slot < state.current_slot {
+                return
+            }
+            state.slots.entry(slot).or_default();
+        }
+        self.update(slot, |_| {});
+    }
+
+    pub fn record_submission(&self, slot: Slot, result: Result<(), &Error>) {
+        counter!("mev_relay_submissions_total").increment(1);
+        if let Err(err) = result {
+            counter!("mev_relay_submissions_rejected_total", "reason" => error_label(err))
+                .increment(1);
+        }
+        self.update(slot, |record| {
+            record.submissions += 1;
+            if result.is_err() {
+                record.rejections += 1;
+            }
+        });
+    }
+
+    pub fn record_best_bid(&self, slot: Slot, value: U256, receive_duration: Duration) {
+        self.update(slot, |record| {
+            record.best_bid_value = Some(value);
+            record.best_bid_time = Some(receive_duration);
+        });
+    }
+
+    pub fn record_payload_request(&self, slot: Slot, result: Result<(), &Error>) {
+        let outcome = match result {
+            Ok(()) => "delivered",
+            Err(err) => error_label(err),
+        };
+        counter!("mev_relay_payload_requests_total", "outcome" => outcome).increment(1);
+        if result.is_ok() {
+            self.update(slot, |record| record.delivered = true);
+        }
+    }
+
+    /// Tallies the outcome of the auctions for the slots before `slot`
+    pub fn on_slot(&self, slot: Slot) {
+        let passed = {
+            let mut state = self.state.lock();
+            state.current_slot = state.current_slot.max(slot);
+            let current = state.slots.split_off(&slot);
+            std::mem::replace(&mut state.slots, current)
+        };
+        for record in passed.into_values() {
+            let outcome = if record.delivered {
+                "delivered"
+            } else if record.best_bid_value.is_some() {
+                "undelivered"
+            } else {
+                "empty"
+            };
+            counter!("mev_relay_auctions_total", "outcome" => outcome).increment(1);
+        }
+    }
+}
+
+// Low-cardinality label for the reason a request failed
+fn error_label(err: &Error) -> &'static str {
+    match err {
+        Error::InvalidFork { .. } | Error::UnsupportedFork(..) => "invalid_fork",
+        Error::NoBidPrepared(..) => "no_bid",
+        Error::Relay(err) => match err {
+            RelayError::InvalidAuctionRequest(..) | RelayError::MissingAuction(..) => {
+                "unknown_auction"
+            }
+            RelayError::InvalidFeeRecipient(..) => "invalid_fee_recipient",
+            RelayError::InvalidGasLimit(..) | RelayError::InvalidGasUsed(..) => "invalid_gas",
+            RelayError::InvalidParentHash(..) |
+            RelayError::InvalidBlockHash(..) |
+            RelayError::InvalidExecutionPayloadInBlock => "invalid_payload",
+            RelayError::InvalidSignedBlindedBeaconBlock => "invalid_signed_block",
+            RelayError::ValidatorNotRegistered(..) | RelayError::UnknownValidatorIndex(..) => {
+                "unknown_validator"
+            }
+            RelayError::BuilderNotRegistered(..) |
+            RelayError::BuilderDenied(..) |
+            RelayError::HeaderSubmissionNotAllowed(..) => "builder_not_allowed",
+            RelayError::MissingBlobCommitments | RelayError::MissingPayload(..) => {
+                "missing_payload"
+            }
+            RelayError::LateSignedBlindedBeaconBlock(..) => "late",
+            RelayError::MissingProposerPayment |
+            RelayError::UnverifiableProposerPayment |
+            RelayError::InsufficientProposerPayment(..) => "invalid_payment",
+        },
+        Error::Consensus(..) => "invalid_signature",
+        Error::Ssz(..) | Error::SszSerialize(..) => "invalid_encoding",
+        _ => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_open_auctions_are_tracked() {
+        let metrics = AuctionMetrics::default();
+        metrics.record_submission(u64::MAX, Ok(()));
+        metrics.record_best_bid(u64::MAX, U256::from(1), Duration::ZERO);
+        assert!(metrics.state.lock().slots.is_empty());
+
+        metrics.record_auction_opened(10);
+        metrics.record_submission(10, Ok(()));
+        assert_eq!(metrics.state.lock().slots[&10].submissions, 1);
+
+        metrics.on_slot(11);
+        metrics.record_auction_opened(10);
+        assert!(metrics.state.lock().slots.is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+use ethereum_consensus::primitives::{BlsPublicKey, Hash32, Slot, ValidatorIndex, U256};
+use metrics::counter;
+use mev_rs::{caller, json_lines::JsonLinesWriter, Error};
+use serde::Serialize;
+use std::{
+    net::IpAddr,
+    path::PathBuf,
+    sync::mpsc::TrySendError,
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+// Number of entries that may wait to be written before further entries are dropped
+const MAX_PENDING_ENTRIES: usize = 4096;
+
+/// A call made to the relay's APIs, identifying the caller by the keys in its request
+#[derive(Debug, Serialize)]
+#[serde(tag = "call", rename_all = "snake_case")]
+pub enum AuditEvent {
+    RegisterValidators {
+        public_keys: Vec<BlsPublicKey>,
+    },
+    GetHeader {
+        slot: Slot,
+        parent_hash: Hash32,
+        proposer_public_key: BlsPublicKey,
+        /// Block hash of the bid served, if any
+        block_hash: Option<Hash32>,
+        value: Option<U256>,
+    },
+    OpenBid {
+        slot: Slot,
+        proposer_index: ValidatorIndex,
+        block_hash: Hash32,
+    },
+    SubmitBid {
+        slot: Slot,
+        builder_public_key: BlsPublicKey,
+        block_hash: Hash32,
+        value: U256,
+    },
+    SubmitHeader {
+        slot: Slot,
+        builder_public_key: BlsPublicKey,
+        block_hash: Hash32,
+        value: U256,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct Entry {
+    /// Time the call was received, in milliseconds since the unix epoch
+    timestamp_ms: u128,
+    /// Address the call was made from, if known
+    caller_ip: Option<IpAddr>,
+    #[serde(flatten)]
+    event: AuditEvent,
+    /// Time taken to serve the call
+    duration_us: u128,
+    /// Error returned to the caller, if the call failed
+    error: Option<String>,
+}
+
+/// Append-only log of the calls made to the relay's APIs, with their outcome and timing
+///
+/// Each call is written to the file at `path` as a single line of JSON, from a dedicated thread
+/// so writes stay off of the request path. If the writer falls behind, entries are dropped
+/// rather than held in memory, as counted by `mev_relay_audit_log_dropped_total`.
+pub struct AuditLog {
+    writer: Option<JsonLinesWriter<Entry>>,
+}
+
+impl AuditLog {
+    /// Opens the log at `path`, if any, failing if the file can not be opened for writing
+    pub fn new(path: Option<PathBuf>) -> Result<Self, Error> {
+        let Some(path) = path else { return Ok(Self { writer: None }) };
+
+        let writer =
+            JsonLinesWriter::spawn("mev-relay-audit-log", path.clone(), MAX_PENDING_ENTRIES, None)
+                .map_err(|err| {
+                    Error::InvalidConfig(format!(
+                        "could not open audit log {}: {err}",
+                        path.display()
+                    ))
+                })?;
+        Ok(Self { writer: Some(writer) })
+    }
+
+    /// Records `event` for a call received at `receive_duration` and started at `start`
+    pub fn record(
+        &self,
+        event: AuditEvent,
+        receive_duration: Duration,
+        start: Instant,
+        error: Option<&Error>,
+    ) {
+        let Some(writer) = self.writer.as_ref() else { return };
+
+        let entry = Entry {
+            timestamp_ms: receive_duration.as_millis(),
+            caller_ip: caller::current_ip(),
+            event,
+            duration_us: start.elapsed().as_micros(),
+            error: error.map(ToString::to_string),
+        };
+        match writer.append(entry) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                counter!("mev_relay_audit_log_dropped_total").increment(1);
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                counter!("mev_relay_audit_log_dropped_total").increment(1);
+                warn!("audit log writer has stopped; dropping entry");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn register_validators() -> AuditEvent {
+        AuditEvent::RegisterValidators { public_keys: vec![Default::default()] }
+    }
+
+    #[test]
+    fn test_audit_log_requires_writable_path() {
+        let path = std::env::temp_dir().join("mev-relay-missing-dir").join("audit.jsonl");
+        assert!(AuditLog::new(Some(path)).is_err());
+        assert!(AuditLog::new(None).is_ok());
+    }
+
+    #[test]
+    fn test_audit_log_entries() {
+        let path =
+            std::env::temp_dir().join(format!("mev-relay-audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let audit_log = AuditLog::new(Some(path.clone())).unwrap();
+        let err = Error::InvalidConfig("test".to_string());
+        audit_log.record(register_validators(), Duration::ZERO, Instant::now(), None);
+        audit_log.record(register_validators(), Duration::ZERO, Instant::now(), Some(&err));
+        // NOTE: waits for the writer to persist the entries
+        drop(audit_log);
+
+        let data = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let entries = data
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["call"], "register_validators");
+        assert_eq!(entries[0]["public_keys"].as_array().unwrap().len(), 1);
+        assert!(entries[0]["caller_ip"].is_null());
+        assert!(entries[0]["error"].is_null());
+        assert_eq!(entries[1]["error"], "invalid configuration: test");
+    }
+}
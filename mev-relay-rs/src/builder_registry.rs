@@ -0,0 +1,168 @@
+use ethereum_consensus::{primitives::BlsPublicKey, serde::try_bytes_from_hex_str};
+use parking_lot::RwLock;
+use std::{
+    collections::HashSet,
+    fs, future, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::{info, warn};
+
+// Interval to check the builder deny list file for changes.
+const DENY_LIST_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Tracks the builders accepted by the relay, optionally persisting any changes to a file
+/// so they survive restarts
+///
+/// If the file exists, its contents take precedence over the builders given in config
+pub struct BuilderRegistry {
+    builders: RwLock<HashSet<BlsPublicKey>>,
+    path: Option<PathBuf>,
+}
+
+impl BuilderRegistry {
+    pub fn new(accepted_builders: Vec<BlsPublicKey>, path: Option<PathBuf>) -> Self {
+        let mut builders = HashSet::from_iter(accepted_builders);
+        if let Some(path) = path.as_ref() {
+            match load(path) {
+                Ok(persisted) => {
+                    info!(count = persisted.len(), path = %path.display(), "loaded accepted builders");
+                    builders = HashSet::from_iter(persisted);
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => {
+                    warn!(%err, path = %path.display(), "could not load accepted builders")
+                }
+            }
+        }
+        Self { builders: RwLock::new(builders), path }
+    }
+
+    pub fn contains(&self, public_key: &BlsPublicKey) -> bool {
+        self.builders.read().contains(public_key)
+    }
+
+    pub fn builders(&self) -> Vec<BlsPublicKey> {
+        self.builders.read().iter().cloned().collect()
+    }
+
+    /// Adds the builder to the registry, returning `true` if it was not already present
+    pub fn insert(&self, public_key: BlsPublicKey) -> io::Result<bool> {
+        let mut builders = self.builders.write();
+        let inserted = builders.insert(public_key);
+        if inserted {
+            self.persist(&builders)?;
+        }
+        Ok(inserted)
+    }
+
+    /// Removes the builder from the registry, returning `true` if it was present
+    pub fn remove(&self, public_key: &BlsPublicKey) -> io::Result<bool> {
+        let mut builders = self.builders.write();
+        let removed = builders.remove(public_key);
+        if removed {
+            self.persist(&builders)?;
+        }
+        Ok(removed)
+    }
+
+    fn persist(&self, builders: &HashSet<BlsPublicKey>) -> io::Result<()> {
+        let Some(path) = self.path.as_ref() else { return Ok(()) };
+
+        let builders = builders.iter().collect::<Vec<_>>();
+        let data = serde_json::to_vec_pretty(&builders)?;
+        // NOTE: write to a temporary file first so a crash can not leave a partial file behind
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, data)?;
+        fs::rename(tmp_path, path)
+    }
+}
+
+fn load(path: &Path) -> io::Result<Vec<BlsPublicKey>> {
+    let data = fs::read(path)?;
+    serde_json::from_slice(&data).map_err(Into::into)
+}
+
+/// Builders excluded from the relay, read from a file with one public key per line
+///
+/// Blank lines and lines starting with `#` are ignored.
+pub struct BuilderDenyList {
+    builders: RwLock<HashSet<BlsPublicKey>>,
+    path: Option<PathBuf>,
+}
+
+impl BuilderDenyList {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        let deny_list = Self { builders: Default::default(), path };
+        deny_list.reload();
+        deny_list
+    }
+
+    pub fn contains(&self, public_key: &BlsPublicKey) -> bool {
+        self.builders.read().contains(public_key)
+    }
+
+    /// Re-reads the deny list file, keeping the current list if the file can not be read
+    pub fn reload(&self) {
+        let Some(path) = self.path.as_ref() else { return };
+
+        match load_deny_list(path) {
+            Ok(builders) => {
+                info!(count = builders.len(), path = %path.display(), "loaded builder deny list");
+                *self.builders.write() = builders;
+            }
+            Err(err) => warn!(%err, path = %path.display(), "could not load builder deny list"),
+        }
+    }
+
+    /// Reloads the deny list whenever the process receives `SIGHUP` or the file is modified
+    pub async fn watch(&self) {
+        let Some(path) = self.path.as_ref() else { return };
+
+        let mut hangups = signal(SignalKind::hangup())
+            .map_err(|err| warn!(%err, "could not listen for SIGHUP to reload builder deny list"))
+            .ok();
+        let mut interval = tokio::time::interval(DENY_LIST_POLL_INTERVAL);
+        let mut last_modified = modified_time(path);
+        loop {
+            let hangup = async {
+                match hangups.as_mut() {
+                    Some(hangups) => hangups.recv().await,
+                    None => future::pending().await,
+                }
+            };
+            tokio::select! {
+                _ = interval.tick() => {
+                    let modified = modified_time(path);
+                    if modified == last_modified {
+                        continue
+                    }
+                    last_modified = modified;
+                }
+                _ = hangup => {}
+            }
+            self.reload();
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn load_deny_list(path: &Path) -> io::Result<HashSet<BlsPublicKey>> {
+    let data = fs::read_to_string(path)?;
+    data.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            try_bytes_from_hex_str(line)
+                .ok()
+                .and_then(|bytes| BlsPublicKey::try_from(bytes.as_ref()).ok())
+                .ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, format!("invalid public key {line}"))
+                })
+        })
+        .collect()
+}
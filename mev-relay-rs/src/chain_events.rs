@@ -0,0 +1,42 @@
+use beacon_api_client::Topic;
+use ethereum_consensus::primitives::{Epoch, Root, Slot};
+use serde::Deserialize;
+
+/// A new head of the chain, as published on the beacon node's `head` topic
+#[derive(Debug, Clone, Deserialize)]
+pub struct HeadEvent {
+    #[serde(with = "ethereum_consensus::serde::as_str")]
+    pub slot: Slot,
+    pub block: Root,
+    pub previous_duty_dependent_root: Root,
+    pub current_duty_dependent_root: Root,
+}
+
+pub struct HeadTopic;
+
+impl Topic for HeadTopic {
+    const NAME: &'static str = "head";
+
+    type Data = HeadEvent;
+}
+
+/// A re-org of the chain, as published on the beacon node's `chain_reorg` topic
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChainReorgEvent {
+    #[serde(with = "ethereum_consensus::serde::as_str")]
+    pub slot: Slot,
+    #[serde(with = "ethereum_consensus::serde::as_str")]
+    pub depth: u64,
+    pub old_head_block: Root,
+    pub new_head_block: Root,
+    #[serde(with = "ethereum_consensus::serde::as_str")]
+    pub epoch: Epoch,
+}
+
+pub struct ChainReorgTopic;
+
+impl Topic for ChainReorgTopic {
+    const NAME: &'static str = "chain_reorg";
+
+    type Data = ChainReorgEvent;
+}
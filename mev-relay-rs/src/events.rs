@@ -0,0 +1,192 @@
+use beacon_api_client::PayloadAttributesEvent;
+use ethereum_consensus::{
+    primitives::{BlsPublicKey, Hash32, Slot, U256},
+    serde::as_str,
+};
+use futures::{stream, Stream};
+use mev_rs::{blinded_block_relayer::PayloadAttributesUpdate, types::SignedValidatorRegistration};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+
+// Number of events buffered for each subscriber before it starts to miss events.
+const EVENT_BUFFER_SIZE: usize = 1024;
+
+/// An event in the lifecycle of an auction run by the relay
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum AuctionEvent {
+    AuctionOpened {
+        #[serde(with = "as_str")]
+        slot: Slot,
+        parent_hash: Hash32,
+        #[serde(rename = "proposer_pubkey")]
+        proposer_public_key: BlsPublicKey,
+    },
+    NewBestBid {
+        #[serde(with = "as_str")]
+        slot: Slot,
+        parent_hash: Hash32,
+        #[serde(rename = "proposer_pubkey")]
+        proposer_public_key: BlsPublicKey,
+        #[serde(rename = "builder_pubkey")]
+        builder_public_key: BlsPublicKey,
+        block_hash: Hash32,
+        #[serde(with = "as_str")]
+        value: U256,
+        /// Whether the payload of the bid is still to be submitted
+        header_only: bool,
+    },
+    BidRejected {
+        #[serde(with = "as_str")]
+        slot: Slot,
+        parent_hash: Hash32,
+        #[serde(rename = "builder_pubkey")]
+        builder_public_key: BlsPublicKey,
+        block_hash: Hash32,
+        reason: String,
+    },
+    PayloadDelivered {
+        #[serde(with = "as_str")]
+        slot: Slot,
+        parent_hash: Hash32,
+        #[serde(rename = "proposer_pubkey")]
+        proposer_public_key: BlsPublicKey,
+        #[serde(rename = "builder_pubkey")]
+        builder_public_key: BlsPublicKey,
+        block_hash: Hash32,
+        #[serde(with = "as_str")]
+        value: U256,
+    },
+}
+
+impl AuctionEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::AuctionOpened { .. } => "auction_opened",
+            Self::NewBestBid { .. } => "new_best_bid",
+            Self::BidRejected { .. } => "bid_rejected",
+            Self::PayloadDelivered { .. } => "payload_delivered",
+        }
+    }
+}
+
+/// Fans out the [`AuctionEvent`]s of the relay to any subscribers
+pub struct AuctionEvents {
+    sender: broadcast::Sender<AuctionEvent>,
+}
+
+impl Default for AuctionEvents {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER_SIZE);
+        Self { sender }
+    }
+}
+
+impl AuctionEvents {
+    pub fn publish(&self, event: AuctionEvent) {
+        // NOTE: sending only fails if there are no subscribers
+        let _ = self.sender.send(event);
+    }
+
+    /// Returns the events published from now on, skipping any missed by a slow subscriber
+    pub fn subscribe(&self) -> impl Stream<Item = AuctionEvent> {
+        subscribe(&self.sender, "auction events")
+    }
+}
+
+/// Fans out the payload attributes of upcoming proposals to the builders subscribed to them
+pub struct PayloadAttributesFeed {
+    sender: broadcast::Sender<Arc<PayloadAttributesUpdate>>,
+}
+
+impl Default for PayloadAttributesFeed {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER_SIZE);
+        Self { sender }
+    }
+}
+
+impl PayloadAttributesFeed {
+    /// Publishes the payload attributes of a proposal, unless the proposer has no registration
+    /// as builders can not build for it
+    pub fn publish(
+        &self,
+        payload_attributes: PayloadAttributesEvent,
+        proposer_registration: Option<&SignedValidatorRegistration>,
+    ) {
+        let Some(proposer_registration) = proposer_registration else { return };
+
+        let update = PayloadAttributesUpdate {
+            payload_attributes,
+            proposer_registration: proposer_registration.clone(),
+        };
+        // NOTE: sending only fails if there are no subscribers
+        let _ = self.sender.send(Arc::new(update));
+    }
+
+    /// Returns the updates published from now on, skipping any missed by a slow subscriber
+    pub fn subscribe(&self) -> impl Stream<Item = Arc<PayloadAttributesUpdate>> {
+        subscribe(&self.sender, "payload attributes")
+    }
+}
+
+fn subscribe<T: Clone>(sender: &broadcast::Sender<T>, kind: &'static str) -> impl Stream<Item = T> {
+    stream::unfold(sender.subscribe(), move |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(item) => return Some((item, receiver)),
+                Err(RecvError::Lagged(count)) => {
+                    warn!(count, "subscriber missed {kind}");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    // Payload attributes as published on the beacon node's `payload_attributes` topic
+    fn payload_attributes(slot: Slot) -> PayloadAttributesEvent {
+        let hash = format!("0x{}", "11".repeat(32));
+        serde_json::from_value(serde_json::json!({
+            "proposer_index": "7",
+            "proposal_slot": slot.to_string(),
+            "parent_block_number": "9",
+            "parent_block_root": hash,
+            "parent_block_hash": hash,
+            "payload_attributes": {
+                "timestamp": "1700000000",
+                "prev_randao": hash,
+                "suggested_fee_recipient": format!("0x{}", "22".repeat(20)),
+                "withdrawals": [],
+                "parent_beacon_block_root": hash,
+            },
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_payload_attributes_feed() {
+        let feed = PayloadAttributesFeed::default();
+        let registration = SignedValidatorRegistration::default();
+        // NOTE: updates published before subscribing are not received
+        feed.publish(payload_attributes(1), Some(&registration));
+
+        let mut updates = Box::pin(feed.subscribe());
+        feed.publish(payload_attributes(2), None);
+        feed.publish(payload_attributes(3), Some(&registration));
+        drop(feed);
+
+        let update = updates.next().await.unwrap();
+        let message = serde_json::to_value(update.as_ref()).unwrap();
+        assert_eq!(message["proposal_slot"], "3");
+        assert!(message["proposer_registration"].is_object());
+        assert!(updates.next().await.is_none());
+    }
+}
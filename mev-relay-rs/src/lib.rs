@@ -1,5 +1,19 @@
+mod admin;
 mod auction_context;
+mod auction_metrics;
+mod audit_log;
+mod builder_registry;
+mod chain_events;
+mod events;
+mod payment;
 mod relay;
+mod replay;
 mod service;
+mod submission_archive;
+mod trace_index;
+mod trace_store;
 
+pub use relay::{HistoryConfig, TimingConfig, ValidationConfig};
+pub use replay::Replayer;
 pub use service::{Config, Service};
+pub use submission_archive::{load_auctions, ArchivedAuction, AUCTIONS_FILE_NAME};
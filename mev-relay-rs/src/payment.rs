@@ -0,0 +1,123 @@
+use alloy_consensus::TxEnvelope;
+use alloy_eips::eip2718::Decodable2718;
+use ethereum_consensus::primitives::U256;
+use mev_rs::{
+    types::{BidTrace, ExecutionPayload},
+    RelayError,
+};
+
+/// Verifies the proposer is paid at least the value of the bid by the last transaction of
+/// `execution_payload`, following the convention of builders paying the proposer with a transfer
+/// at the end of the block
+///
+/// Payloads setting the proposer's fee recipient as the `fee_recipient` are paid by the block's
+/// priority fees, which can not be verified without simulating the payload, so they are only
+/// accepted with `allow_fee_recipient_payment`.
+pub fn verify_proposer_payment(
+    bid_trace: &BidTrace,
+    execution_payload: &ExecutionPayload,
+    allow_fee_recipient_payment: bool,
+) -> Result<(), RelayError> {
+    let proposer_fee_recipient = &bid_trace.proposer_fee_recipient;
+    if execution_payload.fee_recipient() == proposer_fee_recipient {
+        if allow_fee_recipient_payment {
+            return Ok(())
+        }
+        return Err(RelayError::UnverifiableProposerPayment)
+    }
+
+    let transaction =
+        execution_payload.transactions().last().ok_or(RelayError::MissingProposerPayment)?;
+    let transaction = TxEnvelope::decode_2718(&mut transaction.as_ref())
+        .map_err(|_| RelayError::MissingProposerPayment)?;
+    // NOTE: the proposer is paid with a plain transfer so skip any other kind of transaction
+    let (to, value) = match &transaction {
+        TxEnvelope::Legacy(signed) => (signed.tx().to.to(), signed.tx().value),
+        TxEnvelope::Eip2930(signed) => (signed.tx().to.to(), signed.tx().value),
+        TxEnvelope::Eip1559(signed) => (signed.tx().to.to(), signed.tx().value),
+        _ => return Err(RelayError::MissingProposerPayment),
+    };
+    if to.map(|to| to.as_slice() != proposer_fee_recipient.as_ref()).unwrap_or(true) {
+        return Err(RelayError::MissingProposerPayment)
+    }
+
+    let value = U256::from_be_bytes(value.to_be_bytes::<32>());
+    if value < bid_trace.value {
+        return Err(RelayError::InsufficientProposerPayment(bid_trace.value, value))
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethereum_consensus::{
+        primitives::ExecutionAddress, serde::try_bytes_from_hex_str, ssz::prelude::List,
+    };
+
+    #[cfg(not(feature = "minimal-preset"))]
+    use ethereum_consensus::capella::mainnet as capella;
+    #[cfg(feature = "minimal-preset")]
+    use ethereum_consensus::capella::minimal as capella;
+
+    // EIP-1559 transaction sending 1 ether to `0x2222..2222`
+    const PAYMENT_TRANSACTION: &str = "0x02ea01808080825208942222222222222222222222222222222222222222880de0b6b3a764000080c0800101";
+    const ONE_ETHER: u64 = 1_000_000_000_000_000_000;
+
+    fn address(byte: u8) -> ExecutionAddress {
+        ExecutionAddress::try_from([byte; 20].as_ref()).unwrap()
+    }
+
+    fn payload(fee_recipient: ExecutionAddress, transactions: &[&str]) -> ExecutionPayload {
+        let transactions = transactions
+            .iter()
+            .map(|transaction| {
+                let encoding = try_bytes_from_hex_str(transaction).unwrap();
+                capella::Transaction::try_from(encoding.as_slice()).unwrap()
+            })
+            .collect::<Vec<_>>();
+        ExecutionPayload::Capella(capella::ExecutionPayload {
+            fee_recipient,
+            transactions: List::try_from(transactions).unwrap(),
+            ..Default::default()
+        })
+    }
+
+    fn bid_trace(proposer_fee_recipient: ExecutionAddress, value: u64) -> BidTrace {
+        BidTrace { proposer_fee_recipient, value: U256::from(value), ..Default::default() }
+    }
+
+    #[test]
+    fn test_verify_payment_transaction() {
+        let payload = payload(address(0x11), &[PAYMENT_TRANSACTION]);
+
+        verify_proposer_payment(&bid_trace(address(0x22), ONE_ETHER), &payload, false).unwrap();
+        verify_proposer_payment(&bid_trace(address(0x22), ONE_ETHER / 2), &payload, false).unwrap();
+        assert!(matches!(
+            verify_proposer_payment(&bid_trace(address(0x22), ONE_ETHER + 1), &payload, false),
+            Err(RelayError::InsufficientProposerPayment(..))
+        ));
+        assert!(matches!(
+            verify_proposer_payment(&bid_trace(address(0x33), ONE_ETHER), &payload, false),
+            Err(RelayError::MissingProposerPayment)
+        ));
+
+        let payload = payload(address(0x11), &[]);
+        assert!(matches!(
+            verify_proposer_payment(&bid_trace(address(0x22), ONE_ETHER), &payload, false),
+            Err(RelayError::MissingProposerPayment)
+        ));
+    }
+
+    #[test]
+    fn test_verify_fee_recipient_payment() {
+        let payload = payload(address(0x22), &[]);
+        let bid_trace = bid_trace(address(0x22), ONE_ETHER);
+
+        assert!(matches!(
+            verify_proposer_payment(&bid_trace, &payload, false),
+            Err(RelayError::UnverifiableProposerPayment)
+        ));
+        verify_proposer_payment(&bid_trace, &payload, true).unwrap();
+    }
+}
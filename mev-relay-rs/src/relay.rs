@@ -1,34 +1,52 @@
-use crate::auction_context::AuctionContext;
+use crate::{
+    auction_context::{AuctionContext, HeaderBid},
+    auction_metrics::AuctionMetrics,
+    audit_log::{AuditEvent, AuditLog},
+    builder_registry::{BuilderDenyList, BuilderRegistry},
+    chain_events::{ChainReorgEvent, HeadEvent},
+    events::{AuctionEvent, AuctionEvents, PayloadAttributesFeed},
+    payment::verify_proposer_payment,
+    submission_archive::{ArchivedAuction, SubmissionArchive},
+    trace_index::{TraceIndex, TraceQuery},
+    trace_store::{ImportedTrace, TraceStore},
+};
 use async_trait::async_trait;
 use beacon_api_client::{BroadcastValidation, PayloadAttributesEvent, SubmitSignedBeaconBlock};
 use ethereum_consensus::{
     clock::{duration_since_unix_epoch, get_current_unix_time_in_nanos},
     crypto::SecretKey,
-    primitives::{BlsPublicKey, Epoch, Root, Slot, U256},
+    primitives::{BlsPublicKey, Domain, Epoch, Hash32, Root, Slot, U256},
     ssz::prelude::HashTreeRoot,
     state_transition::Context,
     Error as ConsensusError, Fork,
 };
+use futures::{stream, stream::BoxStream, StreamExt};
 use mev_rs::{
-    blinded_block_relayer::{BlockSubmissionFilter, DeliveredPayloadFilter},
-    signing::{compute_consensus_domain, verify_signed_builder_data, verify_signed_data},
+    admin::Introspect,
+    blinded_block_relayer::{
+        BeaconNodeStatus, BlockSubmissionFilter, Client as RelayClient, DeliveredPayloadFilter,
+        LatePayloadRequestFilter, PayloadAttributesUpdate, RegistrationStats, RelayStatus,
+    },
+    signing::{compute_builder_domain, compute_consensus_domain, verify_signed_data},
     types::{
-        block_submission::data_api::{PayloadTrace, SubmissionTrace},
+        block_submission::data_api::{LatePayloadRequestTrace, PayloadTrace, SubmissionTrace},
         AuctionContents, AuctionRequest, BidTrace, ExecutionPayload, ExecutionPayloadHeader,
         ProposerSchedule, SignedBidSubmission, SignedBlindedBeaconBlock, SignedBuilderBid,
-        SignedValidatorRegistration,
+        SignedHeaderSubmission, SignedValidatorRegistration,
     },
     BlindedBlockDataProvider, BlindedBlockProvider, BlindedBlockRelayer, Error, ProposerScheduler,
     RelayError, ValidatorRegistry,
 };
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
+use serde::Deserialize;
+use serde_json::json;
 use std::{
-    cmp::Ordering,
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     ops::Deref,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
+use tokio::time::timeout;
 use tracing::{debug, error, info, trace, warn};
 
 #[cfg(not(feature = "minimal-preset"))]
@@ -51,8 +69,252 @@ use ethereum_consensus::{
 };
 
 // Sets the lifetime of an auction with respect to its proposal slot.
-const AUCTION_LIFETIME_SLOTS: Slot = 1;
-const HISTORY_LOOK_BEHIND_EPOCHS: Epoch = 4;
+const DEFAULT_AUCTION_LIFETIME_SLOTS: Slot = 1;
+// Number of registrations fetched from peer relays at once.
+const REGISTRATION_SYNC_CONCURRENCY: usize = 8;
+// Time to wait for a peer relay to serve a registration before trying the next one.
+const REGISTRATION_SYNC_TIMEOUT: Duration = Duration::from_secs(2);
+// Time to wait for the beacon node to report its sync status for the status endpoint.
+const BEACON_NODE_STATUS_TIMEOUT: Duration = Duration::from_secs(2);
+const DEFAULT_GET_HEADER_DELAY_MS: u64 = 0;
+const DEFAULT_GET_PAYLOAD_CUTOFF_MS: u64 = 4_000;
+const DEFAULT_HISTORY_EPOCHS: Epoch = 4;
+const DEFAULT_OUTBID_SUBMISSION_EPOCHS: Epoch = 1;
+const DEFAULT_MAX_OUTBID_SUBMISSIONS: usize = 4096;
+// About a day of archived submissions.
+const DEFAULT_SUBMISSION_ARCHIVE_EPOCHS: Epoch = 225;
+// Late signed blinded beacon blocks recorded for each auction, beyond which they are only logged.
+const MAX_LATE_PAYLOAD_REQUESTS_PER_AUCTION: usize = 4;
+
+/// Bounds on the auction history kept in memory to serve the data APIs
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Number of epochs to keep auctions, their best bids and delivered payloads
+    pub epochs: Epoch,
+    /// Number of epochs to keep submissions that were outbid, at most `epochs`
+    pub outbid_submission_epochs: Epoch,
+    /// Maximum number of outbid submissions to keep, evicting those of the oldest auctions first
+    pub max_outbid_submissions: usize,
+    /// Number of epochs to keep the submissions recorded to `submission_archive_path` on disk
+    pub submission_archive_epochs: Epoch,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            epochs: DEFAULT_HISTORY_EPOCHS,
+            outbid_submission_epochs: DEFAULT_OUTBID_SUBMISSION_EPOCHS,
+            max_outbid_submissions: DEFAULT_MAX_OUTBID_SUBMISSIONS,
+            submission_archive_epochs: DEFAULT_SUBMISSION_ARCHIVE_EPOCHS,
+        }
+    }
+}
+
+/// Checks made on block submissions beyond those on the bid trace
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ValidationConfig {
+    /// Require the last transaction of a payload to pay the proposer at least the value of the
+    /// bid
+    pub verify_proposer_payment: bool,
+    /// Accept payloads paying the proposer through their `fee_recipient` when verifying the
+    /// proposer payment, although their value can not be verified without simulating them
+    pub allow_fee_recipient_payment: bool,
+    /// Builders allowed to bid ahead of their payload with `/relay/v1/builder/headers`, as the
+    /// relay can not deliver a winning bid whose payload never arrives
+    pub header_submission_builders: Vec<BlsPublicKey>,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            verify_proposer_payment: true,
+            allow_fee_recipient_payment: false,
+            header_submission_builders: vec![],
+        }
+    }
+}
+
+/// Timing of the auctions run by the relay
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TimingConfig {
+    /// Number of slots after its proposal slot an auction stays open, e.g. to serve a late
+    /// `getPayload`
+    pub auction_lifetime_slots: Slot,
+    /// Time to wait before serving the best bid for a `getHeader` request, so bids arriving in
+    /// the meantime can still win; at most a third of a slot
+    pub get_header_delay_ms: u64,
+    /// Time into its slot after which a signed blinded beacon block is refused, as a block
+    /// published later would likely be orphaned
+    pub get_payload_cutoff_ms: u64,
+}
+
+impl Default for TimingConfig {
+    fn default() -> Self {
+        Self {
+            auction_lifetime_slots: DEFAULT_AUCTION_LIFETIME_SLOTS,
+            get_header_delay_ms: DEFAULT_GET_HEADER_DELAY_MS,
+            get_payload_cutoff_ms: DEFAULT_GET_PAYLOAD_CUTOFF_MS,
+        }
+    }
+}
+
+impl TimingConfig {
+    /// Checks the timing fits within the epochs of the network described by `context`
+    pub fn validate(&self, context: &Context) -> Result<(), Error> {
+        if self.auction_lifetime_slots == 0 || self.auction_lifetime_slots > context.slots_per_epoch
+        {
+            return Err(Error::InvalidConfig(format!(
+                "`auction_lifetime_slots` must be between 1 and {} slots, the length of an epoch",
+                context.slots_per_epoch
+            )))
+        }
+        // NOTE: the proposer must still have time to sign and publish the block
+        let max_delay_ms = context.seconds_per_slot * 1_000 / 3;
+        if self.get_header_delay_ms > max_delay_ms {
+            return Err(Error::InvalidConfig(format!(
+                "`get_header_delay_ms` must be at most {max_delay_ms} ms, a third of a slot"
+            )))
+        }
+        let slot_ms = context.seconds_per_slot * 1_000;
+        if self.get_payload_cutoff_ms == 0 || self.get_payload_cutoff_ms > slot_ms {
+            return Err(Error::InvalidConfig(format!(
+                "`get_payload_cutoff_ms` must be between 1 and {slot_ms} ms, the length of a slot"
+            )))
+        }
+        Ok(())
+    }
+}
+
+// Fields of an execution payload, or of its header, that must match the bid trace
+struct PayloadFields<'a> {
+    gas_limit: u64,
+    gas_used: u64,
+    parent_hash: &'a Hash32,
+    block_hash: &'a Hash32,
+}
+
+impl<'a> From<&'a ExecutionPayload> for PayloadFields<'a> {
+    fn from(payload: &'a ExecutionPayload) -> Self {
+        Self {
+            gas_limit: payload.gas_limit(),
+            gas_used: payload.gas_used(),
+            parent_hash: payload.parent_hash(),
+            block_hash: payload.block_hash(),
+        }
+    }
+}
+
+impl<'a> From<&'a ExecutionPayloadHeader> for PayloadFields<'a> {
+    fn from(header: &'a ExecutionPayloadHeader) -> Self {
+        Self {
+            gas_limit: header.gas_limit(),
+            gas_used: header.gas_used(),
+            parent_hash: header.parent_hash(),
+            block_hash: header.block_hash(),
+        }
+    }
+}
+
+pub(crate) fn validate_allowed_builder(
+    builder_registry: &BuilderRegistry,
+    builder_deny_list: &BuilderDenyList,
+    builder_public_key: &BlsPublicKey,
+) -> Result<(), Error> {
+    if builder_deny_list.contains(builder_public_key) {
+        return Err(RelayError::BuilderDenied(builder_public_key.clone()).into())
+    }
+    if builder_registry.contains(builder_public_key) {
+        Ok(())
+    } else {
+        Err(RelayError::BuilderNotRegistered(builder_public_key.clone()).into())
+    }
+}
+
+// Assume:
+// - `execution_payload` is valid
+// - pays the proposer the amount claimed in the `bid_trace`, see `verify_proposer_payment`
+// - respects the proposer's preferred gas limit, within protocol tolerance
+fn validate_submission_trusted(
+    bid_trace: &BidTrace,
+    payload: PayloadFields<'_>,
+    signed_registration: &SignedValidatorRegistration,
+) -> Result<(), RelayError> {
+    let proposer_public_key = &bid_trace.proposer_public_key;
+    if bid_trace.proposer_fee_recipient != signed_registration.message.fee_recipient {
+        let fee_recipient = &signed_registration.message.fee_recipient;
+        return Err(RelayError::InvalidFeeRecipient(
+            proposer_public_key.clone(),
+            fee_recipient.clone(),
+        ))
+    }
+
+    // NOTE: disabled in the "trusted" validation
+    // let adjusted_gas_limit =
+    //     self.compute_adjusted_gas_limit(signed_registration.message.gas_limit);
+    // if bid_trace.gas_limit != adjusted_gas_limit {
+    //     return Err(Error::InvalidGasLimitForProposer(
+    //         proposer_public_key.clone(),
+    //         adjusted_gas_limit,
+    //     ))
+    // }
+
+    if bid_trace.gas_limit != payload.gas_limit {
+        return Err(RelayError::InvalidGasLimit(bid_trace.gas_limit, payload.gas_limit))
+    }
+
+    if bid_trace.gas_used != payload.gas_used {
+        return Err(RelayError::InvalidGasUsed(bid_trace.gas_used, payload.gas_used))
+    }
+
+    if &bid_trace.parent_hash != payload.parent_hash {
+        return Err(RelayError::InvalidParentHash(
+            bid_trace.parent_hash.clone(),
+            payload.parent_hash.clone(),
+        ))
+    }
+
+    if &bid_trace.block_hash != payload.block_hash {
+        return Err(RelayError::InvalidBlockHash(
+            bid_trace.block_hash.clone(),
+            payload.block_hash.clone(),
+        ))
+    }
+
+    Ok(())
+}
+
+/// Validates `signed_submission` as the relay does for a proposer with `signed_registration`,
+/// apart from checking the builder is accepted and the auction is open
+pub fn validate_bid_submission(
+    signed_submission: &SignedBidSubmission,
+    signed_registration: &SignedValidatorRegistration,
+    validation: &ValidationConfig,
+    builder_domain: Domain,
+) -> Result<(), Error> {
+    let bid_trace = signed_submission.message();
+    validate_submission_trusted(
+        bid_trace,
+        signed_submission.payload().into(),
+        signed_registration,
+    )?;
+    if validation.verify_proposer_payment {
+        verify_proposer_payment(
+            bid_trace,
+            signed_submission.payload(),
+            validation.allow_fee_recipient_payment,
+        )?;
+    }
+    verify_signed_data(
+        bid_trace,
+        signed_submission.signature(),
+        &bid_trace.builder_public_key,
+        builder_domain,
+    )?;
+    Ok(())
+}
 
 fn validate_header_equality(
     local_header: &ExecutionPayloadHeader,
@@ -225,53 +487,135 @@ pub struct Inner {
     public_key: BlsPublicKey,
     validator_registry: ValidatorRegistry,
     proposer_scheduler: ProposerScheduler,
-    builder_registry: HashSet<BlsPublicKey>,
+    pub(crate) builder_registry: BuilderRegistry,
+    pub(crate) builder_deny_list: BuilderDenyList,
+    submission_archive: SubmissionArchive,
+    audit_log: AuditLog,
+    trace_store: TraceStore,
     beacon_node: ApiClient,
+    network: String,
     context: Context,
-    state: Mutex<State>,
+    // contains validator public keys that have been updated since we last refreshed
+    // the proposer scheduler
+    outstanding_validator_updates: Mutex<HashSet<BlsPublicKey>>,
+    // duty dependent roots of the latest head, to notice when proposer duties may have changed
+    duty_dependent_roots: Mutex<Option<(Root, Root)>>,
+    // builders currently allowed to submit header-only bids; a builder is removed if the payload
+    // of one of its winning bids does not arrive in time
+    header_submission_builders: RwLock<HashSet<BlsPublicKey>>,
+    // NOTE: auction state is split by concern so submissions, `getHeader` and the data APIs
+    // do not contend on a single lock; take at most one of these locks at a time
+    open_auctions: RwLock<HashSet<AuctionRequest>>,
+    bids: RwLock<Bids>,
+    payloads: RwLock<Payloads>,
+    genesis_time: u64,
     genesis_validators_root: Root,
+    // domain of the builder messages on this network, fixed by the context
+    builder_domain: Domain,
+    history: HistoryConfig,
+    timing: TimingConfig,
+    validation: ValidationConfig,
+    // relays to import the registrations of upcoming proposers from
+    registration_peers: Vec<RelayClient>,
+    pub(crate) events: AuctionEvents,
+    // payload attributes of upcoming proposals, pushed to subscribed builders
+    payload_attributes: PayloadAttributesFeed,
+    auction_metrics: AuctionMetrics,
+    // sync status of the beacon node as of the latest slot, served from the status endpoint;
+    // `None` if the beacon node could not be reached
+    beacon_node_status: RwLock<Option<BeaconNodeStatus>>,
 }
 
+// Bids received for each auction
 #[derive(Debug, Default)]
-struct State {
-    // contains validator public keys that have been updated since we last refreshed
-    // the proposer scheduler
-    outstanding_validator_updates: HashSet<BlsPublicKey>,
-
-    // auction state
-    open_auctions: HashSet<AuctionRequest>,
+struct Bids {
     auctions: HashMap<AuctionRequest, Arc<AuctionContext>>,
-    // keeps set of all submissions that are _NOT_ the current best bid.
+    // best bid submitted without its payload for each auction, if better than the best in
+    // `auctions`; dropped once outbid or its payload arrives
+    header_bids: HashMap<AuctionRequest, Arc<HeaderBid>>,
+    // keeps all submissions that are _NOT_ the current best bid.
     // the current best bid is stored in `auctions`.
-    other_submissions: HashMap<AuctionRequest, HashSet<AuctionContext>>,
+    // ordered so the submissions of the oldest auctions are evicted first
+    other_submissions: BTreeMap<AuctionRequest, HashSet<Arc<AuctionContext>>>,
+    other_submission_count: usize,
+    // traces imported from the data API of another relay, served alongside local traces
+    imported_submissions: HashMap<AuctionRequest, Vec<SubmissionTrace>>,
+    // index over the traces above to serve filtered queries from the data APIs
+    submission_index: TraceIndex,
+}
+
+// Payloads delivered to proposers
+#[derive(Debug, Default)]
+struct Payloads {
     delivered_payloads: HashMap<AuctionRequest, Arc<AuctionContext>>,
+    // traces imported from the data API of another relay, served alongside local traces
+    imported_payloads: HashMap<AuctionRequest, PayloadTrace>,
+    // index over the traces above to serve filtered queries from the data APIs
+    payload_index: TraceIndex,
+    // signed blinded beacon blocks refused for arriving past the cutoff in their slot
+    late_payload_requests: BTreeMap<AuctionRequest, Vec<LatePayloadRequestTrace>>,
 }
 
 impl Relay {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         beacon_node: ApiClient,
         secret_key: SecretKey,
-        accepted_builders: Vec<BlsPublicKey>,
+        builder_registry: BuilderRegistry,
+        builder_deny_list: BuilderDenyList,
+        validator_registry: ValidatorRegistry,
+        submission_archive: SubmissionArchive,
+        audit_log: AuditLog,
+        trace_store: TraceStore,
+        network: String,
         context: Context,
+        genesis_time: u64,
         genesis_validators_root: Root,
-    ) -> Self {
+        history: HistoryConfig,
+        timing: TimingConfig,
+        validation: ValidationConfig,
+        registration_peers: Vec<RelayClient>,
+    ) -> Result<Self, Error> {
         let public_key = secret_key.public_key();
-        let slots_per_epoch = context.slots_per_epoch;
-        let validator_registry = ValidatorRegistry::new(beacon_node.clone(), slots_per_epoch);
-        let proposer_scheduler = ProposerScheduler::new(beacon_node.clone(), slots_per_epoch);
+        let builder_domain = compute_builder_domain(&context)?;
+        let header_submission_builders =
+            HashSet::from_iter(validation.header_submission_builders.iter().cloned());
+        let proposer_scheduler = ProposerScheduler::new(beacon_node.clone());
         let inner = Inner {
             secret_key,
             public_key,
             validator_registry,
             proposer_scheduler,
-            builder_registry: HashSet::from_iter(accepted_builders),
+            builder_registry,
+            builder_deny_list,
+            submission_archive,
+            audit_log,
+            trace_store,
             beacon_node,
+            network,
             context,
-            state: Default::default(),
+            outstanding_validator_updates: Default::default(),
+            duty_dependent_roots: Default::default(),
+            header_submission_builders: RwLock::new(header_submission_builders),
+            open_auctions: Default::default(),
+            bids: Default::default(),
+            payloads: Default::default(),
+            genesis_time,
             genesis_validators_root,
+            builder_domain,
+            history,
+            timing,
+            validation,
+            registration_peers,
+            events: Default::default(),
+            payload_attributes: Default::default(),
+            auction_metrics: Default::default(),
+            beacon_node_status: Default::default(),
         };
         info!(public_key = %inner.public_key, "relay initialized");
-        Self(Arc::new(inner))
+        let relay = Self(Arc::new(inner));
+        relay.insert_imported_traces(relay.trace_store.load());
+        Ok(relay)
     }
 
     pub async fn on_epoch(&self, epoch: Epoch) {
@@ -281,20 +625,145 @@ impl Relay {
             error!(%err, epoch, "could not update validator registry");
         }
         self.refresh_proposer_schedule(epoch).await;
+        self.spawn_registration_sync();
+
+        let slots_per_epoch = self.context.slots_per_epoch;
+        let retain_slot =
+            epoch.checked_sub(self.history.epochs).unwrap_or_default() * slots_per_epoch;
+        let outbid_epochs = self.history.outbid_submission_epochs.min(self.history.epochs);
+        let outbid_retain_slot =
+            epoch.checked_sub(outbid_epochs).unwrap_or_default() * slots_per_epoch;
+        trace!(retain_slot, outbid_retain_slot, "pruning stale auctions");
+        let stale = {
+            let mut bids = self.bids.write();
+            let bids = &mut *bids;
+            bids.auctions.retain(|auction_request, _| auction_request.slot >= retain_slot);
+            bids.header_bids.retain(|auction_request, _| auction_request.slot >= retain_slot);
+            let outbid_retain_request =
+                AuctionRequest { slot: outbid_retain_slot, ..Default::default() };
+            let retained = bids.other_submissions.split_off(&outbid_retain_request);
+            let stale = std::mem::replace(&mut bids.other_submissions, retained);
+            bids.other_submission_count -= stale.values().map(HashSet::len).sum::<usize>();
+            bids.imported_submissions
+                .retain(|auction_request, _| auction_request.slot >= retain_slot);
+            bids.submission_index.retain(|auction_request| auction_request.slot >= retain_slot);
+            stale
+        };
+        // NOTE: release the (potentially large) stale payloads outside of the lock
+        drop(stale);
+
+        {
+            let mut payloads = self.payloads.write();
+            let payloads = &mut *payloads;
+            payloads
+                .delivered_payloads
+                .retain(|auction_request, _| auction_request.slot >= retain_slot);
+            payloads
+                .imported_payloads
+                .retain(|auction_request, _| auction_request.slot >= retain_slot);
+            payloads.payload_index.retain(|auction_request| auction_request.slot >= retain_slot);
+            let retain_request = AuctionRequest { slot: retain_slot, ..Default::default() };
+            payloads.late_payload_requests =
+                payloads.late_payload_requests.split_off(&retain_request);
+        }
 
-        let retain_slot = epoch.checked_sub(HISTORY_LOOK_BEHIND_EPOCHS).unwrap_or_default() *
-            self.context.slots_per_epoch;
-        trace!(retain_slot, "pruning stale auctions");
-        let mut state = self.state.lock();
-        state.auctions.retain(|auction_request, _| auction_request.slot >= retain_slot);
-        state.other_submissions.retain(|auction_request, _| auction_request.slot >= retain_slot);
-        state.delivered_payloads.retain(|auction_request, _| auction_request.slot >= retain_slot);
+        self.trace_store.prune(retain_slot);
+        let archive_retain_slot =
+            epoch.checked_sub(self.history.submission_archive_epochs).unwrap_or_default() *
+                slots_per_epoch;
+        // NOTE: only slots up to the next one are archived, so anything further ahead is stale
+        let archive_horizon_slot = (epoch + 2) * slots_per_epoch;
+        self.submission_archive.prune(archive_retain_slot..=archive_horizon_slot);
     }
 
     async fn refresh_proposer_schedule(&self, epoch: Epoch) {
         if let Err(err) = self.proposer_scheduler.on_epoch(epoch, &self.validator_registry).await {
             error!(%err, epoch, "could not refresh proposer schedule");
         }
+        self.log_proposer_schedule();
+    }
+
+    // Imports the registrations of upcoming proposers unknown to this relay from the
+    // `registration_peers`, e.g. so a new relay does not wait for each validator to register.
+    // Runs in the background, as slow peers should not hold up the epoch processing.
+    fn spawn_registration_sync(&self) {
+        if self.registration_peers.is_empty() {
+            return
+        }
+        let relay = self.clone();
+        std::mem::drop(tokio::spawn(async move { relay.sync_registrations().await }));
+    }
+
+    async fn sync_registrations(&self) {
+        let public_keys = self.proposer_scheduler.unregistered_proposers(&self.validator_registry);
+        if public_keys.is_empty() {
+            return
+        }
+
+        let requested = public_keys.len();
+        let registrations = stream::iter(public_keys)
+            .map(|public_key| async move {
+                for peer in &self.registration_peers {
+                    let request = peer.fetch_validator_registration(&public_key);
+                    match timeout(REGISTRATION_SYNC_TIMEOUT, request).await {
+                        Ok(Ok(registration)) => return Some(registration),
+                        Ok(Err(err)) => {
+                            trace!(%err, %public_key, "could not fetch registration from peer")
+                        }
+                        Err(_) => trace!(%public_key, "timed out fetching registration from peer"),
+                    }
+                }
+                None
+            })
+            .buffer_unordered(REGISTRATION_SYNC_CONCURRENCY)
+            .filter_map(|registration| async move { registration })
+            .collect::<Vec<_>>()
+            .await;
+        if registrations.is_empty() {
+            return
+        }
+
+        // NOTE: registrations from peers are verified like any other
+        let current_time = get_current_unix_time_in_nanos().try_into().expect("fits in type");
+        let (updated_keys, errs) = self.validator_registry.process_registrations(
+            &registrations,
+            current_time,
+            &self.context,
+        );
+        if !errs.is_empty() {
+            warn!(?errs, "error processing some registrations from peers");
+        }
+        info!(requested, imported = updated_keys.len(), "imported registrations from peer relays");
+        // NOTE: the proposer schedule is refreshed with the new registrations on the next slot
+        self.outstanding_validator_updates.lock().extend(updated_keys);
+    }
+
+    // Caches the sync status of the beacon node so the status endpoint does not wait on it,
+    // refreshing it in the background so a slow beacon node does not hold up the slot processing
+    fn spawn_beacon_node_status_refresh(&self) {
+        let relay = self.clone();
+        std::mem::drop(tokio::spawn(async move {
+            let request = relay.beacon_node.get_sync_status();
+            let status = match timeout(BEACON_NODE_STATUS_TIMEOUT, request).await {
+                Ok(Ok(status)) => Some(BeaconNodeStatus {
+                    head_slot: status.head_slot,
+                    sync_distance: status.sync_distance,
+                    is_syncing: status.is_syncing,
+                }),
+                Ok(Err(err)) => {
+                    warn!(%err, "could not get sync status of beacon node");
+                    None
+                }
+                Err(_) => {
+                    warn!("timed out getting sync status of beacon node");
+                    None
+                }
+            };
+            *relay.beacon_node_status.write() = status;
+        }));
+    }
+
+    fn log_proposer_schedule(&self) {
         if let Ok(schedule) = self.proposer_scheduler.get_proposal_schedule() {
             let proposal_slots = schedule
                 .into_iter()
@@ -306,13 +775,11 @@ impl Relay {
 
     pub async fn on_slot(&self, slot: Slot) {
         info!(slot, "processing");
+        self.auction_metrics.on_slot(slot);
 
         // TODO: no reason to wait for slot boundary,
         // but likely want some more sophisticated channel machinery to dispatch updates
-        let keys_to_refresh = {
-            let mut state = self.state.lock();
-            HashSet::<BlsPublicKey>::from_iter(state.outstanding_validator_updates.drain())
-        };
+        let keys_to_refresh = std::mem::take(&mut *self.outstanding_validator_updates.lock());
         if !keys_to_refresh.is_empty() {
             // TODO: can be more precise with which proposers to update
             // for now, just refresh them all...
@@ -320,46 +787,212 @@ impl Relay {
             self.refresh_proposer_schedule(epoch).await;
         }
 
-        trace!(retain_slot = slot - AUCTION_LIFETIME_SLOTS, "dropping old auctions");
-        let mut state = self.state.lock();
-        state
-            .open_auctions
-            .retain(|auction_request| auction_request.slot + AUCTION_LIFETIME_SLOTS >= slot);
+        let lifetime = self.timing.auction_lifetime_slots;
+        trace!(retain_slot = slot.saturating_sub(lifetime), "dropping old auctions");
+        self.open_auctions
+            .write()
+            .retain(|auction_request| auction_request.slot + lifetime >= slot);
+
+        self.spawn_beacon_node_status_refresh();
+    }
+
+    /// Refreshes the proposer duties if the duty dependent roots of the new head differ from
+    /// those of the previous head
+    pub fn on_head(&self, event: HeadEvent) {
+        let roots = (event.previous_duty_dependent_root, event.current_duty_dependent_root);
+        let changed = {
+            let mut duty_dependent_roots = self.duty_dependent_roots.lock();
+            let changed = duty_dependent_roots.as_ref().is_some_and(|known| known != &roots);
+            *duty_dependent_roots = Some(roots);
+            changed
+        };
+        if changed {
+            info!(slot = event.slot, block = %event.block, "duty dependent roots changed with new head");
+            self.spawn_duty_refresh(event.slot / self.context.slots_per_epoch);
+        }
+    }
+
+    /// Refreshes the proposer duties after a re-org of the chain
+    pub fn on_chain_reorg(&self, event: ChainReorgEvent) {
+        warn!(
+            slot = event.slot,
+            depth = event.depth,
+            old_head = %event.old_head_block,
+            new_head = %event.new_head_block,
+            "chain re-org"
+        );
+        self.spawn_duty_refresh(event.epoch);
+    }
+
+    // Checks the proposer duties for `epoch` and the next epoch for changes, off of the task
+    // following the chain so events are not held up on the beacon node.
+    fn spawn_duty_refresh(&self, epoch: Epoch) {
+        let relay = self.clone();
+        std::mem::drop(tokio::spawn(async move {
+            match relay.proposer_scheduler.on_reorg(epoch, &relay.validator_registry).await {
+                Ok(true) => relay.log_proposer_schedule(),
+                Ok(false) => {}
+                Err(err) => error!(%err, epoch, "could not check proposer schedule after re-org"),
+            }
+        }));
     }
 
-    // TODO: build tip context and support reorgs...
+    /// Opens the auction described by `event`
+    ///
+    /// If the head of the chain changed within the slot, the auctions built on the previous head
+    /// are closed and the proposer schedule is checked for changes to the proposer duties.
     pub fn on_payload_attributes(&self, event: PayloadAttributesEvent) -> Result<(), Error> {
         trace!(?event, "processing payload attributes");
         let proposer_public_key =
             self.validator_registry.get_public_key(event.proposer_index).ok_or_else::<Error, _>(
                 || RelayError::UnknownValidatorIndex(event.proposer_index).into(),
             )?;
+        let slot = event.proposal_slot;
         let auction_request = AuctionRequest {
-            slot: event.proposal_slot,
-            parent_hash: event.parent_block_hash,
+            slot,
+            parent_hash: event.parent_block_hash.clone(),
             public_key: proposer_public_key,
         };
-        let mut state = self.state.lock();
-        state.open_auctions.insert(auction_request);
+        let (reorged_auctions, is_new_auction) = {
+            let mut open_auctions = self.open_auctions.write();
+            let reorged_auctions = open_auctions
+                .iter()
+                .filter(|open_auction| {
+                    open_auction.slot == slot &&
+                        open_auction.parent_hash != auction_request.parent_hash
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            for open_auction in &reorged_auctions {
+                open_auctions.remove(open_auction);
+            }
+            let is_new_auction = open_auctions.insert(auction_request.clone());
+            if is_new_auction {
+                self.auction_metrics.record_auction_opened(slot);
+                self.events.publish(AuctionEvent::AuctionOpened {
+                    slot,
+                    parent_hash: auction_request.parent_hash.clone(),
+                    proposer_public_key: auction_request.public_key.clone(),
+                });
+            }
+            (reorged_auctions, is_new_auction)
+        };
+
+        // NOTE: only proposers with a registration can be built for, so skip the rest
+        if is_new_auction {
+            let registration =
+                self.validator_registry.get_signed_registration(&auction_request.public_key);
+            self.submission_archive.record_auction(ArchivedAuction {
+                auction_request: auction_request.clone(),
+                registration: registration.as_deref().cloned(),
+            });
+            self.payload_attributes.publish(event, registration.as_deref());
+        }
+
+        if !reorged_auctions.is_empty() {
+            warn!(%auction_request, ?reorged_auctions, "head changed within slot; closed auctions on previous head");
+            self.spawn_duty_refresh(slot / self.context.slots_per_epoch);
+        }
         Ok(())
     }
 
+    /// Imports traces from the data API of another relay, returning the number of new delivered
+    /// payloads and block submissions
+    ///
+    /// Imported traces are persisted to the trace store, if configured, and kept for as long as
+    /// the relay's own history.
+    pub fn import_traces(
+        &self,
+        payloads: Vec<PayloadTrace>,
+        submissions: Vec<SubmissionTrace>,
+    ) -> (usize, usize) {
+        let traces = payloads
+            .into_iter()
+            .map(ImportedTrace::DeliveredPayload)
+            .chain(submissions.into_iter().map(ImportedTrace::BlockSubmission))
+            .collect();
+        let imported = self.insert_imported_traces(traces);
+        let payload_count = imported
+            .iter()
+            .filter(|trace| matches!(trace, ImportedTrace::DeliveredPayload(..)))
+            .count();
+        let submission_count = imported.len() - payload_count;
+        self.trace_store.record(imported);
+        (payload_count, submission_count)
+    }
+
+    // Adds the `traces` not yet known to the relay, returning them.
+    fn insert_imported_traces(&self, traces: Vec<ImportedTrace>) -> Vec<ImportedTrace> {
+        let (payloads, submissions): (Vec<_>, Vec<_>) = traces
+            .into_iter()
+            .partition(|trace| matches!(trace, ImportedTrace::DeliveredPayload(..)));
+        let mut imported = vec![];
+
+        let mut state = self.payloads.write();
+        for trace in payloads {
+            let ImportedTrace::DeliveredPayload(trace) = trace else { continue };
+            let auction_request = AuctionRequest {
+                slot: trace.slot,
+                parent_hash: trace.parent_hash.clone(),
+                public_key: trace.proposer_public_key.clone(),
+            };
+            if state.delivered_payloads.contains_key(&auction_request) ||
+                state.imported_payloads.contains_key(&auction_request)
+            {
+                continue
+            }
+            state.payload_index.insert(
+                &auction_request,
+                &trace.block_hash,
+                &trace.builder_public_key,
+            );
+            state.imported_payloads.insert(auction_request, trace.clone());
+            imported.push(ImportedTrace::DeliveredPayload(trace));
+        }
+        drop(state);
+
+        let mut state = self.bids.write();
+        for trace in submissions {
+            let ImportedTrace::BlockSubmission(trace) = trace else { continue };
+            let auction_request = AuctionRequest {
+                slot: trace.slot,
+                parent_hash: trace.parent_hash.clone(),
+                public_key: trace.proposer_public_key.clone(),
+            };
+            let traces = state.imported_submissions.entry(auction_request.clone()).or_default();
+            let is_duplicate = traces.iter().any(|existing| {
+                existing.block_hash == trace.block_hash &&
+                    existing.builder_public_key == trace.builder_public_key &&
+                    existing.timestamp_ms == trace.timestamp_ms
+            });
+            if is_duplicate {
+                continue
+            }
+            traces.push(trace.clone());
+            state.submission_index.insert(
+                &auction_request,
+                &trace.block_hash,
+                &trace.builder_public_key,
+            );
+            imported.push(ImportedTrace::BlockSubmission(trace));
+        }
+        imported
+    }
+
     fn get_auction_context(&self, auction_request: &AuctionRequest) -> Option<Arc<AuctionContext>> {
-        let state = self.state.lock();
-        state.auctions.get(auction_request).cloned()
+        self.bids.read().auctions.get(auction_request).cloned()
     }
 
     fn validate_allowed_builder(&self, builder_public_key: &BlsPublicKey) -> Result<(), Error> {
-        if self.builder_registry.contains(builder_public_key) {
-            Ok(())
-        } else {
-            Err(RelayError::BuilderNotRegistered(builder_public_key.clone()).into())
-        }
+        validate_allowed_builder(
+            &self.builder_registry,
+            &self.builder_deny_list,
+            builder_public_key,
+        )
     }
 
     fn validate_auction_request(&self, auction_request: &AuctionRequest) -> Result<(), RelayError> {
-        let state = self.state.lock();
-        if state.open_auctions.contains(auction_request) {
+        if self.open_auctions.read().contains(auction_request) {
             Ok(())
         } else {
             let err = RelayError::InvalidAuctionRequest(auction_request.clone());
@@ -373,65 +1006,13 @@ impl Relay {
     //     compute_preferred_gas_limit(preferred_gas_limit, parent_gas_limit)
     // }
 
-    // Assume:
-    // - `execution_payload` is valid
-    // - pays the proposer the amount claimed in the `bid_trace`
-    // - respects the proposer's preferred gas limit, within protocol tolerance
-    fn validate_builder_submission_trusted(
+    fn get_signed_registration(
         &self,
-        bid_trace: &BidTrace,
-        execution_payload: &ExecutionPayload,
-    ) -> Result<(), RelayError> {
-        let proposer_public_key = &bid_trace.proposer_public_key;
-        let signed_registration = self
-            .validator_registry
+        proposer_public_key: &BlsPublicKey,
+    ) -> Result<Arc<SignedValidatorRegistration>, RelayError> {
+        self.validator_registry
             .get_signed_registration(proposer_public_key)
-            .ok_or_else(|| RelayError::ValidatorNotRegistered(proposer_public_key.clone()))?;
-
-        if bid_trace.proposer_fee_recipient != signed_registration.message.fee_recipient {
-            let fee_recipient = &signed_registration.message.fee_recipient;
-            return Err(RelayError::InvalidFeeRecipient(
-                proposer_public_key.clone(),
-                fee_recipient.clone(),
-            ))
-        }
-
-        // NOTE: disabled in the "trusted" validation
-        // let adjusted_gas_limit =
-        //     self.compute_adjusted_gas_limit(signed_registration.message.gas_limit);
-        // if bid_trace.gas_limit != adjusted_gas_limit {
-        //     return Err(Error::InvalidGasLimitForProposer(
-        //         proposer_public_key.clone(),
-        //         adjusted_gas_limit,
-        //     ))
-        // }
-
-        if bid_trace.gas_limit != execution_payload.gas_limit() {
-            return Err(RelayError::InvalidGasLimit(
-                bid_trace.gas_limit,
-                execution_payload.gas_limit(),
-            ))
-        }
-
-        if bid_trace.gas_used != execution_payload.gas_used() {
-            return Err(RelayError::InvalidGasUsed(bid_trace.gas_used, execution_payload.gas_used()))
-        }
-
-        if &bid_trace.parent_hash != execution_payload.parent_hash() {
-            return Err(RelayError::InvalidParentHash(
-                bid_trace.parent_hash.clone(),
-                execution_payload.parent_hash().clone(),
-            ))
-        }
-
-        if &bid_trace.block_hash != execution_payload.block_hash() {
-            return Err(RelayError::InvalidBlockHash(
-                bid_trace.block_hash.clone(),
-                execution_payload.block_hash().clone(),
-            ))
-        }
-
-        Ok(())
+            .ok_or_else(|| RelayError::ValidatorNotRegistered(proposer_public_key.clone()))
     }
 
     fn insert_bid_if_greater(
@@ -447,39 +1028,204 @@ impl Relay {
                 return Ok(())
             }
         }
+        // NOTE: compute the header and sign the bid before taking the lock
         let auction_context = AuctionContext::new(
-            signed_submission.clone(),
+            signed_submission,
             receive_duration,
             self.public_key.clone(),
             &self.secret_key,
             &self.context,
         )?;
         let auction_context = Arc::new(auction_context);
-        let block_hash = auction_context.execution_payload().block_hash();
+        let block_hash = auction_context.execution_payload().block_hash().clone();
         let txn_count = auction_context.execution_payload().transactions().len();
         let blob_count =
             auction_context.blobs_bundle().map(|bundle| bundle.blobs.len()).unwrap_or_default();
-        info!(%auction_request, builder_public_key = %auction_context.builder_public_key(), %block_hash, txn_count, blob_count, "inserting new bid");
-        let mut state = self.state.lock();
-        let old_context = state.auctions.insert(auction_request.clone(), auction_context);
-
-        // NOTE: save other submissions for data APIs
-        if let Some(context) = old_context {
-            // TODO: better way to remove from `Arc`?
-            if let Some(context) = Arc::into_inner(context) {
+        let builder_public_key = auction_context.builder_public_key().clone();
+
+        let (is_best_bid, evicted) = {
+            let mut state = self.bids.write();
+            state.submission_index.insert(&auction_request, &block_hash, &builder_public_key);
+            // NOTE: a better bid may have arrived while this one was being prepared
+            let outbid = match state.auctions.get(&auction_request) {
+                Some(bid) if bid.value() > value => Some(auction_context),
+                _ => {
+                    info!(%auction_request, %builder_public_key, %block_hash, txn_count, blob_count, "inserting new bid");
+                    if state
+                        .header_bids
+                        .get(&auction_request)
+                        .map(|header_bid| header_bid.value() <= value)
+                        .unwrap_or_default()
+                    {
+                        state.header_bids.remove(&auction_request);
+                    }
+                    state.auctions.insert(auction_request.clone(), auction_context)
+                }
+            };
+
+            // NOTE: a greater header-only bid for the auction remains the best bid
+            let is_best_bid = outbid.is_none() && !state.header_bids.contains_key(&auction_request);
+            // NOTE: save other submissions for data APIs
+            if let Some(context) = outbid {
                 let entry = state.other_submissions.entry(auction_request).or_default();
-                entry.insert(context);
+                if entry.insert(context) {
+                    state.other_submission_count += 1;
+                }
+            }
+            let mut evicted = vec![];
+            while state.other_submission_count > self.history.max_outbid_submissions {
+                let Some((auction_request, contexts)) = state.other_submissions.pop_first() else {
+                    break
+                };
+                debug!(%auction_request, count = contexts.len(), "evicting outbid submissions");
+                state.other_submission_count -= contexts.len();
+                evicted.push(contexts);
             }
+            (is_best_bid, evicted)
+        };
+        // NOTE: release the (potentially large) evicted payloads outside of the lock
+        drop(evicted);
+        if is_best_bid {
+            self.auction_metrics.record_best_bid(auction_request.slot, value, receive_duration);
+            self.events.publish(AuctionEvent::NewBestBid {
+                slot: auction_request.slot,
+                parent_hash: auction_request.parent_hash.clone(),
+                proposer_public_key: auction_request.public_key.clone(),
+                builder_public_key,
+                block_hash,
+                value,
+                header_only: false,
+            });
+        }
+        Ok(())
+    }
+
+    fn insert_header_bid_if_greater(
+        &self,
+        auction_request: AuctionRequest,
+        signed_submission: SignedHeaderSubmission,
+        receive_duration: Duration,
+    ) -> Result<(), Error> {
+        let value = signed_submission.message.value;
+        let header_bid = HeaderBid::new(
+            signed_submission,
+            receive_duration,
+            self.public_key.clone(),
+            &self.secret_key,
+            &self.context,
+        )?;
+        let block_hash = header_bid.bid_trace.block_hash.clone();
+        let builder_public_key = header_bid.bid_trace.builder_public_key.clone();
+
+        let mut state = self.bids.write();
+        let best_value = state
+            .auctions
+            .get(&auction_request)
+            .map(|bid| bid.value())
+            .into_iter()
+            .chain(state.header_bids.get(&auction_request).map(|bid| bid.value()))
+            .max();
+        if best_value.map(|best_value| best_value > value).unwrap_or_default() {
+            info!(%auction_request, %builder_public_key, %block_hash, "header submission was not greater in value; ignoring");
+            return Ok(())
         }
+        info!(%auction_request, %builder_public_key, %block_hash, "inserting new header-only bid");
+        state.header_bids.insert(auction_request.clone(), Arc::new(header_bid));
+        drop(state);
+
+        self.auction_metrics.record_best_bid(auction_request.slot, value, receive_duration);
+        self.events.publish(AuctionEvent::NewBestBid {
+            slot: auction_request.slot,
+            parent_hash: auction_request.parent_hash,
+            proposer_public_key: auction_request.public_key,
+            builder_public_key,
+            block_hash,
+            value,
+            header_only: true,
+        });
         Ok(())
     }
 
+    // Validates `signed_submission`, keeping it as the best bid of its auction if it pays the most.
+    fn process_bid_submission(
+        &self,
+        signed_submission: &SignedBidSubmission,
+        receive_duration: Duration,
+    ) -> Result<(), Error> {
+        let (auction_request, value) = {
+            let bid_trace = signed_submission.message();
+            let builder_public_key = &bid_trace.builder_public_key;
+            self.validate_allowed_builder(builder_public_key)?;
+
+            let auction_request = AuctionRequest {
+                slot: bid_trace.slot,
+                parent_hash: bid_trace.parent_hash.clone(),
+                public_key: bid_trace.proposer_public_key.clone(),
+            };
+            if let Err(err) = self.validate_auction_request(&auction_request) {
+                warn!(%err, "could not validate bid submission");
+                return Err(err.into())
+            }
+
+            let registration = self.get_signed_registration(&bid_trace.proposer_public_key)?;
+            validate_bid_submission(
+                signed_submission,
+                &registration,
+                &self.validation,
+                self.builder_domain,
+            )?;
+            debug!(%auction_request, "validated builder submission");
+            (auction_request, bid_trace.value)
+        };
+
+        // NOTE: this does _not_ respect cancellations
+        // TODO: move to regime where we track best bid by builder
+        // and also move logic to cursor best bid for auction off this API
+        self.insert_bid_if_greater(auction_request, signed_submission, value, receive_duration)?;
+
+        Ok(())
+    }
+
+    // Refuses `signed_block` if it arrived past the cutoff in its slot, recording the request
+    // for the data API. The signature of `signed_block` must be verified beforehand.
+    fn validate_payload_request_timing(
+        &self,
+        auction_request: &AuctionRequest,
+        signed_block: &SignedBlindedBeaconBlock,
+    ) -> Result<(), RelayError> {
+        let slot = auction_request.slot;
+        let slot_start_ms = (self.genesis_time + slot * self.context.seconds_per_slot) * 1_000;
+        let receive_duration = duration_since_unix_epoch();
+        let ms_into_slot = (receive_duration.as_millis() as u64).saturating_sub(slot_start_ms);
+        if ms_into_slot <= self.timing.get_payload_cutoff_ms {
+            return Ok(())
+        }
+
+        let block_hash =
+            signed_block.message().body().execution_payload_header().block_hash().clone();
+        warn!(%auction_request, %block_hash, ms_into_slot, "signed blinded beacon block arrived past the cutoff");
+        let trace = LatePayloadRequestTrace {
+            slot,
+            parent_hash: auction_request.parent_hash.clone(),
+            block_hash,
+            proposer_public_key: auction_request.public_key.clone(),
+            ms_into_slot,
+            timestamp_ms: receive_duration.as_millis(),
+        };
+        let mut payloads = self.payloads.write();
+        let traces = payloads.late_payload_requests.entry(auction_request.clone()).or_default();
+        if traces.len() < MAX_LATE_PAYLOAD_REQUESTS_PER_AUCTION {
+            traces.push(trace);
+        }
+        Err(RelayError::LateSignedBlindedBeaconBlock(slot, ms_into_slot))
+    }
+
     fn store_delivered_payload(
         &self,
         auction_request: AuctionRequest,
         auction_context: Arc<AuctionContext>,
     ) {
-        let mut state = self.state.lock();
+        let mut state = self.payloads.write();
         if let Some(existing) = state.delivered_payloads.get(&auction_request) {
             if existing != &auction_context {
                 error!(
@@ -491,16 +1237,33 @@ impl Relay {
                 return
             }
         }
+        state.payload_index.insert(
+            &auction_request,
+            &auction_context.bid_trace().block_hash,
+            auction_context.builder_public_key(),
+        );
+        let bid_trace = auction_context.bid_trace();
+        let event = AuctionEvent::PayloadDelivered {
+            slot: auction_request.slot,
+            parent_hash: auction_request.parent_hash.clone(),
+            proposer_public_key: auction_request.public_key.clone(),
+            builder_public_key: bid_trace.builder_public_key.clone(),
+            block_hash: bid_trace.block_hash.clone(),
+            value: bid_trace.value,
+        };
         state.delivered_payloads.insert(auction_request, auction_context);
+        drop(state);
+
+        self.events.publish(event);
     }
-}
 
-#[async_trait]
-impl BlindedBlockProvider for Relay {
-    async fn register_validators(
+    async fn process_registrations(
         &self,
         registrations: &[SignedValidatorRegistration],
     ) -> Result<(), Error> {
+        if let Err(err) = self.validator_registry.fetch_unknown_validators(registrations).await {
+            warn!(%err, "could not look up unknown validators");
+        }
         let current_time = get_current_unix_time_in_nanos().try_into().expect("fits in type");
         let (updated_keys, errs) = self.validator_registry.process_registrations(
             registrations,
@@ -514,8 +1277,7 @@ impl BlindedBlockProvider for Relay {
             registrations = registrations.len(),
             "processed validator registrations"
         );
-        let mut state = self.state.lock();
-        state.outstanding_validator_updates.extend(updated_keys);
+        self.outstanding_validator_updates.lock().extend(updated_keys);
 
         if errs.is_empty() {
             Ok(())
@@ -525,7 +1287,7 @@ impl BlindedBlockProvider for Relay {
         }
     }
 
-    async fn fetch_best_bid(
+    async fn select_best_bid(
         &self,
         auction_request: &AuctionRequest,
     ) -> Result<SignedBuilderBid, Error> {
@@ -534,15 +1296,46 @@ impl BlindedBlockProvider for Relay {
             return Err(err.into())
         }
 
-        let auction_context = self
-            .get_auction_context(auction_request)
-            .ok_or_else(|| Error::NoBidPrepared(auction_request.clone()))?;
-        let signed_builder_bid = auction_context.signed_builder_bid();
+        if self.timing.get_header_delay_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(self.timing.get_header_delay_ms)).await;
+        }
+
+        let (signed_builder_bid, builder_public_key) = {
+            let bids = self.bids.read();
+            let bid = bids.auctions.get(auction_request).map(|auction_context| {
+                (
+                    auction_context.value(),
+                    auction_context.signed_builder_bid().as_ref(),
+                    auction_context.builder_public_key(),
+                )
+            });
+            let header_bid = bids.header_bids.get(auction_request).map(|header_bid| {
+                (
+                    header_bid.value(),
+                    &header_bid.signed_builder_bid,
+                    &header_bid.bid_trace.builder_public_key,
+                )
+            });
+            // NOTE: prefer the bid with a payload if the values are equal
+            let best_bid = match (bid, header_bid) {
+                (Some(bid), Some(header_bid)) if header_bid.0 > bid.0 => Some(header_bid),
+                (bid, header_bid) => bid.or(header_bid),
+            };
+            best_bid.map(|(_, signed_builder_bid, builder_public_key)| {
+                (signed_builder_bid.clone(), builder_public_key.clone())
+            })
+        }
+        .ok_or_else(|| Error::NoBidPrepared(auction_request.clone()))?;
+        // NOTE: builders may be denied after submitting a bid
+        if self.builder_deny_list.contains(&builder_public_key) {
+            warn!(%auction_request, %builder_public_key, "best bid is from a denied builder");
+            return Err(Error::NoBidPrepared(auction_request.clone()))
+        }
         info!(%auction_request, %signed_builder_bid, "serving bid");
-        Ok(signed_builder_bid.clone())
+        Ok(signed_builder_bid)
     }
 
-    async fn open_bid(
+    async fn process_payload_request(
         &self,
         signed_block: &SignedBlindedBeaconBlock,
     ) -> Result<AuctionContents, Error> {
@@ -565,9 +1358,44 @@ impl BlindedBlockProvider for Relay {
             return Err(err.into())
         }
 
-        let auction_context = self
-            .get_auction_context(&auction_request)
-            .ok_or_else(|| RelayError::MissingAuction(auction_request.clone()))?;
+        // NOTE: verify the proposer signed the block before recording anything about it
+        if let Err(err) = verify_blinded_block_signature(
+            &auction_request,
+            signed_block,
+            &self.genesis_validators_root,
+            &self.context,
+        ) {
+            warn!(%err, %auction_request, "invalid incoming signed blinded beacon block signature");
+            return Err(RelayError::InvalidSignedBlindedBeaconBlock.into())
+        }
+
+        self.validate_payload_request_timing(&auction_request, signed_block)?;
+
+        let auction_context = self.get_auction_context(&auction_request);
+        let block_hash =
+            signed_block.message().body().execution_payload_header().block_hash().clone();
+        if auction_context
+            .as_ref()
+            .map(|auction_context| auction_context.execution_payload().block_hash() != &block_hash)
+            .unwrap_or(true)
+        {
+            let builder_public_key = self
+                .bids
+                .read()
+                .header_bids
+                .get(&auction_request)
+                .filter(|header_bid| header_bid.bid_trace.block_hash == block_hash)
+                .map(|header_bid| header_bid.bid_trace.builder_public_key.clone());
+            if let Some(builder_public_key) = builder_public_key {
+                warn!(%auction_request, %block_hash, %builder_public_key, "payload of header-only bid has not arrived");
+                if self.header_submission_builders.write().remove(&builder_public_key) {
+                    warn!(%builder_public_key, "demoting builder from header-only submissions");
+                }
+                return Err(RelayError::MissingPayload(block_hash).into())
+            }
+        }
+        let auction_context =
+            auction_context.ok_or_else(|| RelayError::MissingAuction(auction_request.clone()))?;
 
         {
             let block = signed_block.message();
@@ -580,16 +1408,6 @@ impl BlindedBlockProvider for Relay {
             }
         }
 
-        if let Err(err) = verify_blinded_block_signature(
-            &auction_request,
-            signed_block,
-            &self.genesis_validators_root,
-            &self.context,
-        ) {
-            warn!(%err, %auction_request, "invalid incoming signed blinded beacon block signature");
-            return Err(RelayError::InvalidSignedBlindedBeaconBlock.into())
-        }
-
         match unblind_block(signed_block, auction_context.execution_payload()) {
             Ok(signed_block) => {
                 let version = signed_block.version();
@@ -625,6 +1443,136 @@ impl BlindedBlockProvider for Relay {
             }
         }
     }
+
+    // Returns `true` if `signed_submission`, rejected with `err`, was made by an accepted builder,
+    // so its rejection can be published without trusting an unauthenticated request.
+    fn is_authenticated(&self, signed_submission: &SignedBidSubmission, err: &Error) -> bool {
+        if matches!(
+            err,
+            Error::Relay(RelayError::BuilderNotRegistered(..) | RelayError::BuilderDenied(..)) |
+                Error::Consensus(..)
+        ) {
+            return false
+        }
+        let bid_trace = signed_submission.message();
+        verify_signed_data(
+            bid_trace,
+            signed_submission.signature(),
+            &bid_trace.builder_public_key,
+            self.builder_domain,
+        )
+        .is_ok()
+    }
+
+    // Whether `slot` is recent enough for the relay to hold an auction for it at `receive_duration`
+    fn is_in_auction_window(&self, slot: Slot, receive_duration: Duration) -> bool {
+        let current_slot = receive_duration.as_secs().saturating_sub(self.genesis_time) /
+            self.context.seconds_per_slot;
+        let earliest_slot = current_slot.saturating_sub(self.timing.auction_lifetime_slots);
+        (earliest_slot..=current_slot + 1).contains(&slot)
+    }
+
+    fn process_header_submission(
+        &self,
+        signed_submission: &SignedHeaderSubmission,
+        receive_duration: Duration,
+    ) -> Result<(), Error> {
+        let bid_trace = &signed_submission.message;
+        self.validate_allowed_builder(&bid_trace.builder_public_key)?;
+        if !self.header_submission_builders.read().contains(&bid_trace.builder_public_key) {
+            return Err(
+                RelayError::HeaderSubmissionNotAllowed(bid_trace.builder_public_key.clone()).into()
+            )
+        }
+
+        let auction_request = AuctionRequest {
+            slot: bid_trace.slot,
+            parent_hash: bid_trace.parent_hash.clone(),
+            public_key: bid_trace.proposer_public_key.clone(),
+        };
+        if let Err(err) = self.validate_auction_request(&auction_request) {
+            warn!(%err, "could not validate header submission");
+            return Err(err.into())
+        }
+
+        let header = &signed_submission.execution_payload_header;
+        let registration = self.get_signed_registration(&bid_trace.proposer_public_key)?;
+        validate_submission_trusted(bid_trace, header.into(), &registration)?;
+        let expected_fork = self.context.fork_for(bid_trace.slot);
+        if header.version() != expected_fork {
+            return Err(Error::InvalidFork { expected: expected_fork, provided: header.version() })
+        }
+        debug!(%auction_request, "validated header submission");
+
+        verify_signed_data(
+            bid_trace,
+            &signed_submission.signature,
+            &bid_trace.builder_public_key,
+            self.builder_domain,
+        )?;
+
+        self.insert_header_bid_if_greater(
+            auction_request,
+            signed_submission.clone(),
+            receive_duration,
+        )
+    }
+}
+
+#[async_trait]
+impl BlindedBlockProvider for Relay {
+    async fn register_validators(
+        &self,
+        registrations: &[SignedValidatorRegistration],
+    ) -> Result<(), Error> {
+        let receive_duration = duration_since_unix_epoch();
+        let start = Instant::now();
+        let result = self.process_registrations(registrations).await;
+        let public_keys = registrations
+            .iter()
+            .map(|registration| registration.message.public_key.clone())
+            .collect();
+        let event = AuditEvent::RegisterValidators { public_keys };
+        self.audit_log.record(event, receive_duration, start, result.as_ref().err());
+        result
+    }
+
+    async fn fetch_best_bid(
+        &self,
+        auction_request: &AuctionRequest,
+    ) -> Result<SignedBuilderBid, Error> {
+        let receive_duration = duration_since_unix_epoch();
+        let start = Instant::now();
+        let result = self.select_best_bid(auction_request).await;
+        let bid = result.as_ref().ok().map(|bid| &bid.message);
+        let event = AuditEvent::GetHeader {
+            slot: auction_request.slot,
+            parent_hash: auction_request.parent_hash.clone(),
+            proposer_public_key: auction_request.public_key.clone(),
+            block_hash: bid.map(|bid| bid.header().block_hash().clone()),
+            value: bid.map(|bid| bid.value()),
+        };
+        self.audit_log.record(event, receive_duration, start, result.as_ref().err());
+        result
+    }
+
+    async fn open_bid(
+        &self,
+        signed_block: &SignedBlindedBeaconBlock,
+    ) -> Result<AuctionContents, Error> {
+        let receive_duration = duration_since_unix_epoch();
+        let start = Instant::now();
+        let result = self.process_payload_request(signed_block).await;
+        let block = signed_block.message();
+        self.auction_metrics.record_payload_request(block.slot(), result.as_ref().map(|_| ()));
+        let event = AuditEvent::OpenBid {
+            slot: block.slot(),
+            proposer_index: block.proposer_index(),
+            block_hash: block.body().execution_payload_header().block_hash().clone(),
+        };
+        self.audit_log.record(event, receive_duration, start, result.as_ref().err());
+        result
+    }
 }
 
 #[async_trait]
@@ -636,39 +1584,64 @@ impl BlindedBlockRelayer for Relay {
         Ok(schedule)
     }
 
+    async fn get_proposer_schedule(&self, slot: Slot) -> Result<Option<ProposerSchedule>, Error> {
+        Ok(self.proposer_scheduler.get_proposer_schedule(slot))
+    }
+
+    fn subscribe_payload_attributes(
+        &self,
+    ) -> Option<BoxStream<'static, Arc<PayloadAttributesUpdate>>> {
+        Some(self.payload_attributes.subscribe().boxed())
+    }
+
     async fn submit_bid(&self, signed_submission: &SignedBidSubmission) -> Result<(), Error> {
         let receive_duration = duration_since_unix_epoch();
-        let (auction_request, value) = {
-            let bid_trace = signed_submission.message();
-            let builder_public_key = &bid_trace.builder_public_key;
-            self.validate_allowed_builder(builder_public_key)?;
-
-            let auction_request = AuctionRequest {
-                slot: bid_trace.slot,
-                parent_hash: bid_trace.parent_hash.clone(),
-                public_key: bid_trace.proposer_public_key.clone(),
-            };
-            if let Err(err) = self.validate_auction_request(&auction_request) {
-                warn!(%err, "could not validate bid submission");
-                return Err(err.into())
+        let start = Instant::now();
+        let result = self.process_bid_submission(signed_submission, receive_duration);
+        let bid_trace = signed_submission.message();
+        let is_authenticated = match &result {
+            Ok(()) => true,
+            Err(err) => self.is_authenticated(signed_submission, err),
+        };
+        if is_authenticated && self.is_in_auction_window(bid_trace.slot, receive_duration) {
+            self.submission_archive.record(signed_submission, receive_duration);
+        }
+        self.auction_metrics.record_submission(bid_trace.slot, result.as_ref().map(|_| ()));
+        let event = AuditEvent::SubmitBid {
+            slot: bid_trace.slot,
+            builder_public_key: bid_trace.builder_public_key.clone(),
+            block_hash: bid_trace.block_hash.clone(),
+            value: bid_trace.value,
+        };
+        self.audit_log.record(event, receive_duration, start, result.as_ref().err());
+        if let Err(err) = &result {
+            if is_authenticated {
+                self.events.publish(AuctionEvent::BidRejected {
+                    slot: bid_trace.slot,
+                    parent_hash: bid_trace.parent_hash.clone(),
+                    builder_public_key: bid_trace.builder_public_key.clone(),
+                    block_hash: bid_trace.block_hash.clone(),
+                    reason: err.to_string(),
+                });
             }
+        }
+        result
+    }
 
-            self.validate_builder_submission_trusted(bid_trace, signed_submission.payload())?;
-            debug!(%auction_request, "validated builder submission");
-            (auction_request, bid_trace.value)
+    async fn submit_header(&self, signed_submission: &SignedHeaderSubmission) -> Result<(), Error> {
+        let receive_duration = duration_since_unix_epoch();
+        let start = Instant::now();
+        let result = self.process_header_submission(signed_submission, receive_duration);
+        let bid_trace = &signed_submission.message;
+        self.auction_metrics.record_submission(bid_trace.slot, result.as_ref().map(|_| ()));
+        let event = AuditEvent::SubmitHeader {
+            slot: bid_trace.slot,
+            builder_public_key: bid_trace.builder_public_key.clone(),
+            block_hash: bid_trace.block_hash.clone(),
+            value: bid_trace.value,
         };
-
-        let message = signed_submission.message();
-        let public_key = &signed_submission.message().builder_public_key;
-        let signature = signed_submission.signature();
-        verify_signed_builder_data(message, public_key, signature, &self.context)?;
-
-        // NOTE: this does _not_ respect cancellations
-        // TODO: move to regime where we track best bid by builder
-        // and also move logic to cursor best bid for auction off this API
-        self.insert_bid_if_greater(auction_request, signed_submission, value, receive_duration)?;
-
-        Ok(())
+        self.audit_log.record(event, receive_duration, start, result.as_ref().err());
+        result
     }
 }
 
@@ -721,6 +1694,33 @@ fn submission_trace_from_auction(auction_context: &AuctionContext) -> Submission
     }
 }
 
+#[async_trait]
+impl Introspect for Relay {
+    async fn state_summary(&self) -> serde_json::Value {
+        let open_auctions =
+            self.open_auctions.read().iter().map(ToString::to_string).collect::<Vec<_>>();
+        let (auctions_with_bids, outbid_submissions) = {
+            let bids = self.bids.read();
+            (bids.auctions.len(), bids.other_submission_count)
+        };
+        let (delivered_payloads, imported_delivered_payloads) = {
+            let payloads = self.payloads.read();
+            (payloads.delivered_payloads.len(), payloads.imported_payloads.len())
+        };
+        json!({
+            "public_key": self.public_key,
+            "registered_validators": self.registered_validators_count(),
+            "accepted_builders": self.builder_registry.builders().len(),
+            "outstanding_validator_updates": self.outstanding_validator_updates.lock().len(),
+            "open_auctions": open_auctions,
+            "auctions_with_bids": auctions_with_bids,
+            "outbid_submissions": outbid_submissions,
+            "delivered_payloads": delivered_payloads,
+            "imported_delivered_payloads": imported_delivered_payloads,
+        })
+    }
+}
+
 #[async_trait]
 impl BlindedBlockDataProvider for Relay {
     fn public_key(&self) -> &BlsPublicKey {
@@ -731,57 +1731,141 @@ impl BlindedBlockDataProvider for Relay {
         self.validator_registry.registration_count()
     }
 
+    async fn get_status(&self) -> Result<RelayStatus, Error> {
+        Ok(RelayStatus {
+            public_key: self.public_key.clone(),
+            network: self.network.clone(),
+            genesis_validators_root: self.genesis_validators_root,
+            beacon_node: self.beacon_node_status.read().clone(),
+            open_auctions: self.open_auctions.read().len(),
+            registered_validators: self.registered_validators_count(),
+        })
+    }
+
+    async fn get_registration_stats(&self) -> Result<RegistrationStats, Error> {
+        Ok(self.validator_registry.registration_stats())
+    }
+
     async fn get_delivered_payloads(
         &self,
-        _filters: &DeliveredPayloadFilter,
+        filters: &DeliveredPayloadFilter,
     ) -> Result<Vec<PayloadTrace>, Error> {
-        let state = self.state.lock();
-        let mut traces = state
-            .delivered_payloads
+        let query = TraceQuery {
+            slot: filters.slot,
+            cursor: filters.cursor,
+            block_hash: filters.block_hash.as_ref(),
+            builder_public_key: filters.builder_public_key.as_ref(),
+            proposer_public_key: filters.proposer_public_key.as_ref(),
+        };
+        let limit = filters.limit.unwrap_or(usize::MAX);
+        let state = self.payloads.read();
+        let traces = state
+            .payload_index
+            .query(&query)
+            .filter_map(|auction_request| {
+                state
+                    .delivered_payloads
+                    .get(auction_request)
+                    .map(|auction_context| payload_trace_from_auction(auction_context))
+                    .or_else(|| state.imported_payloads.get(auction_request).cloned())
+            })
+            .filter(|trace| {
+                filters.block_hash.as_ref().map(|hash| &trace.block_hash == hash).unwrap_or(true) &&
+                    filters
+                        .builder_public_key
+                        .as_ref()
+                        .map(|public_key| &trace.builder_public_key == public_key)
+                        .unwrap_or(true) &&
+                    filters
+                        .block_number
+                        .map(|number| trace.block_number == number as u64)
+                        .unwrap_or(true)
+            })
+            .take(limit)
+            .collect();
+        Ok(traces)
+    }
+
+    async fn get_late_payload_requests(
+        &self,
+        filters: &LatePayloadRequestFilter,
+    ) -> Result<Vec<LatePayloadRequestTrace>, Error> {
+        let highest_slot = match (filters.slot, filters.cursor) {
+            (Some(slot), Some(cursor)) => Some(slot.min(cursor)),
+            (slot, cursor) => slot.or(cursor),
+        };
+        let limit = filters.limit.unwrap_or(usize::MAX);
+        let state = self.payloads.read();
+        let traces = state
+            .late_payload_requests
             .iter()
-            .map(|(auction_request, auction_context)| {
-                let trace = payload_trace_from_auction(auction_context);
-                (auction_request, trace)
+            .rev()
+            .skip_while(|(auction_request, _)| {
+                highest_slot.map(|slot| auction_request.slot > slot).unwrap_or_default()
             })
-            .collect::<Vec<_>>();
-        traces.sort_by(|a, b| a.0.cmp(b.0));
-        Ok(traces.into_iter().rev().map(|(_, trace)| trace).collect())
+            .take_while(|(auction_request, _)| {
+                filters.slot.map(|slot| auction_request.slot >= slot).unwrap_or(true)
+            })
+            .filter(|(auction_request, _)| {
+                filters
+                    .proposer_public_key
+                    .as_ref()
+                    .map(|public_key| &auction_request.public_key == public_key)
+                    .unwrap_or(true)
+            })
+            .flat_map(|(_, traces)| traces.iter().rev().cloned())
+            .take(limit)
+            .collect();
+        Ok(traces)
     }
 
     async fn get_block_submissions(
         &self,
-        _filters: &BlockSubmissionFilter,
+        filters: &BlockSubmissionFilter,
     ) -> Result<Vec<SubmissionTrace>, Error> {
-        let state = self.state.lock();
-        let mut traces = state
-            .auctions
-            .iter()
-            .map(|(auction_request, auction_context)| {
-                let trace = submission_trace_from_auction(auction_context);
-                (auction_request.clone(), trace)
+        let query = TraceQuery {
+            slot: filters.slot,
+            block_hash: filters.block_hash.as_ref(),
+            builder_public_key: filters.builder_public_key.as_ref(),
+            ..Default::default()
+        };
+        let limit = filters.limit.unwrap_or(usize::MAX);
+        let state = self.bids.read();
+        let traces = state
+            .submission_index
+            .query(&query)
+            .flat_map(|auction_request| {
+                let mut traces = vec![];
+                if let Some(auction_context) = state.auctions.get(auction_request) {
+                    traces.push(submission_trace_from_auction(auction_context));
+                }
+                if let Some(contexts) = state.other_submissions.get(auction_request) {
+                    traces.extend(
+                        contexts.iter().map(|context| submission_trace_from_auction(context)),
+                    );
+                }
+                if let Some(imported) = state.imported_submissions.get(auction_request) {
+                    traces.extend(imported.iter().cloned());
+                }
+                // most recently received first
+                traces.sort_by(|a, b| b.timestamp_ms.cmp(&a.timestamp_ms));
+                traces
             })
-            .collect::<Vec<_>>();
-        let other_traces = state
-            .other_submissions
-            .iter()
-            .flat_map(|(auction_request, contexts)| {
-                contexts.iter().map(|auction_context| {
-                    let trace = submission_trace_from_auction(auction_context);
-                    (auction_request.clone(), trace)
-                })
+            .filter(|trace| {
+                filters.block_hash.as_ref().map(|hash| &trace.block_hash == hash).unwrap_or(true) &&
+                    filters
+                        .builder_public_key
+                        .as_ref()
+                        .map(|public_key| &trace.builder_public_key == public_key)
+                        .unwrap_or(true) &&
+                    filters
+                        .block_number
+                        .map(|number| trace.block_number == number as u64)
+                        .unwrap_or(true)
             })
-            .collect::<Vec<_>>();
-        traces.extend(other_traces);
-        // sort by primarily slot, and then receipt timestamp
-        traces.sort_by(|a, b| {
-            let auction_request = a.0.cmp(&b.0);
-            if let Ordering::Equal = auction_request {
-                a.1.timestamp_ms.cmp(&b.1.timestamp_ms)
-            } else {
-                auction_request
-            }
-        });
-        Ok(traces.into_iter().rev().map(|(_, trace)| trace).collect())
+            .take(limit)
+            .collect();
+        Ok(traces)
     }
 
     async fn fetch_validator_registration(
@@ -790,6 +1874,7 @@ impl BlindedBlockDataProvider for Relay {
     ) -> Result<SignedValidatorRegistration, Error> {
         self.validator_registry
             .get_signed_registration(public_key)
+            .map(|registration| registration.as_ref().clone())
             .ok_or_else(|| RelayError::ValidatorNotRegistered(public_key.clone()))
             .map_err(Into::into)
     }
@@ -0,0 +1,74 @@
+use crate::{
+    builder_registry::{BuilderDenyList, BuilderRegistry},
+    relay::{validate_allowed_builder, validate_bid_submission},
+    submission_archive::ArchivedAuction,
+    Config,
+};
+use ethereum_consensus::{primitives::Domain, state_transition::Context};
+use mev_rs::{
+    signing::compute_builder_domain,
+    types::{AuctionRequest, SignedBidSubmission, SignedValidatorRegistration},
+    Error, RelayError,
+};
+use std::collections::HashMap;
+
+/// Validates recorded block submissions in-process against the auctions archived with them, as
+/// the relay configured by `config` would have on receipt
+pub struct Replayer {
+    builder_registry: BuilderRegistry,
+    builder_deny_list: BuilderDenyList,
+    auctions: HashMap<AuctionRequest, Option<SignedValidatorRegistration>>,
+    config: Config,
+    builder_domain: Domain,
+}
+
+impl Replayer {
+    pub fn new(config: Config, context: &Context) -> Result<Self, Error> {
+        let builder_registry = BuilderRegistry::new(
+            config.accepted_builders.clone(),
+            config.builder_registry_path.clone(),
+        );
+        let builder_deny_list = BuilderDenyList::new(config.builder_deny_list_path.clone());
+        let builder_domain = compute_builder_domain(context)?;
+        Ok(Self {
+            builder_registry,
+            builder_deny_list,
+            auctions: Default::default(),
+            config,
+            builder_domain,
+        })
+    }
+
+    pub fn insert_auction(&mut self, auction: ArchivedAuction) {
+        self.auctions.insert(auction.auction_request, auction.registration);
+    }
+
+    pub fn validate(&self, signed_submission: &SignedBidSubmission) -> Result<(), Error> {
+        let bid_trace = signed_submission.message();
+        validate_allowed_builder(
+            &self.builder_registry,
+            &self.builder_deny_list,
+            &bid_trace.builder_public_key,
+        )?;
+
+        let auction_request = AuctionRequest {
+            slot: bid_trace.slot,
+            parent_hash: bid_trace.parent_hash.clone(),
+            public_key: bid_trace.proposer_public_key.clone(),
+        };
+        let registration = self
+            .auctions
+            .get(&auction_request)
+            .ok_or_else(|| RelayError::InvalidAuctionRequest(auction_request.clone()))?
+            .as_ref()
+            .ok_or_else(|| {
+                RelayError::ValidatorNotRegistered(auction_request.public_key.clone())
+            })?;
+        validate_bid_submission(
+            signed_submission,
+            registration,
+            &self.config.validation,
+            self.builder_domain,
+        )
+    }
+}
@@ -1,13 +1,34 @@
-use crate::relay::Relay;
+use crate::{
+    admin,
+    audit_log::AuditLog,
+    builder_registry::{BuilderDenyList, BuilderRegistry},
+    chain_events::{ChainReorgTopic, HeadTopic},
+    relay::{HistoryConfig, Relay, TimingConfig, ValidationConfig},
+    submission_archive::SubmissionArchive,
+    trace_store::TraceStore,
+};
 use backoff::ExponentialBackoff;
-use beacon_api_client::PayloadAttributesTopic;
+use beacon_api_client::{PayloadAttributesTopic, Topic};
 use ethereum_consensus::{
     crypto::SecretKey, networks::Network, primitives::BlsPublicKey, state_transition::Context,
 };
 use futures::StreamExt;
-use mev_rs::{blinded_block_relayer::Server as BlindedBlockRelayerServer, get_genesis_time, Error};
+use mev_rs::{
+    blinded_block_relayer::{Client as RelayClient, Server as BlindedBlockRelayerServer},
+    config::{AdminConfig, MetricsConfig, ServerConfig},
+    get_genesis_time,
+    telemetry::install_metrics_exporter,
+    Error, ValidatorRegistry,
+};
 use serde::Deserialize;
-use std::{future::Future, net::Ipv4Addr, pin::Pin, task::Poll};
+use std::{
+    future::Future,
+    net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
+    pin::Pin,
+    sync::Arc,
+    task::Poll,
+};
 use tokio::task::{JoinError, JoinHandle};
 use tracing::{error, warn};
 use url::Url;
@@ -19,32 +40,98 @@ use beacon_api_client::minimal::Client;
 
 #[derive(Deserialize, Debug)]
 pub struct Config {
-    pub host: Ipv4Addr,
+    #[serde(deserialize_with = "mev_rs::serde::ip_addr::deserialize")]
+    pub host: IpAddr,
     pub port: u16,
     pub beacon_node_url: String,
+    /// Secret key given inline, as `file:/path` or as `env:VAR`
+    #[serde(deserialize_with = "mev_rs::serde::secret::deserialize")]
     pub secret_key: SecretKey,
     pub accepted_builders: Vec<BlsPublicKey>,
+    /// File to persist changes made to the accepted builders via the admin API
+    pub builder_registry_path: Option<PathBuf>,
+    /// File of builder public keys to deny, reloaded on change or `SIGHUP`
+    pub builder_deny_list_path: Option<PathBuf>,
+    /// File to persist validator registrations so they survive restarts
+    pub validator_registrations_path: Option<PathBuf>,
+    /// Number of threads dedicated to verifying validator registrations, otherwise they share
+    /// the global thread pool
+    pub registration_threads: Option<usize>,
+    /// Directory to record every block submission received, for replay with `mev relay replay`
+    pub submission_archive_path: Option<PathBuf>,
+    /// File to append a JSON line to for every registration, submission, `getHeader` and
+    /// `getPayload` call, with its outcome and timing
+    pub audit_log_path: Option<PathBuf>,
+    /// Directory to persist traces imported with `mev relay backfill`, so they are served across
+    /// restarts for as long as the relay's history
+    pub imported_traces_path: Option<PathBuf>,
+    /// Relays to import the registrations of upcoming proposers from, if not registered here
+    #[serde(default)]
+    pub registration_peers: Vec<Url>,
+    #[serde(default)]
+    pub server: ServerConfig,
+    /// Bounds on the auction history kept to serve the data APIs
+    #[serde(default)]
+    pub history: HistoryConfig,
+    /// Timing of the relay's auctions, e.g. for devnets with unusual slot timing
+    #[serde(default)]
+    pub timing: TimingConfig,
+    /// Checks made on block submissions beyond those on the bid trace
+    #[serde(default)]
+    pub validation: ValidationConfig,
+    /// Serve the admin API if provided
+    pub admin: Option<AdminConfig>,
+    /// Serve Prometheus metrics if provided
+    pub metrics: Option<MetricsConfig>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
-            host: Ipv4Addr::LOCALHOST,
+            host: Ipv4Addr::LOCALHOST.into(),
             port: 28545,
             beacon_node_url: "http://127.0.0.1:5052".into(),
             secret_key: Default::default(),
             accepted_builders: Default::default(),
+            builder_registry_path: None,
+            builder_deny_list_path: None,
+            validator_registrations_path: None,
+            registration_threads: None,
+            submission_archive_path: None,
+            audit_log_path: None,
+            imported_traces_path: None,
+            registration_peers: Default::default(),
+            server: Default::default(),
+            history: Default::default(),
+            timing: Default::default(),
+            validation: Default::default(),
+            admin: None,
+            metrics: None,
         }
     }
 }
 
 pub struct Service {
-    host: Ipv4Addr,
+    host: IpAddr,
     port: u16,
     beacon_node: Client,
     network: Network,
     secret_key: SecretKey,
     accepted_builders: Vec<BlsPublicKey>,
+    builder_registry_path: Option<PathBuf>,
+    builder_deny_list_path: Option<PathBuf>,
+    validator_registrations_path: Option<PathBuf>,
+    registration_threads: Option<usize>,
+    submission_archive_path: Option<PathBuf>,
+    audit_log_path: Option<PathBuf>,
+    imported_traces_path: Option<PathBuf>,
+    registration_peers: Vec<Url>,
+    server_config: ServerConfig,
+    history_config: HistoryConfig,
+    timing_config: TimingConfig,
+    validation_config: ValidationConfig,
+    admin_config: Option<AdminConfig>,
+    metrics_config: Option<MetricsConfig>,
 }
 
 impl Service {
@@ -58,74 +145,131 @@ impl Service {
             network,
             secret_key: config.secret_key,
             accepted_builders: config.accepted_builders,
+            builder_registry_path: config.builder_registry_path,
+            builder_deny_list_path: config.builder_deny_list_path,
+            validator_registrations_path: config.validator_registrations_path,
+            registration_threads: config.registration_threads,
+            submission_archive_path: config.submission_archive_path,
+            audit_log_path: config.audit_log_path,
+            imported_traces_path: config.imported_traces_path,
+            registration_peers: config.registration_peers,
+            server_config: config.server,
+            history_config: config.history,
+            timing_config: config.timing,
+            validation_config: config.validation,
+            admin_config: config.admin,
+            metrics_config: config.metrics,
         }
     }
 
     /// Configures the [`Relay`] and the [`BlindedBlockProviderServer`] and spawns both to
     /// individual tasks
     pub async fn spawn(self) -> Result<ServiceHandle, Error> {
-        let Self { host, port, beacon_node, network, secret_key, accepted_builders } = self;
+        let Self {
+            host,
+            port,
+            beacon_node,
+            network,
+            secret_key,
+            accepted_builders,
+            builder_registry_path,
+            builder_deny_list_path,
+            validator_registrations_path,
+            registration_threads,
+            submission_archive_path,
+            audit_log_path,
+            imported_traces_path,
+            registration_peers,
+            server_config,
+            history_config,
+            timing_config,
+            validation_config,
+            admin_config,
+            metrics_config,
+        } = self;
 
+        if let Some(metrics_config) = metrics_config.as_ref() {
+            install_metrics_exporter(metrics_config);
+        }
+
+        let network_name = network.to_string();
         let context = Context::try_from(network)?;
+        timing_config.validate(&context)?;
         let genesis_time = get_genesis_time(&context, None, Some(&beacon_node)).await;
         let clock = context.clock_at(genesis_time);
         let genesis_validators_root =
             beacon_node.get_genesis_details().await?.genesis_validators_root;
 
+        let builder_registry = BuilderRegistry::new(accepted_builders, builder_registry_path);
+        let builder_deny_list = BuilderDenyList::new(builder_deny_list_path);
+        let validator_registry = ValidatorRegistry::new(
+            beacon_node.clone(),
+            context.slots_per_epoch,
+            validator_registrations_path,
+            registration_threads,
+        );
+        let submission_archive = SubmissionArchive::new(submission_archive_path)?;
+        let audit_log = AuditLog::new(audit_log_path)?;
+        let trace_store = TraceStore::new(imported_traces_path);
+        let registration_peers = registration_peers
+            .into_iter()
+            .map(|endpoint| RelayClient::new(Client::new(endpoint)))
+            .collect();
         let relay = Relay::new(
             beacon_node.clone(),
             secret_key,
-            accepted_builders,
-            context,
+            builder_registry,
+            builder_deny_list,
+            validator_registry,
+            submission_archive,
+            audit_log,
+            trace_store,
+            network_name,
+            context.clone(),
+            genesis_time,
             genesis_validators_root,
-        );
+            history_config,
+            timing_config,
+            validation_config,
+            registration_peers,
+        )?;
 
         let relay_for_api = relay.clone();
-        let server = BlindedBlockRelayerServer::new(host, port, relay_for_api).spawn();
-
-        let relay_clone = relay.clone();
-        let consensus = tokio::spawn(async move {
-            let relay = relay_clone;
-
-            loop {
-                let result = backoff::future::retry::<(), (), _, _, _>(
-                    ExponentialBackoff::default(),
-                    || async {
-                        let retry = backoff::Error::transient(());
-                        let mut stream =
-                            match beacon_node.get_events::<PayloadAttributesTopic>().await {
-                                Ok(stream) => stream,
-                                Err(err) => {
-                                    error!(%err, "could not open payload attributes stream");
-                                    return Err(retry)
-                                }
-                            };
-
-                        while let Some(event) = stream.next().await {
-                            match event {
-                                Ok(event) => {
-                                    if let Err(err) = relay.on_payload_attributes(event.data) {
-                                        warn!(%err, "could not process payload attributes");
-                                        continue
-                                    }
-                                }
-                                Err(err) => {
-                                    warn!(%err, "error reading payload attributes stream");
-                                    return Err(retry)
-                                }
-                            }
+        let server = BlindedBlockRelayerServer::new(
+            host,
+            port,
+            server_config,
+            relay_for_api,
+            Arc::new(context),
+        )
+        .spawn();
+
+        let relay_for_deny_list = relay.clone();
+        std::mem::drop(tokio::spawn(async move {
+            relay_for_deny_list.builder_deny_list.watch().await;
+        }));
+
+        if let Some(admin_config) = admin_config {
+            std::mem::drop(admin::spawn(&admin_config, relay.clone())?);
+        }
+
+        let consensus = {
+            let relay = relay.clone();
+            tokio::spawn(async move {
+                let payload_attributes =
+                    follow_events::<PayloadAttributesTopic, _>(&beacon_node, |event| {
+                        if let Err(err) = relay.on_payload_attributes(event.data) {
+                            warn!(%err, "could not process payload attributes");
                         }
-                        Err(retry)
-                    },
-                )
-                .await;
-                if result.is_err() {
-                    error!(
-                        "failed to read from event stream with exponential backoff, restarting..."
-                    );
-                }
-            }
-        });
+                    });
+                let heads =
+                    follow_events::<HeadTopic, _>(&beacon_node, |event| relay.on_head(event));
+                let reorgs = follow_events::<ChainReorgTopic, _>(&beacon_node, |event| {
+                    relay.on_chain_reorg(event)
+                });
+                tokio::join!(payload_attributes, heads, reorgs);
+            })
+        };
 
         let relay = tokio::spawn(async move {
             let mut slots = clock.clone().into_stream();
@@ -150,6 +294,42 @@ impl Service {
     }
 }
 
+// Follows the `T` events published by the beacon node, passing each to `on_event` and
+// reconnecting with exponential backoff whenever the stream fails.
+async fn follow_events<T: Topic, F: Fn(T::Data)>(beacon_node: &Client, on_event: F) {
+    loop {
+        let result =
+            backoff::future::retry::<(), (), _, _, _>(ExponentialBackoff::default(), || async {
+                let retry = backoff::Error::transient(());
+                let mut stream = match beacon_node.get_events::<T>().await {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        error!(%err, topic = T::NAME, "could not open event stream");
+                        return Err(retry)
+                    }
+                };
+
+                while let Some(event) = stream.next().await {
+                    match event {
+                        Ok(event) => on_event(event),
+                        Err(err) => {
+                            warn!(%err, topic = T::NAME, "error reading event stream");
+                            return Err(retry)
+                        }
+                    }
+                }
+                Err(retry)
+            })
+            .await;
+        if result.is_err() {
+            error!(
+                topic = T::NAME,
+                "failed to read from event stream with exponential backoff, restarting..."
+            );
+        }
+    }
+}
+
 /// Contains the handles to spawned [`Relay`] and [`BlindedBlockProviderServer`] tasks
 ///
 /// This struct is created by the [`Service::spawn`] function
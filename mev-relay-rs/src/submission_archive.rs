@@ -0,0 +1,208 @@
+use ethereum_consensus::primitives::Slot;
+use metrics::counter;
+use mev_rs::{
+    types::{AuctionRequest, SignedBidSubmission, SignedValidatorRegistration},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, SyncSender, TrySendError},
+    thread,
+    time::Duration,
+};
+use tracing::warn;
+
+/// Name of the file in each slot's directory recording the auctions opened for that slot
+pub const AUCTIONS_FILE_NAME: &str = "auctions.jsonl";
+
+/// An auction opened by the relay, with the state its submissions are validated against
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedAuction {
+    pub auction_request: AuctionRequest,
+    /// Registration of the proposer when the auction opened, if any
+    pub registration: Option<SignedValidatorRegistration>,
+}
+
+// Number of writes that may wait for the writer before further writes are dropped
+const MAX_PENDING_WRITES: usize = 1024;
+
+enum Task {
+    Submission { path: PathBuf, slot: Slot, signed_submission: SignedBidSubmission },
+    Auction { path: PathBuf, slot: Slot, auction: ArchivedAuction },
+    Prune { retain_slots: RangeInclusive<Slot> },
+}
+
+/// Records the block submissions the relay receives from allowed builders, valid or not, so the
+/// traffic can later be replayed through the relay's validation with `mev relay replay`
+///
+/// Each submission is written as JSON to `{path}/{slot}/{receive_time_ms}-{block_hash}.json`,
+/// with zero-padded numbers so the files sort in the order they were received. The auctions
+/// opened for the slot are appended to `{path}/{slot}/auctions.jsonl`.
+///
+/// Writes are made from a dedicated thread. If the writer falls behind, further writes are
+/// dropped rather than held in memory, as counted by `mev_relay_submission_archive_dropped_total`.
+pub struct SubmissionArchive {
+    writer: Option<(PathBuf, SyncSender<Task>)>,
+}
+
+impl SubmissionArchive {
+    pub fn new(path: Option<PathBuf>) -> Result<Self, Error> {
+        let Some(path) = path else { return Ok(Self { writer: None }) };
+
+        let (tx, rx) = mpsc::sync_channel(MAX_PENDING_WRITES);
+        thread::Builder::new()
+            .name("mev-relay-submission-archive".to_string())
+            .spawn({
+                let path = path.clone();
+                move || run_writer(&path, rx)
+            })
+            .map_err(|err| {
+                Error::InvalidConfig(format!("could not spawn submission archive writer: {err}"))
+            })?;
+        Ok(Self { writer: Some((path, tx)) })
+    }
+
+    fn send(&self, task: Task) {
+        let Some((_, tasks)) = self.writer.as_ref() else { return };
+
+        match tasks.try_send(task) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                counter!("mev_relay_submission_archive_dropped_total").increment(1);
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                counter!("mev_relay_submission_archive_dropped_total").increment(1);
+                warn!("submission archive writer has stopped; dropping write");
+            }
+        }
+    }
+
+    /// Records `signed_submission`, which must have been signed by an allowed builder for a slot
+    /// the relay could hold an auction for, so arbitrary traffic can not fill the disk
+    pub fn record(&self, signed_submission: &SignedBidSubmission, receive_duration: Duration) {
+        let Some((path, _)) = self.writer.as_ref() else { return };
+
+        let bid_trace = signed_submission.message();
+        let slot = bid_trace.slot;
+        let path = slot_path(path, slot).join(format!(
+            "{:013}-{}.json",
+            receive_duration.as_millis(),
+            bid_trace.block_hash
+        ));
+        self.send(Task::Submission { path, slot, signed_submission: signed_submission.clone() });
+    }
+
+    pub fn record_auction(&self, auction: ArchivedAuction) {
+        let Some((path, _)) = self.writer.as_ref() else { return };
+
+        let slot = auction.auction_request.slot;
+        let path = slot_path(path, slot).join(AUCTIONS_FILE_NAME);
+        self.send(Task::Auction { path, slot, auction });
+    }
+
+    /// Removes the submissions archived for slots outside of `retain_slots`
+    pub fn prune(&self, retain_slots: RangeInclusive<Slot>) {
+        self.send(Task::Prune { retain_slots });
+    }
+}
+
+/// Loads the auctions archived in the directory of a slot
+pub fn load_auctions(path: &Path) -> io::Result<Vec<ArchivedAuction>> {
+    let data = fs::read_to_string(path)?;
+    let mut auctions = vec![];
+    for line in data.lines().filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str(line) {
+            Ok(auction) => auctions.push(auction),
+            Err(err) => warn!(%err, path = %path.display(), "skipping invalid archived auction"),
+        }
+    }
+    Ok(auctions)
+}
+
+fn slot_path(path: &Path, slot: Slot) -> PathBuf {
+    path.join(format!("{slot:010}"))
+}
+
+fn write(path: &PathBuf, signed_submission: &SignedBidSubmission) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let data = serde_json::to_vec(signed_submission)?;
+    fs::write(path, data)
+}
+
+fn append(path: &Path, auction: &ArchivedAuction) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_vec(auction)?;
+    line.push(b'\n');
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(&line)
+}
+
+fn run_writer(root: &Path, tasks: Receiver<Task>) {
+    while let Ok(task) = tasks.recv() {
+        match task {
+            Task::Submission { path, slot, signed_submission } => {
+                if let Err(err) = write(&path, &signed_submission) {
+                    warn!(%err, slot, path = %path.display(), "could not archive block submission");
+                }
+            }
+            Task::Auction { path, slot, auction } => {
+                if let Err(err) = append(&path, &auction) {
+                    warn!(%err, slot, path = %path.display(), "could not archive auction");
+                }
+            }
+            Task::Prune { retain_slots } => {
+                if let Err(err) = prune(root, &retain_slots) {
+                    warn!(%err, ?retain_slots, path = %root.display(), "could not prune submission archive");
+                }
+            }
+        }
+    }
+}
+
+fn prune(path: &Path, retain_slots: &RangeInclusive<Slot>) -> io::Result<()> {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    for entry in entries {
+        let path = entry?.path();
+        let slot = path.file_name().and_then(|name| name.to_str()?.parse::<Slot>().ok());
+        if path.is_dir() && slot.is_some_and(|slot| !retain_slots.contains(&slot)) {
+            fs::remove_dir_all(&path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prune_keeps_retained_slots() {
+        let path = std::env::temp_dir()
+            .join(format!("mev-relay-submission-archive-{}", std::process::id()));
+        for slot in [1, 5, 9, 1_000_000] {
+            fs::create_dir_all(slot_path(&path, slot)).unwrap();
+        }
+
+        prune(&path, &(5..=9)).unwrap();
+
+        let mut slots = fs::read_dir(&path)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_str().unwrap().parse::<Slot>().unwrap())
+            .collect::<Vec<_>>();
+        slots.sort();
+        fs::remove_dir_all(&path).unwrap();
+        assert_eq!(slots, vec![5, 9]);
+    }
+}
@@ -0,0 +1,168 @@
+use ethereum_consensus::primitives::{BlsPublicKey, Hash32, Slot};
+use mev_rs::types::AuctionRequest;
+use std::{
+    collections::{BTreeSet, HashMap},
+    ops::Bound,
+};
+
+static EMPTY: BTreeSet<AuctionRequest> = BTreeSet::new();
+
+/// Filters supported by [`TraceIndex::query`]
+#[derive(Debug, Default)]
+pub struct TraceQuery<'a> {
+    pub slot: Option<Slot>,
+    /// Only match auctions at or before this slot
+    pub cursor: Option<Slot>,
+    pub block_hash: Option<&'a Hash32>,
+    pub builder_public_key: Option<&'a BlsPublicKey>,
+    pub proposer_public_key: Option<&'a BlsPublicKey>,
+}
+
+/// Indexes the auctions with traces served by the data APIs by block hash, builder and proposer
+/// so filtered queries only visit the matching auctions
+///
+/// An auction can have traces for many blocks, so the traces of each auction returned from a
+/// query should still be filtered.
+#[derive(Debug, Default)]
+pub struct TraceIndex {
+    auctions: BTreeSet<AuctionRequest>,
+    by_block_hash: HashMap<Hash32, BTreeSet<AuctionRequest>>,
+    by_builder: HashMap<BlsPublicKey, BTreeSet<AuctionRequest>>,
+    by_proposer: HashMap<BlsPublicKey, BTreeSet<AuctionRequest>>,
+}
+
+fn retain_index<K>(
+    index: &mut HashMap<K, BTreeSet<AuctionRequest>>,
+    f: &impl Fn(&AuctionRequest) -> bool,
+) {
+    index.retain(|_, auctions| {
+        auctions.retain(f);
+        !auctions.is_empty()
+    });
+}
+
+impl TraceIndex {
+    pub fn insert(
+        &mut self,
+        auction_request: &AuctionRequest,
+        block_hash: &Hash32,
+        builder_public_key: &BlsPublicKey,
+    ) {
+        self.auctions.insert(auction_request.clone());
+        self.by_block_hash.entry(block_hash.clone()).or_default().insert(auction_request.clone());
+        self.by_builder
+            .entry(builder_public_key.clone())
+            .or_default()
+            .insert(auction_request.clone());
+        self.by_proposer
+            .entry(auction_request.public_key.clone())
+            .or_default()
+            .insert(auction_request.clone());
+    }
+
+    /// Keeps only the auctions for which `f` returns `true`
+    pub fn retain(&mut self, f: impl Fn(&AuctionRequest) -> bool) {
+        self.auctions.retain(&f);
+        retain_index(&mut self.by_block_hash, &f);
+        retain_index(&mut self.by_builder, &f);
+        retain_index(&mut self.by_proposer, &f);
+    }
+
+    /// Returns the auctions that may have traces matching `query`, most recent first
+    pub fn query<'a>(
+        &'a self,
+        query: &'a TraceQuery<'a>,
+    ) -> impl Iterator<Item = &'a AuctionRequest> + 'a {
+        let block_hash_auctions =
+            query.block_hash.map(|block_hash| self.by_block_hash.get(block_hash).unwrap_or(&EMPTY));
+        let builder_auctions = query
+            .builder_public_key
+            .map(|public_key| self.by_builder.get(public_key).unwrap_or(&EMPTY));
+        let proposer_auctions = query
+            .proposer_public_key
+            .map(|public_key| self.by_proposer.get(public_key).unwrap_or(&EMPTY));
+        // walk the smallest set of auctions matching any filter, checking the others as we go
+        let auctions = [block_hash_auctions, builder_auctions, proposer_auctions]
+            .into_iter()
+            .flatten()
+            .min_by_key(|auctions| auctions.len())
+            .unwrap_or(&self.auctions);
+
+        let lowest_slot = query.slot.unwrap_or_default();
+        let highest_slot = match (query.slot, query.cursor) {
+            (Some(slot), Some(cursor)) => Some(slot.min(cursor)),
+            (slot, cursor) => slot.or(cursor),
+        };
+        let start = AuctionRequest { slot: lowest_slot, ..Default::default() };
+        let end = match highest_slot {
+            Some(slot) if slot < lowest_slot => Bound::Excluded(start.clone()),
+            Some(slot) => match slot.checked_add(1) {
+                Some(slot) => Bound::Excluded(AuctionRequest { slot, ..Default::default() }),
+                None => Bound::Unbounded,
+            },
+            None => Bound::Unbounded,
+        };
+
+        auctions.range((Bound::Included(start), end)).rev().filter(move |auction_request| {
+            let contains = |auctions: Option<&BTreeSet<AuctionRequest>>| {
+                auctions.map(|auctions| auctions.contains(auction_request)).unwrap_or(true)
+            };
+            contains(block_hash_auctions) &&
+                contains(builder_auctions) &&
+                contains(proposer_auctions)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethereum_consensus::crypto::SecretKey;
+
+    fn public_key(seed: u8) -> BlsPublicKey {
+        SecretKey::try_from([seed; 32].as_ref()).unwrap().public_key()
+    }
+
+    fn auction_request(slot: Slot, public_key: &BlsPublicKey) -> AuctionRequest {
+        AuctionRequest { slot, parent_hash: Default::default(), public_key: public_key.clone() }
+    }
+
+    #[test]
+    fn test_trace_index_query() {
+        let proposer = public_key(1);
+        let builder = public_key(2);
+        let block_hash = Hash32::try_from([3u8; 32].as_ref()).unwrap();
+
+        let mut index = TraceIndex::default();
+        let other_proposer = public_key(4);
+        for slot in 0..10 {
+            index.insert(&auction_request(slot, &other_proposer), &Default::default(), &builder);
+        }
+        index.insert(&auction_request(4, &proposer), &block_hash, &builder);
+
+        let slots = |query: &TraceQuery| {
+            index.query(query).map(|auction_request| auction_request.slot).collect::<Vec<_>>()
+        };
+        assert_eq!(slots(&TraceQuery { cursor: Some(2), ..Default::default() }), vec![2, 1, 0]);
+        assert_eq!(slots(&TraceQuery { slot: Some(4), ..Default::default() }).len(), 2);
+        assert_eq!(
+            slots(&TraceQuery { slot: Some(5), cursor: Some(4), ..Default::default() }),
+            Vec::<Slot>::new()
+        );
+        let query = TraceQuery {
+            block_hash: Some(&block_hash),
+            builder_public_key: Some(&builder),
+            ..Default::default()
+        };
+        assert_eq!(slots(&query), vec![4]);
+        let query = TraceQuery { proposer_public_key: Some(&proposer), ..Default::default() };
+        assert_eq!(slots(&query), vec![4]);
+
+        index.retain(|auction_request| auction_request.slot >= 5);
+        assert_eq!(slots(&TraceQuery::default()), vec![9, 8, 7, 6, 5]);
+        assert_eq!(
+            slots(&TraceQuery { proposer_public_key: Some(&proposer), ..Default::default() }),
+            Vec::<Slot>::new()
+        );
+    }
+}
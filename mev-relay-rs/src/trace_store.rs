@@ -0,0 +1,180 @@
+use ethereum_consensus::primitives::Slot;
+use mev_rs::types::block_submission::data_api::{PayloadTrace, SubmissionTrace};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+use tracing::{info, warn};
+
+/// A trace imported from the data API of another relay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ImportedTrace {
+    DeliveredPayload(PayloadTrace),
+    BlockSubmission(SubmissionTrace),
+}
+
+impl ImportedTrace {
+    pub fn slot(&self) -> Slot {
+        match self {
+            Self::DeliveredPayload(trace) => trace.slot,
+            Self::BlockSubmission(trace) => trace.slot,
+        }
+    }
+}
+
+/// Persists the traces imported from other relays so they are served across restarts
+///
+/// Traces are appended as JSON lines to `{path}/{slot}.jsonl`, one file per slot so the traces
+/// past the relay's history can be removed whole.
+pub struct TraceStore {
+    path: Option<PathBuf>,
+}
+
+impl TraceStore {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self { path }
+    }
+
+    /// Returns the traces persisted by earlier runs of the relay
+    pub fn load(&self) -> Vec<ImportedTrace> {
+        let Some(path) = self.path.as_ref() else { return vec![] };
+
+        match load(path) {
+            Ok(traces) => {
+                info!(count = traces.len(), path = %path.display(), "loaded imported traces");
+                traces
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => vec![],
+            Err(err) => {
+                warn!(%err, path = %path.display(), "could not load imported traces");
+                vec![]
+            }
+        }
+    }
+
+    pub fn record(&self, traces: Vec<ImportedTrace>) {
+        let Some(path) = self.path.clone() else { return };
+        if traces.is_empty() {
+            return
+        }
+
+        let mut traces_by_slot = BTreeMap::<_, Vec<_>>::new();
+        for trace in traces {
+            traces_by_slot.entry(trace.slot()).or_default().push(trace);
+        }
+        std::mem::drop(tokio::task::spawn_blocking(move || {
+            for (slot, traces) in traces_by_slot {
+                if let Err(err) = append(&slot_path(&path, slot), &traces) {
+                    warn!(%err, slot, path = %path.display(), "could not persist imported traces");
+                }
+            }
+        }));
+    }
+
+    /// Removes the traces persisted for slots before `retain_slot`
+    pub fn prune(&self, retain_slot: Slot) {
+        let Some(path) = self.path.clone() else { return };
+
+        std::mem::drop(tokio::task::spawn_blocking(move || {
+            if let Err(err) = prune(&path, retain_slot) {
+                warn!(%err, retain_slot, path = %path.display(), "could not prune imported traces");
+            }
+        }));
+    }
+}
+
+fn slot_path(path: &Path, slot: Slot) -> PathBuf {
+    path.join(format!("{slot:010}.jsonl"))
+}
+
+fn slot_of(path: &Path) -> Option<Slot> {
+    if path.extension()? != "jsonl" {
+        return None
+    }
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+fn append(path: &Path, traces: &[ImportedTrace]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut data = vec![];
+    for trace in traces {
+        serde_json::to_writer(&mut data, trace)?;
+        data.push(b'\n');
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(&data)
+}
+
+fn load(path: &Path) -> io::Result<Vec<ImportedTrace>> {
+    let mut traces = vec![];
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        if slot_of(&path).is_none() {
+            continue
+        }
+        let data = fs::read_to_string(&path)?;
+        for line in data.lines().filter(|line| !line.trim().is_empty()) {
+            match serde_json::from_str(line) {
+                Ok(trace) => traces.push(trace),
+                Err(err) => warn!(%err, path = %path.display(), "skipping invalid imported trace"),
+            }
+        }
+    }
+    Ok(traces)
+}
+
+fn prune(path: &Path, retain_slot: Slot) -> io::Result<()> {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    for entry in entries {
+        let path = entry?.path();
+        if slot_of(&path).is_some_and(|slot| slot < retain_slot) {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(slot: Slot) -> ImportedTrace {
+        ImportedTrace::BlockSubmission(SubmissionTrace { slot, ..Default::default() })
+    }
+
+    #[test]
+    fn test_trace_store_round_trip() {
+        let path = std::env::temp_dir().join(format!("mev-relay-traces-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        append(&slot_path(&path, 1), &[submission(1)]).unwrap();
+        append(
+            &slot_path(&path, 2),
+            &[
+                submission(2),
+                ImportedTrace::DeliveredPayload(PayloadTrace { slot: 2, ..Default::default() }),
+            ],
+        )
+        .unwrap();
+
+        let store = TraceStore::new(Some(path.clone()));
+        let mut slots = store.load().iter().map(ImportedTrace::slot).collect::<Vec<_>>();
+        slots.sort();
+        assert_eq!(slots, vec![1, 2, 2]);
+
+        prune(&path, 2).unwrap();
+        let slots = store.load().iter().map(ImportedTrace::slot).collect::<Vec<_>>();
+        assert_eq!(slots, vec![2, 2]);
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+}
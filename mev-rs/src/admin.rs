@@ -0,0 +1,90 @@
+use crate::{config::AdminConfig, error::Error as MevError, request_id::propagate_request_id};
+use async_trait::async_trait;
+use axum::{
+    extract::{Json, State},
+    http::StatusCode,
+    middleware,
+    routing::get,
+    Router,
+};
+use std::{error::Error, net::SocketAddr, sync::OnceLock};
+use tokio::task::JoinHandle;
+use tower_http::validate_request::ValidateRequestHeaderLayer;
+use tracing::{error, info, warn};
+
+static LOG_FILTER: OnceLock<Box<dyn LogFilter>> = OnceLock::new();
+
+/// A tracing filter that can be changed while the process is running
+pub trait LogFilter: Send + Sync {
+    fn current(&self) -> String;
+
+    fn update(&self, directives: &str) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// Registers the process-wide `filter` exposed by the admin API
+pub fn set_log_filter(filter: impl LogFilter + 'static) {
+    if LOG_FILTER.set(Box::new(filter)).is_err() {
+        warn!("log filter was already set");
+    }
+}
+
+/// Summary of the internal state of a service, served by the admin API
+#[async_trait]
+pub trait Introspect {
+    async fn state_summary(&self) -> serde_json::Value;
+}
+
+async fn handle_get_log_filter() -> Result<String, StatusCode> {
+    LOG_FILTER.get().map(|filter| filter.current()).ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn handle_set_log_filter(directives: String) -> Result<(), (StatusCode, String)> {
+    let filter = LOG_FILTER
+        .get()
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "log filter is not configurable".to_string()))?;
+    let directives = directives.trim();
+    filter.update(directives).map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))?;
+    info!(directives, "updated log filter");
+    Ok(())
+}
+
+async fn handle_get_state<S: Introspect>(State(service): State<S>) -> Json<serde_json::Value> {
+    Json(service.state_summary().await)
+}
+
+/// Returns the admin routes common to all services, which can be extended with more routes
+/// for a given service before passing to [`spawn`]
+pub fn router<S: Introspect + Clone + Send + Sync + 'static>() -> Router<S> {
+    Router::new()
+        .route("/admin/v1/log_filter", get(handle_get_log_filter).put(handle_set_log_filter))
+        .route("/admin/v1/state", get(handle_get_state::<S>))
+}
+
+/// Spawns the admin API serving `router` on a new task returning the handle for it
+///
+/// Refuses to serve on an address reachable from outside this host without an `auth_token`.
+pub fn spawn(config: &AdminConfig, router: Router) -> Result<JoinHandle<()>, MevError> {
+    let router = if config.auth_token.is_empty() {
+        if !config.host.is_loopback() {
+            return Err(MevError::InvalidConfig(format!(
+                "admin API on non-loopback host {} requires an `auth_token`",
+                config.host
+            )))
+        }
+        router
+    } else {
+        if !config.host.is_loopback() {
+            warn!(host = %config.host, "admin API is reachable from outside this host");
+        }
+        router.layer(ValidateRequestHeaderLayer::bearer(&config.auth_token))
+    };
+    let router = router.layer(middleware::from_fn(propagate_request_id));
+    let addr = SocketAddr::from((config.host, config.port));
+    let server = axum::Server::bind(&addr).serve(router.into_make_service());
+    Ok(tokio::spawn(async move {
+        info!("admin API listening at {addr}...");
+        if let Err(err) = server.await {
+            error!(%err, "error while listening for incoming")
+        }
+    }))
+}
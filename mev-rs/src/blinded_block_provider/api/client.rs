@@ -1,20 +1,70 @@
 use crate::{
+    relay::as_base_url,
+    request_id::{self, REQUEST_ID_HEADER},
     types::{
         AuctionContents, AuctionRequest, SignedBlindedBeaconBlock, SignedBuilderBid,
         SignedValidatorRegistration,
     },
     Error,
 };
-use axum::http::{Method, StatusCode};
+use axum::http::{header::CONTENT_TYPE, Method, StatusCode};
+use backoff::{ExponentialBackoff, ExponentialBackoffBuilder};
 use beacon_api_client::{
     api_error_or_ok, ApiResult, Error as ApiError, VersionedValue, ETH_CONSENSUS_VERSION_HEADER,
 };
+use bytes::Bytes;
+use std::{future::Future, time::Duration};
 
 #[cfg(not(feature = "minimal-preset"))]
 use beacon_api_client::mainnet::Client as BeaconApiClient;
 #[cfg(feature = "minimal-preset")]
 use beacon_api_client::minimal::Client as BeaconApiClient;
 
+/// Policy to retry idempotent requests that fail due to transient network errors
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Delay before the first retry, growing exponentially for each subsequent retry
+    pub initial_interval: Duration,
+    /// Total time after which no further retries are attempted
+    pub max_elapsed_time: Duration,
+}
+
+impl RetryConfig {
+    fn backoff(&self) -> ExponentialBackoff {
+        ExponentialBackoffBuilder::new()
+            .with_initial_interval(self.initial_interval)
+            .with_max_elapsed_time(Some(self.max_elapsed_time))
+            .build()
+    }
+}
+
+fn is_transient(err: &ApiError) -> bool {
+    matches!(err, ApiError::Http(err) if err.is_connect() || err.is_timeout())
+}
+
+/// A batch of validator registrations serialized once, so the same request body can be sent
+/// to many relays without copying
+#[derive(Debug, Clone)]
+pub struct EncodedRegistrations {
+    count: usize,
+    body: Bytes,
+}
+
+impl EncodedRegistrations {
+    pub fn new(registrations: &[SignedValidatorRegistration]) -> serde_json::Result<Self> {
+        let body = serde_json::to_vec(registrations)?;
+        Ok(Self { count: registrations.len(), body: body.into() })
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
 /// A `Client` for a service implementing the Builder APIs.
 ///
 /// Note that `Client` does not implement the `BlindedBlockProvider` trait so that
@@ -23,15 +73,66 @@ use beacon_api_client::minimal::Client as BeaconApiClient;
 #[derive(Clone)]
 pub struct Client {
     api: BeaconApiClient,
+    retry: Option<RetryConfig>,
 }
 
 impl Client {
-    pub fn new(api_client: BeaconApiClient) -> Self {
-        Self { api: api_client }
+    pub fn new(mut api_client: BeaconApiClient) -> Self {
+        // NOTE: API paths are relative so any path prefix of the endpoint is kept
+        api_client.endpoint = as_base_url(api_client.endpoint);
+        Self { api: api_client, retry: None }
+    }
+
+    /// Retry status checks according to `retry`
+    ///
+    /// NOTE: `fetch_best_bid` is not retried as it must complete within the proposer's deadline
+    /// for getHeader, after which a retried bid is of no use
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    // Builds a request to `path`, forwarding the id of the request currently being served, if any
+    fn request(&self, method: Method, path: &str) -> Result<reqwest::RequestBuilder, ApiError> {
+        let endpoint = self.api.endpoint.join(path).map_err(ApiError::Url)?;
+        let request = self.api.http.request(method, endpoint);
+        match request_id::current() {
+            Some(request_id) => Ok(request.header(REQUEST_ID_HEADER, request_id)),
+            None => Ok(request),
+        }
+    }
+
+    async fn send_with_retry<T, F, Fut>(&self, mut send: F) -> Result<T, ApiError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, ApiError>>,
+    {
+        let Some(retry) = self.retry.as_ref() else { return send().await };
+
+        backoff::future::retry(retry.backoff(), || {
+            let request = send();
+            async move {
+                request.await.map_err(|err| {
+                    if is_transient(&err) {
+                        backoff::Error::transient(err)
+                    } else {
+                        backoff::Error::permanent(err)
+                    }
+                })
+            }
+        })
+        .await
     }
 
     pub async fn check_status(&self) -> Result<(), beacon_api_client::Error> {
-        let response = self.api.http_get("/eth/v1/builder/status").await?;
+        let response = self
+            .send_with_retry(|| async {
+                self.request(Method::GET, "eth/v1/builder/status")?
+                    .send()
+                    .await
+                    .map_err(ApiError::Http)
+            })
+            .await?;
         api_error_or_ok(response).await
     }
 
@@ -39,7 +140,26 @@ impl Client {
         &self,
         registrations: &[SignedValidatorRegistration],
     ) -> Result<(), Error> {
-        let response = self.api.http_post("/eth/v1/builder/validators", &registrations).await?;
+        let response = self
+            .request(Method::POST, "eth/v1/builder/validators")?
+            .json(&registrations)
+            .send()
+            .await
+            .map_err(ApiError::Http)?;
+        api_error_or_ok(response).await.map_err(From::from)
+    }
+
+    pub async fn register_encoded_validators(
+        &self,
+        registrations: &EncodedRegistrations,
+    ) -> Result<(), Error> {
+        let response = self
+            .request(Method::POST, "eth/v1/builder/validators")?
+            .header(CONTENT_TYPE, "application/json")
+            .body(registrations.body.clone())
+            .send()
+            .await
+            .map_err(ApiError::Http)?;
         api_error_or_ok(response).await.map_err(From::from)
     }
 
@@ -48,10 +168,10 @@ impl Client {
         auction_request: &AuctionRequest,
     ) -> Result<SignedBuilderBid, Error> {
         let target = format!(
-            "/eth/v1/builder/header/{}/{:?}/{:?}",
+            "eth/v1/builder/header/{}/{:?}/{:?}",
             auction_request.slot, auction_request.parent_hash, auction_request.public_key
         );
-        let response = self.api.http_get(&target).await?;
+        let response = self.request(Method::GET, &target)?.send().await.map_err(ApiError::Http)?;
 
         if response.status() == StatusCode::NO_CONTENT {
             return Err(Error::NoBidPrepared(auction_request.clone()))
@@ -69,15 +189,8 @@ impl Client {
         &self,
         signed_block: &SignedBlindedBeaconBlock,
     ) -> Result<AuctionContents, Error> {
-        let endpoint = self
-            .api
-            .endpoint
-            .join("/eth/v1/builder/blinded_blocks")
-            .map_err(beacon_api_client::Error::Url)?;
         let response = self
-            .api
-            .http
-            .request(Method::POST, endpoint)
+            .request(Method::POST, "eth/v1/builder/blinded_blocks")?
             .header(ETH_CONSENSUS_VERSION_HEADER, signed_block.version().to_string())
             .json(signed_block)
             .send()
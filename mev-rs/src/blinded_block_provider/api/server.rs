@@ -1,31 +1,66 @@
 use crate::{
     blinded_block_provider::BlindedBlockProvider,
+    config::{ServerConfig, TlsConfig},
     error::Error,
+    request_id::propagate_request_id,
     types::{
         AuctionContents, AuctionRequest, SignedBlindedBeaconBlock, SignedBuilderBid,
         SignedValidatorRegistration,
     },
 };
 use axum::{
-    extract::{Json, Path, State},
-    http::StatusCode,
-    response::IntoResponse,
+    extract::{DefaultBodyLimit, Json, Path, State},
+    middleware,
     routing::{get, post, IntoMakeService},
     Router,
 };
+use axum_server::tls_rustls::RustlsConfig;
 use beacon_api_client::VersionedValue;
 use hyper::server::conn::AddrIncoming;
-use std::net::{Ipv4Addr, SocketAddr};
+use std::net::{IpAddr, SocketAddr};
 use tokio::task::JoinHandle;
+use tower_http::{compression::CompressionLayer, timeout::TimeoutLayer};
 use tracing::{error, info, trace};
 
 /// Type alias for the configured axum server
 pub type BlockProviderServer = axum::Server<AddrIncoming, IntoMakeService<Router>>;
 
-pub(crate) async fn handle_status_check() -> impl IntoResponse {
-    StatusCode::OK
+/// Applies the limits and compression from `config` to every route in `router`, along with
+/// request id propagation
+pub(crate) fn with_common_layers(router: Router, config: &ServerConfig) -> Router {
+    let router = router
+        .layer(DefaultBodyLimit::max(config.max_body_size_bytes))
+        .layer(TimeoutLayer::new(config.request_timeout()));
+    let router = if config.compression { router.layer(CompressionLayer::new()) } else { router };
+    router.layer(middleware::from_fn(propagate_request_id))
 }
 
+/// Spawns a task serving `router` over HTTPS at `addr` using the certificate in `tls`
+pub(crate) fn spawn_with_tls(router: Router, addr: SocketAddr, tls: TlsConfig) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let tls_config = match RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path).await {
+            Ok(config) => config,
+            Err(err) => {
+                error!(%err, "could not load TLS certificate and key");
+                return
+            }
+        };
+        info!("listening at {addr} with TLS...");
+        let server = axum_server::bind_rustls(addr, tls_config)
+            .serve(router.into_make_service_with_connect_info::<SocketAddr>());
+        if let Err(err) = server.await {
+            error!(%err, "error while listening for incoming")
+        }
+    })
+}
+
+pub(crate) async fn handle_status_check<B: BlindedBlockProvider>(
+    State(builder): State<B>,
+) -> Result<(), Error> {
+    builder.check_status().await
+}
+
+#[tracing::instrument(skip_all)]
 pub(crate) async fn handle_validator_registration<B: BlindedBlockProvider>(
     State(builder): State<B>,
     Json(registrations): Json<Vec<SignedValidatorRegistration>>,
@@ -34,6 +69,7 @@ pub(crate) async fn handle_validator_registration<B: BlindedBlockProvider>(
     builder.register_validators(&registrations).await.map_err(From::from)
 }
 
+#[tracing::instrument(skip_all, fields(%auction_request))]
 pub(crate) async fn handle_fetch_bid<B: BlindedBlockProvider>(
     State(builder): State<B>,
     Path(auction_request): Path<AuctionRequest>,
@@ -45,6 +81,7 @@ pub(crate) async fn handle_fetch_bid<B: BlindedBlockProvider>(
     Ok(Json(response))
 }
 
+#[tracing::instrument(skip_all)]
 pub(crate) async fn handle_open_bid<B: BlindedBlockProvider>(
     State(builder): State<B>,
     Json(block): Json<SignedBlindedBeaconBlock>,
@@ -60,20 +97,27 @@ pub(crate) async fn handle_open_bid<B: BlindedBlockProvider>(
 }
 
 pub struct Server<B: BlindedBlockProvider> {
-    host: Ipv4Addr,
+    host: IpAddr,
     port: u16,
+    config: ServerConfig,
     builder: B,
+    routes: Option<Router>,
 }
 
 impl<B: BlindedBlockProvider + Clone + Send + Sync + 'static> Server<B> {
-    pub fn new(host: Ipv4Addr, port: u16, builder: B) -> Self {
-        Self { host, port, builder }
+    pub fn new(host: IpAddr, port: u16, config: ServerConfig, builder: B) -> Self {
+        Self { host, port, config, builder, routes: None }
     }
 
-    /// Configures and returns the axum server
-    pub fn serve(&self) -> BlockProviderServer {
+    /// Serves `routes` alongside the builder APIs
+    pub fn with_routes(mut self, routes: Router) -> Self {
+        self.routes = Some(routes);
+        self
+    }
+
+    fn router(&self) -> Router {
         let router = Router::new()
-            .route("/eth/v1/builder/status", get(handle_status_check))
+            .route("/eth/v1/builder/status", get(handle_status_check::<B>))
             .route("/eth/v1/builder/validators", post(handle_validator_registration::<B>))
             .route(
                 "/eth/v1/builder/header/:slot/:parent_hash/:public_key",
@@ -81,12 +125,28 @@ impl<B: BlindedBlockProvider + Clone + Send + Sync + 'static> Server<B> {
             )
             .route("/eth/v1/builder/blinded_blocks", post(handle_open_bid::<B>))
             .with_state(self.builder.clone());
+        let router = match self.routes.clone() {
+            Some(routes) => router.merge(routes),
+            None => router,
+        };
+        with_common_layers(router, &self.config)
+    }
+
+    /// Configures and returns the axum server
+    pub fn serve(&self) -> BlockProviderServer {
         let addr = SocketAddr::from((self.host, self.port));
-        axum::Server::bind(&addr).serve(router.into_make_service())
+        axum::Server::bind(&addr).serve(self.router().into_make_service())
     }
 
     /// Spawns the server on a new task returning the handle for it
+    ///
+    /// Serves over HTTPS if TLS is configured
     pub fn spawn(&self) -> JoinHandle<()> {
+        if let Some(tls) = self.config.tls.clone() {
+            let addr = SocketAddr::from((self.host, self.port));
+            return spawn_with_tls(self.router(), addr, tls)
+        }
+
         let server = self.serve();
         let address = server.local_addr();
         tokio::spawn(async move {
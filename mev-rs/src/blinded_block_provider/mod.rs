@@ -2,7 +2,10 @@
 pub(crate) mod api;
 
 #[cfg(feature = "builder-api")]
-pub use {api::client::Client, api::server::Server};
+pub use {
+    api::client::{Client, EncodedRegistrations, RetryConfig},
+    api::server::Server,
+};
 
 use crate::{
     error::Error,
@@ -15,6 +18,11 @@ use async_trait::async_trait;
 
 #[async_trait]
 pub trait BlindedBlockProvider {
+    /// Checks the provider is ready to serve auctions, backing `/eth/v1/builder/status`
+    async fn check_status(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
     async fn register_validators(
         &self,
         registrations: &[SignedValidatorRegistration],
@@ -1,9 +1,30 @@
 use crate::{
-    blinded_block_relayer::BlindedBlockRelayer,
-    types::{ProposerSchedule, SignedBidSubmission},
+    blinded_block_relayer::{
+        BlindedBlockRelayer, BlockSubmissionFilter, DeliveredPayloadFilter,
+        LatePayloadRequestFilter, RegistrationStats, RelayStatus,
+    },
+    config::SubmissionEncoding,
+    relay::as_base_url,
+    types::{
+        block_submission::data_api::{LatePayloadRequestTrace, PayloadTrace, SubmissionTrace},
+        ProposerSchedule, SignedBidSubmission, SignedHeaderSubmission, SignedValidatorRegistration,
+    },
     Error,
 };
-use beacon_api_client::api_error_or_ok;
+use axum::http::{
+    header::{CONTENT_ENCODING, CONTENT_TYPE},
+    StatusCode,
+};
+use beacon_api_client::{
+    api_error_or_ok, ApiResult, Error as ApiError, ETH_CONSENSUS_VERSION_HEADER,
+};
+use ethereum_consensus::{
+    primitives::{BlsPublicKey, Slot},
+    ssz::prelude::serialize,
+};
+use flate2::{write::GzEncoder, Compression};
+use std::io::Write;
+use url::form_urlencoded;
 
 #[cfg(not(feature = "minimal-preset"))]
 use beacon_api_client::mainnet::Client as BeaconApiClient;
@@ -14,22 +35,193 @@ use beacon_api_client::minimal::Client as BeaconApiClient;
 #[derive(Clone)]
 pub struct Client {
     api: BeaconApiClient,
+    submission_encoding: SubmissionEncoding,
+    cancellations: bool,
+}
+
+// Appends the parameters that are present to `path` as a query string.
+fn with_query(path: &str, params: &[(&str, Option<String>)]) -> String {
+    let mut query = form_urlencoded::Serializer::new(String::new());
+    for (key, value) in params {
+        if let Some(value) = value {
+            query.append_pair(key, value);
+        }
+    }
+    let query = query.finish();
+    if query.is_empty() {
+        path.to_string()
+    } else {
+        format!("{path}?{query}")
+    }
 }
 
 impl Client {
-    pub fn new(api_client: BeaconApiClient) -> Self {
-        Self { api: api_client }
+    pub fn new(mut api_client: BeaconApiClient) -> Self {
+        // NOTE: API paths are relative so any path prefix of the endpoint is kept
+        api_client.endpoint = as_base_url(api_client.endpoint);
+        Self { api: api_client, submission_encoding: Default::default(), cancellations: false }
+    }
+
+    /// Send block submissions with the given `encoding`
+    pub fn with_submission_encoding(mut self, encoding: SubmissionEncoding) -> Self {
+        self.submission_encoding = encoding;
+        self
+    }
+
+    /// Ask the relay to accept bids lower than earlier ones from this builder in the same slot
+    pub fn with_cancellations(mut self, enabled: bool) -> Self {
+        self.cancellations = enabled;
+        self
+    }
+
+    pub fn cancellations(&self) -> bool {
+        self.cancellations
+    }
+
+    fn blocks_path(&self) -> &'static str {
+        if self.cancellations {
+            "relay/v1/builder/blocks?cancellations=1"
+        } else {
+            "relay/v1/builder/blocks"
+        }
+    }
+
+    // Sends `signed_submission` encoded as SSZ, compressed with gzip if `compress` is set.
+    async fn submit_ssz_bid(
+        &self,
+        signed_submission: &SignedBidSubmission,
+        compress: bool,
+    ) -> Result<(), Error> {
+        let mut body = serialize(signed_submission)?;
+        let endpoint = self.api.endpoint.join(self.blocks_path()).map_err(ApiError::Url)?;
+        let mut request = self
+            .api
+            .http
+            .post(endpoint)
+            .header(CONTENT_TYPE, "application/octet-stream")
+            .header(ETH_CONSENSUS_VERSION_HEADER, signed_submission.version().to_string());
+        if compress {
+            let mut encoder = GzEncoder::new(Vec::with_capacity(body.len()), Compression::fast());
+            encoder.write_all(&body).expect("can write to memory");
+            body = encoder.finish().expect("can write to memory");
+            request = request.header(CONTENT_ENCODING, "gzip");
+        }
+        let response = request.body(body).send().await.map_err(ApiError::Http)?;
+        api_error_or_ok(response).await.map_err(From::from)
+    }
+
+    /// Fetches the payloads delivered by the relay matching `filters` from its data API
+    pub async fn get_delivered_payloads(
+        &self,
+        filters: &DeliveredPayloadFilter,
+    ) -> Result<Vec<PayloadTrace>, Error> {
+        let path = with_query(
+            "relay/v1/data/bidtraces/proposer_payload_delivered",
+            &[
+                ("slot", filters.slot.map(|slot| slot.to_string())),
+                ("block_hash", filters.block_hash.as_ref().map(ToString::to_string)),
+                ("block_number", filters.block_number.map(|number| number.to_string())),
+                ("proposer_pubkey", filters.proposer_public_key.as_ref().map(ToString::to_string)),
+                ("builder_pubkey", filters.builder_public_key.as_ref().map(ToString::to_string)),
+                ("cursor", filters.cursor.map(|cursor| cursor.to_string())),
+                ("limit", filters.limit.map(|limit| limit.to_string())),
+            ],
+        );
+        self.api.get(&path).await.map_err(From::from)
+    }
+
+    /// Fetches the block submissions received by the relay matching `filters` from its data API
+    pub async fn get_block_submissions(
+        &self,
+        filters: &BlockSubmissionFilter,
+    ) -> Result<Vec<SubmissionTrace>, Error> {
+        let path = with_query(
+            "relay/v1/data/bidtraces/builder_blocks_received",
+            &[
+                ("slot", filters.slot.map(|slot| slot.to_string())),
+                ("block_hash", filters.block_hash.as_ref().map(ToString::to_string)),
+                ("block_number", filters.block_number.map(|number| number.to_string())),
+                ("builder_pubkey", filters.builder_public_key.as_ref().map(ToString::to_string)),
+                ("limit", filters.limit.map(|limit| limit.to_string())),
+            ],
+        );
+        self.api.get(&path).await.map_err(From::from)
+    }
+
+    /// Fetches the registration of the validator with `public_key` known to the relay from its
+    /// data API
+    pub async fn fetch_validator_registration(
+        &self,
+        public_key: &BlsPublicKey,
+    ) -> Result<SignedValidatorRegistration, Error> {
+        let path = with_query(
+            "relay/v1/data/validator_registration",
+            &[("pubkey", Some(public_key.to_string()))],
+        );
+        self.api.get(&path).await.map_err(From::from)
+    }
+
+    /// Fetches the signed blinded beacon blocks the relay refused for arriving too late in their
+    /// slot matching `filters` from its data API
+    pub async fn get_late_payload_requests(
+        &self,
+        filters: &LatePayloadRequestFilter,
+    ) -> Result<Vec<LatePayloadRequestTrace>, Error> {
+        let path = with_query(
+            "relay/v1/data/late_payload_requests",
+            &[
+                ("slot", filters.slot.map(|slot| slot.to_string())),
+                ("proposer_pubkey", filters.proposer_public_key.as_ref().map(ToString::to_string)),
+                ("cursor", filters.cursor.map(|cursor| cursor.to_string())),
+                ("limit", filters.limit.map(|limit| limit.to_string())),
+            ],
+        );
+        self.api.get(&path).await.map_err(From::from)
+    }
+
+    /// Fetches a summary of the validator registrations known to the relay from its data API
+    pub async fn get_registration_stats(&self) -> Result<RegistrationStats, Error> {
+        self.api.get("relay/v1/data/validator_registrations/stats").await.map_err(From::from)
+    }
+
+    /// Fetches the identity and state of the relay
+    pub async fn get_status(&self) -> Result<RelayStatus, Error> {
+        self.api.get("relay/v1/status").await.map_err(From::from)
     }
 }
 
 #[async_trait::async_trait]
 impl BlindedBlockRelayer for Client {
     async fn get_proposal_schedule(&self) -> Result<Vec<ProposerSchedule>, Error> {
-        self.api.get("/relay/v1/builder/validators").await.map_err(From::from)
+        self.api.get("relay/v1/builder/validators").await.map_err(From::from)
+    }
+
+    async fn get_proposer_schedule(&self, slot: Slot) -> Result<Option<ProposerSchedule>, Error> {
+        let path = format!("relay/v1/builder/validators/{slot}");
+        let response = self.api.http_get(&path).await?;
+        if response.status() == StatusCode::NO_CONTENT {
+            return Ok(None)
+        }
+        let result: ApiResult<ProposerSchedule> = response.json().await.map_err(ApiError::Http)?;
+        match result {
+            ApiResult::Ok(schedule) => Ok(Some(schedule)),
+            ApiResult::Err(err) => Err(Error::Api(err.into())),
+        }
     }
 
     async fn submit_bid(&self, signed_submission: &SignedBidSubmission) -> Result<(), Error> {
-        let response = self.api.http_post("/relay/v1/builder/blocks", signed_submission).await?;
+        match self.submission_encoding {
+            SubmissionEncoding::Json => {
+                let response = self.api.http_post(self.blocks_path(), signed_submission).await?;
+                api_error_or_ok(response).await.map_err(From::from)
+            }
+            SubmissionEncoding::Ssz => self.submit_ssz_bid(signed_submission, false).await,
+            SubmissionEncoding::SszGzip => self.submit_ssz_bid(signed_submission, true).await,
+        }
+    }
+
+    async fn submit_header(&self, signed_submission: &SignedHeaderSubmission) -> Result<(), Error> {
+        let response = self.api.http_post("relay/v1/builder/headers", signed_submission).await?;
         api_error_or_ok(response).await.map_err(From::from)
     }
 }
@@ -2,71 +2,141 @@ use crate::{
     blinded_block_provider::{
         api::server::{
             handle_fetch_bid, handle_open_bid, handle_status_check, handle_validator_registration,
+            spawn_with_tls, with_common_layers,
         },
         BlindedBlockProvider,
     },
     blinded_block_relayer::{
         BlindedBlockDataProvider, BlindedBlockRelayer, BlockSubmissionFilter,
-        DeliveredPayloadFilter, ValidatorRegistrationQuery,
+        DeliveredPayloadFilter, LatePayloadRequestFilter, PayloadAttributesUpdate,
+        RegistrationStats, RelayStatus, ValidatorRegistrationQuery,
     },
+    caller::propagate_caller_ip,
+    config::{CorsConfig, ServerConfig},
     error::Error,
     types::{
-        block_submission::data_api::{PayloadTrace, SubmissionTrace},
-        ProposerSchedule, SignedBidSubmission, SignedValidatorRegistration,
+        block_submission::data_api::{LatePayloadRequestTrace, PayloadTrace, SubmissionTrace},
+        ProposerSchedule, SignedBidSubmission, SignedHeaderSubmission, SignedValidatorRegistration,
     },
 };
 use axum::{
-    extract::{Json, Query, State},
-    response::Html,
-    routing::{get, post, IntoMakeService},
-    Router,
+    body::{Body, Bytes},
+    extract::{
+        connect_info::IntoMakeServiceWithConnectInfo,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        ConnectInfo, DefaultBodyLimit, Json, Path, Query, State,
+    },
+    http::{
+        header::{AUTHORIZATION, CONTENT_TYPE},
+        HeaderMap, HeaderValue, Method, Request, StatusCode,
+    },
+    middleware::{self, Next},
+    response::{Html, IntoResponse, Response},
+    routing::{get, post, MethodRouter},
+    Extension, Router,
 };
+use beacon_api_client::ApiError;
+use ethereum_consensus::{primitives::Slot, state_transition::Context};
+use futures::{future, stream::BoxStream, SinkExt, StreamExt};
 use hyper::server::conn::AddrIncoming;
-use std::net::{Ipv4Addr, SocketAddr};
+use ipnet::IpNet;
+use std::{
+    collections::HashSet,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
 use tokio::task::JoinHandle;
-use tracing::{error, info, trace};
+use tower_http::{
+    cors::{AllowOrigin, Any, CorsLayer},
+    decompression::{DecompressionBody, RequestDecompressionLayer},
+    timeout::TimeoutLayer,
+};
+use tracing::{error, info, trace, warn};
 
 /// Type alias for the configured axum server
-pub type BlockRelayServer = axum::Server<AddrIncoming, IntoMakeService<Router>>;
+pub type BlockRelayServer =
+    axum::Server<AddrIncoming, IntoMakeServiceWithConnectInfo<Router, SocketAddr>>;
 
-// cribbed from: https://github.com/tbjgolden/simple-json-viewer
+// Renders the most recent data from the data APIs, a page at a time.
 const ROOT_HTML_TRAILER: &str = r#"
-<script>
-(function(a){function b(d){if(c[d])return c[d].exports;var e=c[d]={i:d,l:!1,exports:{}};return a[d].call(e.exports,e,e.exports,b),e.l=!0,e.exports}var c={};return b.m=a,b.c=c,b.d=function(a,c,d){b.o(a,c)||Object.defineProperty(a,c,{configurable:!1,enumerable:!0,get:d})},b.n=function(a){var c=a&&a.__esModule?function(){return a['default']}:function(){return a};return b.d(c,'a',c),c},b.o=function(a,b){return Object.prototype.hasOwnProperty.call(a,b)},b.p='',b(b.s=0)})([function(){'use strict';function a(a){if(Array.isArray(a)){for(var b=0,c=Array(a.length);b<a.length;b++)c[b]=a[b];return c}return Array.from(a)}function b(a,b){if(!(a instanceof b))throw new TypeError('Cannot call a class as a function')}var c=function(){function a(a,b){var c,d=[],e=!0,f=!1;try{for(var g,h=a[Symbol.iterator]();!(e=(g=h.next()).done)&&(d.push(g.value),!(b&&d.length===b));e=!0);}catch(a){f=!0,c=a}finally{try{!e&&h['return']&&h['return']()}finally{if(f)throw c}}return d}return function(b,c){if(Array.isArray(b))return b;if(Symbol.iterator in Object(b))return a(b,c);throw new TypeError('Invalid attempt to destructure non-iterable instance')}}(),d=function(){function a(a,b){for(var c,d=0;d<b.length;d++)c=b[d],c.enumerable=c.enumerable||!1,c.configurable=!0,'value'in c&&(c.writable=!0),Object.defineProperty(a,c.key,c)}return function(b,c,d){return c&&a(b.prototype,c),d&&a(b,d),b}}(),e=Object.assign||function(a){for(var b,c=1;c<arguments.length;c++)for(var d in b=arguments[c],b)Object.prototype.hasOwnProperty.call(b,d)&&(a[d]=b[d]);return a},f='function'==typeof Symbol&&'symbol'==typeof Symbol.iterator?function(a){return typeof a}:function(a){return a&&'function'==typeof Symbol&&a.constructor===Symbol&&a!==Symbol.prototype?'symbol':typeof a};(function(){function g(b){var c=1<arguments.length&&void 0!==arguments[1]?arguments[1]:[];return[].concat(a(b.childNodes||[])).forEach(function(a){[1,3].includes(a.nodeType)&&(c.push(a),1===a.nodeType&&g(a,c))}),c}function h(a){do if(a=a.parentNode,!a||a.classList.contains('json-viewer'))return;while(!a.classList.contains('json-viewer-collection'));return a}function i(a,b){if(a instanceof RegExp)return a.test(b);if(!a||!b)return!1;a=a.toLowerCase(),b=b.toLowerCase();for(var c=0,d=0;;){if(c===a.length)return!0;if(d===b.length)return!1;a.charCodeAt(c)===b.charCodeAt(d)&&c++,d++}}function j(a,b){var c=document.createElement('STYLE');c.innerHTML=''.replace(/\[timestamp\]/g,'.'+a).replace(/([0-9])vmax/g,function(a,c){return b.colors[~~c]}).replace('monospace',b.fontFamily),document.head.appendChild(c)}function k(a){return a=JSON.stringify(a),a.substring(1,a.length-1)}function l(a){var b='undefined'==typeof a?'undefined':f(a);return'object'===b?Array.isArray(a)?'array':'object':b}function m(a){return'object'===('undefined'==typeof HTMLElement?'undefined':f(HTMLElement))?a instanceof HTMLElement:a&&'object'===('undefined'==typeof a?'undefined':f(a))&&null!==a&&1===a.nodeType&&'string'==typeof a.nodeName}var n={colors:['#999','#090','#c00','#c0c','#00c','#ccc','#333','#ff0','#eee'],fontFamily:'monospace'};document.addEventListener('DOMContentLoaded',function(){var a=document.createElement('STYLE');a.innerHTML='.json-viewer-container{display:flex;flex-direction:column}.json-viewer-container *{white-space:pre;font-family:monospace;font-size:14px;line-height:16px;letter-spacing:0;box-sizing:border-box}.json-viewer-search{display:block;border:1px solid;border-bottom-width:0;padding:2.5ex;z-index:2}.json-viewer{display:block;border:1px solid;padding:2.5ex;user-select:none;overflow:auto;z-index:1}.json-viewer-object:before{content:"{"}.json-viewer-object:after{content:"}"}.json-viewer-array:before{content:"["}.json-viewer-array:after{content:"]"}.json-viewer-string:before,.json-viewer-string:after{content:\'"\'}.json-viewer-object-value:before{content:": "}.json-viewer-array-value:not(:last-child):after,.json-viewer-key-value-pair:not(:last-child):after{content:",\\A"}.json-viewer-array-contents,.json-viewer-object-contents{display:block;padding-left:5ex}.json-viewer-toggle{border:0;padding:0 .4ex;margin:0;outline:0;background:none}.json-viewer-object .json-viewer-toggle:before,.json-viewer-array .json-viewer-toggle:before{content:"><";font-size:1.8ex}.json-viewer-object .json-viewer-toggle:hover:before,.json-viewer-array .json-viewer-toggle:hover:before{cursor:pointer}.json-viewer-object.json-viewer-closed .json-viewer-toggle:before,.json-viewer-array.json-viewer-closed .json-viewer-toggle:before{content:"<>"}.json-viewer-object.json-viewer-closed .json-viewer-object-contents,.json-viewer-array.json-viewer-closed .json-viewer-array-contents{display:none}.json-viewer-array-contents{counter-reset:index -1}.json-viewer-array-value{counter-increment:index;position:relative}.json-viewer-searching .json-viewer-array-value:before,.json-viewer-array-value:hover:before{content:counter(index);position:absolute;display:inline-block;top:.1ex;left:0;transform:translate3d(calc(-100% - .5ex), 0, 0)}.json-viewer :before,.json-viewer :after{font:inherit;line-height:inherit}',document.head.appendChild(a)}),window.createJSONViewer=function(a){var b=1<arguments.length&&void 0!==arguments[1]?arguments[1]:{},c=2<arguments.length&&void 0!==arguments[2]?arguments[2]:{};if(!m(a))throw Error('createJSONViewer must be called with a HTML element');else if(!['string','object'].includes('undefined'==typeof b?'undefined':f(b)))throw Error('json parameter passed to createJSONViewer is not a string or object');else if('object'!==('undefined'==typeof c?'undefined':f(c)))throw Error('options parameter passed to createJSONViewer is not an object');return'string'!=typeof b&&(b=JSON.stringify(b)),new o(a,b,e({},n,c))};var o=function(){function e(a,c,d){b(this,e);var f=document.createElement('DIV');f.classList.add('json-viewer');var g=document.createElement('INPUT');g.setAttribute('placeholder','Search'),g.classList.add('json-viewer-search'),g.addEventListener('keyup',this.searchInputKeyUp.bind(this)),a.classList.add('json-viewer-container'),this.container=a,this.el=f,this.searchEl=g,this.searchValue='',this.json=c,this.opts=d,this.values=[],this.container.appendChild(g),this.container.appendChild(f),this.uid='time-'+Date.now(),a.classList.add(this.uid),j(this.uid,this.opts),this.generate()}return d(e,[{key:'generate',value:function(){var a=this;this.values=[];var b=this.htmlify(JSON.parse(this.json))||'';this.el.innerHTML=b,this.el.querySelectorAll('[data-json-id]').forEach(function(b){b._value=a.values[b.getAttribute('data-json-id')]}),this.attachListeners(),this.allNodes=g(this.el)}},{key:'getJSON',value:function(){return JSON.parse(this.json)}},{key:'changeJSON',value:function(){var a=0<arguments.length&&void 0!==arguments[0]?arguments[0]:{},b=1<arguments.length&&void 0!==arguments[1]?arguments[1]:[];'string'==typeof a&&(a=JSON.parse(a));for(var c=this.getJSON(),d=c;1<b.length;)d=d[b.shift()];b.length?(d[b[0]]=a,this.json=JSON.stringify(c)):this.json=JSON.stringify(a),this.generate(),this.searchValue=null,this.searchInputKeyUp()}},{key:'searchInputKeyUp',value:function(){var a=this;clearTimeout(this.debounce),this.debounce=setTimeout(function(){var b=a.searchEl.value,c=a.searchValue;b!==c&&(b?(a.el.classList.add('json-viewer-searching'),a.search(b)):(a.el.classList.remove('json-viewer-searching'),a.allNodes.forEach(function(a){return 1===a.nodeType?a.classList.contains('json-viewer-collection')&&a.classList.remove('json-viewer-closed'):void a.parentNode.classList.remove('json-viewer-match')}))),a.searchValue=b},200)}},{key:'search',value:function(a){var b=null;try{if(!/\/[^/]*\/[\s\S]*/.test(a))throw new Error;var d=function(a){return[a[0]?null:a.slice(1,a.length-1).join('/'),a[a.length-1]]}(a.split('/')),e=c(d,2),f=e[0],g=e[1];b=new RegExp(f,g)}catch(a){b=null}this.searchEl.style.color=b?this.opts.colors[4]:this.opts.colors[6],this.allNodes.forEach(function(c){if(1===c.nodeType)return c.classList.contains('json-viewer-collection')&&c.classList.add('json-viewer-closed');if(i(b||a,c.textContent)){c.parentNode.classList.add('json-viewer-match');for(var d=h(c);d;)d.classList.remove('json-viewer-closed'),d=h(d)}else c.parentNode.classList.remove('json-viewer-match')})}},{key:'htmlify',value:function(b){var c=this,d=1<arguments.length&&void 0!==arguments[1]?arguments[1]:[],e=l(b);return'number'===e||'boolean'===e?'<span class="json-viewer-'+e+'">'+b+'</span>':'string'===e?'<span class="json-viewer-string">'+k(b)+'</span>':'array'===e?'<span class="json-viewer-array json-viewer-collection"><button class="json-viewer-toggle"></button><span class="json-viewer-array-contents">'+b.map(function(b,e){return c.values.push(b),'<span class="json-viewer-array-value" data-index="'+e+'" data-json-id="'+(c.values.length-1)+'" data-location="'+encodeURIComponent(JSON.stringify(d))+'">'+c.htmlify(b,[].concat(a(d),[e]))+'</span>'}).join('')+'</span></span>':'object'===e?'<span class="json-viewer-object json-viewer-collection"><button class="json-viewer-toggle"></button><span class="json-viewer-object-contents">'+Object.keys(b).map(function(e){return c.values.push(e),c.values.push(b[e]),'<span class="json-viewer-key-value-pair" data-location="'+encodeURIComponent(JSON.stringify(d))+'"><span class="json-viewer-object-key" data-json-id="'+(c.values.length-2)+'">'+k(e)+'</span><span class="json-viewer-object-value" data-json-id="'+(c.values.length-1)+'">'+c.htmlify(b[e],[].concat(a(d),[e]))+'</span></span>'}).join('')+'</span></span>':void 0}},{key:'attachListeners',value:function(){var b=this;this.el.querySelectorAll('.json-viewer-toggle').forEach(function(a){a.addEventListener('click',function(a){a.target.parentNode.classList.toggle('json-viewer-closed')})}),this.el.querySelectorAll('.json-viewer-key-value-pair').forEach(function(c){c.addEventListener('click',function(c){if(c.stopPropagation(),b.opts.onValueClick){for(var d=c.target;!d.classList.contains('json-viewer-key-value-pair');)d=d.parentNode;b.opts.onValueClick(d.querySelector('.json-viewer-object-value')._value,[].concat(a(JSON.parse(decodeURIComponent(d.getAttribute('data-location')))),[d.querySelector('.json-viewer-object-key')._value]),d)}})}),this.el.querySelectorAll('.json-viewer-array-value').forEach(function(c){c.addEventListener('click',function(c){if(c.stopPropagation(),b.opts.onValueClick){for(var d=c.target;!d.classList.contains('json-viewer-array-value');)d=d.parentNode;b.opts.onValueClick(d._value,[].concat(a(JSON.parse(decodeURIComponent(d.getAttribute('data-location')))),[~~d.getAttribute('data-index')]),d)}})})}}]),e}()})()}]);
-</script>
-
-<div id="json-viewer-container"></div>
+<section>
+  <h2>delivered payloads</h2>
+  <table>
+    <thead><tr><th>slot</th><th>block number</th><th>block hash</th><th>value (wei)</th><th>builder</th></tr></thead>
+    <tbody id="proposer-payloads"></tbody>
+  </table>
+  <button id="newer-payloads">newest</button>
+  <button id="older-payloads">older</button>
+</section>
+<section>
+  <h2>recent block submissions</h2>
+  <table>
+    <thead><tr><th>slot</th><th>block number</th><th>block hash</th><th>value (wei)</th><th>builder</th></tr></thead>
+    <tbody id="builder-submissions"></tbody>
+  </table>
 </section>
 </main>
 </body>
 
 <script>
-  var container = document.querySelector('#json-viewer-container');
+  const PAGE_SIZE = 25;
+  const payloadsBase = "/relay/v1/data/bidtraces/proposer_payload_delivered";
+  const submissionsBase = "/relay/v1/data/bidtraces/builder_blocks_received";
+  // `null` shows the most recent page, which is then refreshed periodically
+  let payloadsCursor = null;
+  let oldestPayloadSlot = null;
 
-  loadData();
+  function renderRows(id, traces) {
+    const body = document.getElementById(id);
+    body.replaceChildren(...traces.map((trace) => {
+      const row = document.createElement("tr");
+      for (const value of [trace.slot, trace.block_number, trace.block_hash, trace.value, trace.builder_pubkey]) {
+        const cell = document.createElement("td");
+        cell.textContent = value;
+        row.appendChild(cell);
+      }
+      return row;
+    }));
+  }
 
-  // refresh every 12 seconds
-  setInterval(loadData, 12000);
-
-  function loadData() {
-    const proposer_payloads = fetch("/relay/v1/data/bidtraces/proposer_payload_delivered").then(response => response.json());
-
-    const builder_submissions = fetch("/relay/v1/data/bidtraces/builder_blocks_received").then(response => response.json());
-
-    Promise.all([proposer_payloads, builder_submissions]).then(([proposer_payloads, builder_submissions]) => {
-      const data = {
-          "proposer_payloads_delivered" : proposer_payloads,
-          "builder_blocks_received": builder_submissions,
-      };
-      var options = {
-        fontFamily: '"Fira Mono", monospace',
-        colors: ['gray', '#090', '#c00', 'purple', '#00c', '#ccc', '#333', 'yellow', 'rgb(240,240,240)']
-      };
-
-      container.innerHTML = "";
-      var viewer = createJSONViewer(container, data, options);
-    })
+  function loadPayloads() {
+    let url = `${payloadsBase}?limit=${PAGE_SIZE}`;
+    if (payloadsCursor !== null) {
+      url += `&cursor=${payloadsCursor}`;
+    }
+    fetch(url).then(response => response.json()).then(traces => {
+      renderRows("proposer-payloads", traces);
+      oldestPayloadSlot = traces.length > 0 ? Number(traces[traces.length - 1].slot) : null;
+    });
+  }
+
+  function loadSubmissions() {
+    fetch(`${submissionsBase}?limit=${PAGE_SIZE}`)
+      .then(response => response.json())
+      .then(traces => renderRows("builder-submissions", traces));
   }
+
+  document.getElementById("older-payloads").onclick = () => {
+    if (oldestPayloadSlot !== null && oldestPayloadSlot > 0) {
+      payloadsCursor = oldestPayloadSlot - 1;
+      loadPayloads();
+    }
+  };
+  document.getElementById("newer-payloads").onclick = () => {
+    payloadsCursor = null;
+    loadPayloads();
+  };
+
+  loadPayloads();
+  loadSubmissions();
+
+  // refresh every 12 seconds
+  setInterval(() => {
+    if (payloadsCursor === null) {
+      loadPayloads();
+    }
+    loadSubmissions();
+  }, 12000);
 </script>
 </html>
 "#;
@@ -93,7 +163,6 @@ async fn handle_get_root<R: BlindedBlockDataProvider>(
           <p>relay public key: {0:?} </p>
           <p>registered validators: {1}</p>
           </section>
-          <section>
     "#,
         relay.public_key(),
         relay.registered_validators_count(),
@@ -102,6 +171,13 @@ async fn handle_get_root<R: BlindedBlockDataProvider>(
     Ok(Html(response))
 }
 
+async fn handle_get_status<R: BlindedBlockDataProvider>(
+    State(relay): State<R>,
+) -> Result<Json<RelayStatus>, Error> {
+    trace!("serving status");
+    Ok(Json(relay.get_status().await?))
+}
+
 async fn handle_get_proposal_schedule<R: BlindedBlockRelayer>(
     State(relay): State<R>,
 ) -> Result<Json<Vec<ProposerSchedule>>, Error> {
@@ -109,12 +185,142 @@ async fn handle_get_proposal_schedule<R: BlindedBlockRelayer>(
     Ok(Json(relay.get_proposal_schedule().await?))
 }
 
+async fn handle_get_proposer_schedule<R: BlindedBlockRelayer>(
+    State(relay): State<R>,
+    Path(slot): Path<Slot>,
+) -> Result<Response, Error> {
+    trace!(slot, "serving proposer schedule");
+    let response = match relay.get_proposer_schedule(slot).await? {
+        Some(schedule) => Json(schedule).into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
+    };
+    Ok(response)
+}
+
+async fn handle_subscribe_payload_attributes<R: BlindedBlockRelayer>(
+    State(relay): State<R>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let Some(updates) = relay.subscribe_payload_attributes() else {
+        return StatusCode::NOT_FOUND.into_response()
+    };
+    trace!("builder subscribed to payload attributes");
+    upgrade.on_upgrade(|socket| push_payload_attributes(socket, updates))
+}
+
+// Forwards `updates` to the builder connected on `socket` until either side closes
+async fn push_payload_attributes(
+    socket: WebSocket,
+    mut updates: BoxStream<'static, Arc<PayloadAttributesUpdate>>,
+) {
+    let (mut sender, receiver) = socket.split();
+    // NOTE: messages from the builder are only read to notice when it disconnects
+    let disconnected = receiver
+        .take_while(|message| {
+            future::ready(matches!(message, Ok(msg) if !matches!(msg, Message::Close(_))))
+        })
+        .for_each(|_| future::ready(()));
+    let forward = async move {
+        while let Some(update) = updates.next().await {
+            let message = match serde_json::to_string(update.as_ref()) {
+                Ok(message) => message,
+                Err(err) => {
+                    warn!(%err, "could not encode payload attributes");
+                    continue
+                }
+            };
+            if let Err(err) = sender.send(Message::Text(message)).await {
+                trace!(%err, "could not push payload attributes to builder");
+                return
+            }
+        }
+    };
+    futures::pin_mut!(disconnected, forward);
+    future::select(disconnected, forward).await;
+    trace!("builder unsubscribed from payload attributes");
+}
+
+// Media type of SSZ-encoded request bodies.
+const SSZ_CONTENT_TYPE: &str = "application/octet-stream";
+
+fn is_ssz_request(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with(SSZ_CONTENT_TYPE))
+        .unwrap_or(false)
+}
+
+fn unsupported_media_type() -> Response {
+    let code = StatusCode::UNSUPPORTED_MEDIA_TYPE;
+    let message = format!("block submissions must be SSZ-encoded with `{SSZ_CONTENT_TYPE}`");
+    (code, Json(ApiError::ErrorMessage { code, message })).into_response()
+}
+
+/// Accepts block submissions encoded as JSON or, with `Content-Type: application/octet-stream`,
+/// as SSZ, either optionally compressed with `Content-Encoding: gzip`
+#[tracing::instrument(skip_all)]
 async fn handle_submit_bid<R: BlindedBlockRelayer>(
     State(relay): State<R>,
-    Json(signed_bid_submission): Json<SignedBidSubmission>,
-) -> Result<(), Error> {
+    Extension(context): Extension<Arc<Context>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
     trace!("handling bid submission");
-    relay.submit_bid(&signed_bid_submission).await
+    let signed_bid_submission = if is_ssz_request(&headers) {
+        match SignedBidSubmission::deserialize_for_slot(&body, &context) {
+            Ok(signed_bid_submission) => signed_bid_submission,
+            Err(err) => return err.into_response(),
+        }
+    } else {
+        match Json::<SignedBidSubmission>::from_bytes(&body) {
+            Ok(Json(signed_bid_submission)) => signed_bid_submission,
+            Err(rejection) => return rejection.into_response(),
+        }
+    };
+    relay.submit_bid(&signed_bid_submission).await.into_response()
+}
+
+/// Accepts only SSZ-encoded block submissions, skipping the JSON decoding paths entirely
+#[tracing::instrument(skip_all)]
+async fn handle_submit_bid_ssz<R: BlindedBlockRelayer>(
+    State(relay): State<R>,
+    Extension(context): Extension<Arc<Context>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    trace!("handling SSZ bid submission");
+    if !is_ssz_request(&headers) {
+        return unsupported_media_type()
+    }
+    match SignedBidSubmission::deserialize_for_slot(&body, &context) {
+        Ok(signed_bid_submission) => relay.submit_bid(&signed_bid_submission).await.into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Accepts header submissions encoded as JSON or, with `Content-Type: application/octet-stream`,
+/// as SSZ
+#[tracing::instrument(skip_all)]
+async fn handle_submit_header<R: BlindedBlockRelayer>(
+    State(relay): State<R>,
+    Extension(context): Extension<Arc<Context>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    trace!("handling header submission");
+    let signed_header_submission = if is_ssz_request(&headers) {
+        match SignedHeaderSubmission::deserialize_for_slot(&body, &context) {
+            Ok(signed_header_submission) => signed_header_submission,
+            Err(err) => return err.into_response(),
+        }
+    } else {
+        match Json::<SignedHeaderSubmission>::from_bytes(&body) {
+            Ok(Json(signed_header_submission)) => signed_header_submission,
+            Err(rejection) => return rejection.into_response(),
+        }
+    };
+    relay.submit_header(&signed_header_submission).await.into_response()
 }
 
 async fn handle_get_proposer_payloads_delivered<R: BlindedBlockDataProvider>(
@@ -133,6 +339,14 @@ async fn handle_get_builder_blocks_received<R: BlindedBlockDataProvider>(
     Ok(Json(relay.get_block_submissions(&filters).await?))
 }
 
+async fn handle_get_late_payload_requests<R: BlindedBlockDataProvider>(
+    State(relay): State<R>,
+    Query(filters): Query<LatePayloadRequestFilter>,
+) -> Result<Json<Vec<LatePayloadRequestTrace>>, Error> {
+    trace!("handling late payload requests");
+    Ok(Json(relay.get_late_payload_requests(&filters).await?))
+}
+
 async fn handle_get_validator_registration<R: BlindedBlockDataProvider>(
     State(relay): State<R>,
     Query(params): Query<ValidatorRegistrationQuery>,
@@ -141,10 +355,93 @@ async fn handle_get_validator_registration<R: BlindedBlockDataProvider>(
     Ok(Json(relay.fetch_validator_registration(&params.public_key).await?))
 }
 
+async fn handle_get_registration_stats<R: BlindedBlockDataProvider>(
+    State(relay): State<R>,
+) -> Result<Json<RegistrationStats>, Error> {
+    trace!("serving validator registration stats");
+    Ok(Json(relay.get_registration_stats().await?))
+}
+
+fn cors_layer(config: &CorsConfig) -> Option<CorsLayer> {
+    if config.allowed_origins.is_empty() {
+        return None
+    }
+
+    let allowed_origins = if config.allowed_origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::from(Any)
+    } else {
+        let origins = config.allowed_origins.iter().filter_map(|origin| {
+            HeaderValue::from_str(origin)
+                .map_err(|err| warn!(%err, origin, "skipping invalid CORS origin"))
+                .ok()
+        });
+        AllowOrigin::list(origins)
+    };
+    let allowed_methods = config
+        .allowed_methods
+        .iter()
+        .filter_map(|method| {
+            method
+                .parse::<Method>()
+                .map_err(|err| warn!(%err, method, "skipping invalid CORS method"))
+                .ok()
+        })
+        .collect::<Vec<_>>();
+
+    Some(CorsLayer::new().allow_origin(allowed_origins).allow_methods(allowed_methods))
+}
+
+async fn require_auth_token(
+    State(tokens): State<Arc<HashSet<String>>>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Result<Response, StatusCode> {
+    let token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match token {
+        Some(token) if tokens.contains(token) => Ok(next.run(request).await),
+        _ => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+fn parse_ip_allowlist(entries: &[String]) -> Vec<IpNet> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            entry
+                .parse::<IpNet>()
+                .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                .map_err(|err| warn!(%err, entry, "skipping invalid IP allowlist entry"))
+                .ok()
+        })
+        .collect()
+}
+
+async fn require_allowed_ip(
+    State(allowlist): State<Arc<Vec<IpNet>>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Result<Response, StatusCode> {
+    let ip = addr.ip().to_canonical();
+    if allowlist.iter().any(|network| network.contains(&ip)) {
+        Ok(next.run(request).await)
+    } else {
+        trace!(%ip, "rejecting request from address not in allowlist");
+        Err(StatusCode::FORBIDDEN)
+    }
+}
+
 pub struct Server<R> {
-    host: Ipv4Addr,
+    host: IpAddr,
     port: u16,
+    config: ServerConfig,
     relay: R,
+    // used to find the fork of SSZ-encoded submissions
+    context: Arc<Context>,
 }
 
 impl<
@@ -157,23 +454,41 @@ impl<
             + 'static,
     > Server<R>
 {
-    pub fn new(host: Ipv4Addr, port: u16, relay: R) -> Self {
-        Self { host, port, relay }
+    pub fn new(
+        host: IpAddr,
+        port: u16,
+        config: ServerConfig,
+        relay: R,
+        context: Arc<Context>,
+    ) -> Self {
+        Self { host, port, config, relay, context }
     }
 
-    /// Configures and returns the axum server
-    pub fn serve(&self) -> BlockRelayServer {
-        let router = Router::new()
-            .route("/", get(handle_get_root::<R>))
-            .route("/eth/v1/builder/status", get(handle_status_check))
-            .route("/eth/v1/builder/validators", post(handle_validator_registration::<R>))
-            .route(
-                "/eth/v1/builder/header/:slot/:parent_hash/:public_key",
-                get(handle_fetch_bid::<R>),
-            )
-            .route("/eth/v1/builder/blinded_blocks", post(handle_open_bid::<R>))
-            .route("/relay/v1/builder/validators", get(handle_get_proposal_schedule::<R>))
-            .route("/relay/v1/builder/blocks", post(handle_submit_bid::<R>))
+    fn router(&self) -> Router {
+        let submit_bid: MethodRouter<R, DecompressionBody<Body>> =
+            if self.config.ssz_only_submissions {
+                post(handle_submit_bid_ssz::<R>)
+            } else {
+                post(handle_submit_bid::<R>)
+            };
+        // NOTE: the body limit applies to the decompressed submission
+        let mut submit_bid: MethodRouter<R> = submit_bid
+            .layer(DefaultBodyLimit::max(self.config.max_submission_body_size_bytes))
+            .layer(TimeoutLayer::new(self.config.submission_timeout()))
+            .layer(RequestDecompressionLayer::new())
+            .layer(Extension(self.context.clone()));
+        let mut submit_header: MethodRouter<R> = post(handle_submit_header::<R>)
+            .layer(TimeoutLayer::new(self.config.submission_timeout()))
+            .layer(Extension(self.context.clone()));
+        if !self.config.submission_ip_allowlist.is_empty() {
+            let allowlist = Arc::new(parse_ip_allowlist(&self.config.submission_ip_allowlist));
+            submit_bid = submit_bid
+                .layer(middleware::from_fn_with_state(allowlist.clone(), require_allowed_ip));
+            submit_header =
+                submit_header.layer(middleware::from_fn_with_state(allowlist, require_allowed_ip));
+        }
+
+        let mut data_router: Router<R> = Router::new()
             .route(
                 "/relay/v1/data/bidtraces/proposer_payload_delivered",
                 get(handle_get_proposer_payloads_delivered::<R>),
@@ -182,17 +497,70 @@ impl<
                 "/relay/v1/data/bidtraces/builder_blocks_received",
                 get(handle_get_builder_blocks_received::<R>),
             )
+            .route(
+                "/relay/v1/data/late_payload_requests",
+                get(handle_get_late_payload_requests::<R>),
+            )
             .route(
                 "/relay/v1/data/validator_registration",
                 get(handle_get_validator_registration::<R>),
             )
+            .route(
+                "/relay/v1/data/validator_registrations/stats",
+                get(handle_get_registration_stats::<R>),
+            );
+        if let Some(cors) = cors_layer(&self.config.cors) {
+            data_router = data_router.layer(cors);
+        }
+
+        let mut builder_router: Router<R> = Router::new()
+            .route("/relay/v1/builder/validators", get(handle_get_proposal_schedule::<R>))
+            .route("/relay/v1/builder/validators/:slot", get(handle_get_proposer_schedule::<R>))
+            .route("/relay/v1/builder/blocks", submit_bid)
+            .route("/relay/v1/builder/headers", submit_header);
+        if !self.config.builder_auth_tokens.is_empty() {
+            // NOTE: payload attributes are only pushed to authenticated builders
+            let tokens = Arc::new(HashSet::from_iter(self.config.builder_auth_tokens.clone()));
+            builder_router = builder_router
+                .route(
+                    "/relay/v1/builder/payload_attributes",
+                    get(handle_subscribe_payload_attributes::<R>),
+                )
+                .route_layer(middleware::from_fn_with_state(tokens, require_auth_token));
+        }
+
+        let router = Router::new()
+            .route("/", get(handle_get_root::<R>))
+            .route("/relay/v1/status", get(handle_get_status::<R>))
+            .route("/eth/v1/builder/status", get(handle_status_check::<R>))
+            .route("/eth/v1/builder/validators", post(handle_validator_registration::<R>))
+            .route(
+                "/eth/v1/builder/header/:slot/:parent_hash/:public_key",
+                get(handle_fetch_bid::<R>),
+            )
+            .route("/eth/v1/builder/blinded_blocks", post(handle_open_bid::<R>))
+            .merge(builder_router)
+            .merge(data_router)
             .with_state(self.relay.clone());
+        with_common_layers(router, &self.config).layer(middleware::from_fn(propagate_caller_ip))
+    }
+
+    /// Configures and returns the axum server
+    pub fn serve(&self) -> BlockRelayServer {
         let addr = SocketAddr::from((self.host, self.port));
-        axum::Server::bind(&addr).serve(router.into_make_service())
+        axum::Server::bind(&addr)
+            .serve(self.router().into_make_service_with_connect_info::<SocketAddr>())
     }
 
     /// Spawns the server on a new task returning the handle for it
+    ///
+    /// Serves over HTTPS if TLS is configured
     pub fn spawn(&self) -> JoinHandle<()> {
+        if let Some(tls) = self.config.tls.clone() {
+            let addr = SocketAddr::from((self.host, self.port));
+            return spawn_with_tls(self.router(), addr, tls)
+        }
+
         let server = self.serve();
         let addr = server.local_addr();
         tokio::spawn(async move {
@@ -7,21 +7,41 @@ pub use {api::client::Client, api::server::Server};
 use crate::{
     error::Error,
     types::{
-        block_submission::data_api::{PayloadTrace, SubmissionTrace},
-        ProposerSchedule, SignedBidSubmission, SignedValidatorRegistration,
+        block_submission::data_api::{LatePayloadRequestTrace, PayloadTrace, SubmissionTrace},
+        ProposerSchedule, SignedBidSubmission, SignedHeaderSubmission, SignedValidatorRegistration,
     },
 };
 use async_trait::async_trait;
-use ethereum_consensus::primitives::{BlsPublicKey, Bytes32, Slot};
+use beacon_api_client::PayloadAttributesEvent;
+use ethereum_consensus::primitives::{BlsPublicKey, Bytes32, Root, Slot};
+use futures::stream::BoxStream;
+use std::{collections::BTreeMap, sync::Arc};
 
 #[async_trait]
 pub trait BlindedBlockRelayer {
     async fn get_proposal_schedule(&self) -> Result<Vec<ProposerSchedule>, Error>;
 
+    /// Returns the schedule of the proposer for `slot`, if it is registered
+    async fn get_proposer_schedule(&self, slot: Slot) -> Result<Option<ProposerSchedule>, Error> {
+        let schedules = self.get_proposal_schedule().await?;
+        Ok(schedules.into_iter().find(|schedule| schedule.slot == slot))
+    }
+
     async fn submit_bid(&self, signed_submission: &SignedBidSubmission) -> Result<(), Error>;
+
+    /// Submits a bid without its execution payload, which must follow with `submit_bid`
+    async fn submit_header(&self, signed_submission: &SignedHeaderSubmission) -> Result<(), Error>;
+
+    /// Subscribes to the payload attributes of upcoming proposals by registered validators,
+    /// returning `None` if they are not pushed to builders
+    fn subscribe_payload_attributes(
+        &self,
+    ) -> Option<BoxStream<'static, Arc<PayloadAttributesUpdate>>> {
+        None
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct DeliveredPayloadFilter {
     pub slot: Option<Slot>,
@@ -31,9 +51,13 @@ pub struct DeliveredPayloadFilter {
     pub proposer_public_key: Option<BlsPublicKey>,
     #[serde(rename = "builder_pubkey")]
     pub builder_public_key: Option<BlsPublicKey>,
+    /// Only return payloads delivered at or before this slot
+    pub cursor: Option<Slot>,
+    /// Return at most this many payloads
+    pub limit: Option<usize>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct BlockSubmissionFilter {
     pub slot: Option<Slot>,
@@ -41,6 +65,20 @@ pub struct BlockSubmissionFilter {
     pub block_number: Option<usize>,
     #[serde(rename = "builder_pubkey")]
     pub builder_public_key: Option<BlsPublicKey>,
+    /// Return at most this many submissions
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct LatePayloadRequestFilter {
+    pub slot: Option<Slot>,
+    #[serde(rename = "proposer_pubkey")]
+    pub proposer_public_key: Option<BlsPublicKey>,
+    /// Only return requests at or before this slot
+    pub cursor: Option<Slot>,
+    /// Return at most this many requests
+    pub limit: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -50,6 +88,53 @@ pub struct ValidatorRegistrationQuery {
     pub public_key: BlsPublicKey,
 }
 
+/// Sync status of the beacon node backing the relay
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BeaconNodeStatus {
+    pub head_slot: Slot,
+    pub sync_distance: usize,
+    pub is_syncing: bool,
+}
+
+/// Identity and state of the relay, served from `/relay/v1/status`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RelayStatus {
+    #[serde(rename = "pubkey")]
+    pub public_key: BlsPublicKey,
+    pub network: String,
+    pub genesis_validators_root: Root,
+    /// As of the relay's latest slot, or `None` if the beacon node could not be reached
+    pub beacon_node: Option<BeaconNodeStatus>,
+    pub open_auctions: usize,
+    pub registered_validators: usize,
+}
+
+/// Payload attributes of an upcoming proposal, pushed to builders subscribed to
+/// `/relay/v1/builder/payload_attributes`
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PayloadAttributesUpdate {
+    #[serde(flatten)]
+    pub payload_attributes: PayloadAttributesEvent,
+    /// Latest registration of the proposer, carrying its fee recipient and gas limit
+    pub proposer_registration: SignedValidatorRegistration,
+}
+
+/// Summary of the validator registrations known to the relay, served from
+/// `/relay/v1/data/validator_registrations/stats`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegistrationStats {
+    pub total: usize,
+    /// Validators that registered for the first time in the last full epoch
+    pub new_in_last_epoch: usize,
+    pub distinct_fee_recipients: usize,
+    /// Number of validators registered with each gas limit
+    pub gas_limits: BTreeMap<u64, usize>,
+}
+
 #[async_trait]
 pub trait BlindedBlockDataProvider {
     fn public_key(&self) -> &BlsPublicKey;
@@ -66,8 +151,18 @@ pub trait BlindedBlockDataProvider {
         filters: &BlockSubmissionFilter,
     ) -> Result<Vec<SubmissionTrace>, Error>;
 
+    /// Returns the signed blinded beacon blocks refused for arriving too late in their slot
+    async fn get_late_payload_requests(
+        &self,
+        filters: &LatePayloadRequestFilter,
+    ) -> Result<Vec<LatePayloadRequestTrace>, Error>;
+
     async fn fetch_validator_registration(
         &self,
         public_key: &BlsPublicKey,
     ) -> Result<SignedValidatorRegistration, Error>;
+
+    async fn get_status(&self) -> Result<RelayStatus, Error>;
+
+    async fn get_registration_stats(&self) -> Result<RegistrationStats, Error>;
 }
@@ -0,0 +1,25 @@
+use axum::{extract::ConnectInfo, http::Request, middleware::Next, response::Response};
+use std::net::{IpAddr, SocketAddr};
+
+tokio::task_local! {
+    static CALLER_IP: IpAddr;
+}
+
+/// Returns the IP address of the caller of the request currently being served by this task, if
+/// known
+pub fn current_ip() -> Option<IpAddr> {
+    CALLER_IP.try_with(|ip| *ip).ok()
+}
+
+/// Middleware that makes the IP address of the connected caller available to the handler, for
+/// servers serving with connect info
+pub(crate) async fn propagate_caller_ip<B>(request: Request<B>, next: Next<B>) -> Response {
+    let ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_canonical());
+    match ip {
+        Some(ip) => CALLER_IP.scope(ip, next.run(request)).await,
+        None => next.run(request).await,
+    }
+}
@@ -1,12 +1,36 @@
-use std::{io, path::Path};
+use figment::{
+    providers::{Env, Format, Json, Toml, Yaml},
+    Figment,
+};
+use serde::Deserialize;
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr},
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use thiserror::Error;
 
+// Default size limits on request bodies, in bytes.
+const DEFAULT_MAX_BODY_SIZE_BYTES: usize = 4 * 1024 * 1024;
+const DEFAULT_MAX_SUBMISSION_BODY_SIZE_BYTES: usize = 16 * 1024 * 1024;
+// Default time limits to handle a request, in milliseconds.
+const DEFAULT_REQUEST_TIMEOUT_MS: u64 = 12_000;
+const DEFAULT_SUBMISSION_TIMEOUT_MS: u64 = 4_000;
+
+/// Prefix of the environment variables that override values from a configuration file
+pub const ENV_PREFIX: &str = "MEV_RS_";
+// Separates the keys of nested tables in the name of an environment variable.
+const ENV_KEY_SEPARATOR: &str = "__";
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
     Io(#[from] io::Error),
     #[error(transparent)]
     Toml(#[from] toml::de::Error),
+    #[error(transparent)]
+    Figment(#[from] Box<figment::Error>),
 }
 
 pub fn from_toml_file<P: AsRef<Path>, T: serde::de::DeserializeOwned>(path: P) -> Result<T, Error> {
@@ -14,3 +38,172 @@ pub fn from_toml_file<P: AsRef<Path>, T: serde::de::DeserializeOwned>(path: P) -
 
     toml::from_str(&config_data).map_err(From::from)
 }
+
+/// Loads the configuration file at `path` with any value overridden by a `MEV_RS_*` environment
+/// variable
+///
+/// The file is read as YAML if its extension is `.yaml` or `.yml`, as JSON if it is `.json` and
+/// as TOML otherwise. Nested keys are separated by `__`, e.g. `MEV_RS_BOOST__PORT=18551` overrides
+/// `port` in the `[boost]` table and `MEV_RS_RELAY__ADMIN__AUTH_TOKEN` overrides `auth_token` in
+/// `[relay.admin]`.
+pub fn from_file_with_env<P: AsRef<Path>, T: serde::de::DeserializeOwned>(
+    path: P,
+) -> Result<T, Error> {
+    let path = path.as_ref();
+    let config_data = std::fs::read_to_string(path)?;
+
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    let figment = match extension {
+        Some("yaml" | "yml") => Figment::from(Yaml::string(&config_data)),
+        Some("json") => Figment::from(Json::string(&config_data)),
+        _ => Figment::from(Toml::string(&config_data)),
+    };
+    figment
+        .merge(Env::prefixed(ENV_PREFIX).split(ENV_KEY_SEPARATOR))
+        .extract()
+        .map_err(|err| Box::new(err).into())
+}
+
+/// Limits applied to requests handled by the API servers
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Maximum size of a request body, in bytes
+    pub max_body_size_bytes: usize,
+    /// Maximum size of a block submission to `/relay/v1/builder/blocks`, in bytes
+    pub max_submission_body_size_bytes: usize,
+    /// Maximum time to handle a request, in milliseconds
+    pub request_timeout_ms: u64,
+    /// Maximum time to handle a block submission to `/relay/v1/builder/blocks`, in milliseconds
+    pub submission_timeout_ms: u64,
+    /// Compress responses with gzip or brotli when requested by the client
+    pub compression: bool,
+    /// CORS policy for the relay data APIs under `/relay/v1/data`
+    pub cors: CorsConfig,
+    /// Serve over HTTPS with the given certificate, otherwise plain HTTP
+    pub tls: Option<TlsConfig>,
+    /// Bearer tokens accepted on the builder routes under `/relay/v1/builder`;
+    /// if empty, these routes do not require authentication and payload attributes are not
+    /// pushed to builders
+    pub builder_auth_tokens: Vec<String>,
+    /// IP addresses or CIDR ranges allowed to submit to `/relay/v1/builder/blocks`;
+    /// if empty, submissions are accepted from any address
+    pub submission_ip_allowlist: Vec<String>,
+    /// Only accept SSZ-encoded submissions to `/relay/v1/builder/blocks`, rejecting JSON
+    pub ssz_only_submissions: bool,
+}
+
+/// PEM-encoded certificate chain and private key used to terminate TLS
+#[derive(Debug, Clone, Deserialize)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+/// CORS policy for the relay data APIs; disabled if no origins are given
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    /// Origins allowed to make cross-origin requests, or `"*"` to allow any origin
+    pub allowed_origins: Vec<String>,
+    /// HTTP methods allowed in cross-origin requests
+    pub allowed_methods: Vec<String>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self { allowed_origins: vec![], allowed_methods: vec!["GET".to_string()] }
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            max_body_size_bytes: DEFAULT_MAX_BODY_SIZE_BYTES,
+            max_submission_body_size_bytes: DEFAULT_MAX_SUBMISSION_BODY_SIZE_BYTES,
+            request_timeout_ms: DEFAULT_REQUEST_TIMEOUT_MS,
+            submission_timeout_ms: DEFAULT_SUBMISSION_TIMEOUT_MS,
+            compression: true,
+            cors: Default::default(),
+            tls: None,
+            builder_auth_tokens: vec![],
+            submission_ip_allowlist: vec![],
+            ssz_only_submissions: false,
+        }
+    }
+}
+
+impl ServerConfig {
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout_ms)
+    }
+
+    pub fn submission_timeout(&self) -> Duration {
+        Duration::from_millis(self.submission_timeout_ms)
+    }
+}
+
+/// Encoding of the block submissions sent to a relay
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionEncoding {
+    #[default]
+    Json,
+    Ssz,
+    /// SSZ compressed with gzip
+    SszGzip,
+}
+
+/// Settings for the HTTP client used to connect to a relay
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RelayClientConfig {
+    /// Maximum time to establish a connection, in milliseconds
+    pub connect_timeout_ms: Option<u64>,
+    /// Maximum time for a request to complete, in milliseconds
+    pub request_timeout_ms: Option<u64>,
+    /// Interval for TCP keep-alive probes on open connections, in milliseconds
+    pub tcp_keepalive_ms: Option<u64>,
+    /// Time an idle connection is kept in the pool before closing, in milliseconds
+    pub pool_idle_timeout_ms: Option<u64>,
+    /// Maximum number of idle connections kept in the pool per host
+    pub pool_max_idle_per_host: Option<usize>,
+    /// Delay before retrying a status check that failed to connect or timed out, doubling for
+    /// each further retry, in milliseconds; unset disables retries
+    pub retry_initial_interval_ms: Option<u64>,
+    /// Time after which a failing status check is no longer retried, in milliseconds;
+    /// defaults to 5000 when retries are enabled
+    pub retry_max_elapsed_ms: Option<u64>,
+    /// Encoding of block submissions, as SSZ is much cheaper for relays to decode
+    pub submission_encoding: SubmissionEncoding,
+}
+
+/// Settings for the admin API of a service
+#[derive(Debug, Clone, Deserialize)]
+pub struct AdminConfig {
+    #[serde(
+        default = "default_admin_host",
+        deserialize_with = "crate::serde::ip_addr::deserialize"
+    )]
+    pub host: IpAddr,
+    pub port: u16,
+    /// Bearer token required on every request to the admin API, given inline, as `file:/path` or
+    /// as `env:VAR`; may only be omitted when `host` is a loopback address
+    #[serde(default, deserialize_with = "crate::serde::secret::deserialize")]
+    pub auth_token: String,
+}
+
+fn default_admin_host() -> IpAddr {
+    Ipv4Addr::LOCALHOST.into()
+}
+
+/// Settings for the Prometheus metrics endpoint of a service
+#[derive(Debug, Clone, Deserialize)]
+pub struct MetricsConfig {
+    #[serde(
+        default = "default_admin_host",
+        deserialize_with = "crate::serde::ip_addr::deserialize"
+    )]
+    pub host: IpAddr,
+    pub port: u16,
+}
@@ -2,19 +2,27 @@ use crate::types::AuctionRequest;
 use beacon_api_client::Error as ApiError;
 use ethereum_consensus::{
     crypto::KzgCommitment,
-    primitives::{BlsPublicKey, ExecutionAddress, Hash32, ValidatorIndex},
+    primitives::{BlsPublicKey, ExecutionAddress, Hash32, Slot, ValidatorIndex, U256},
+    ssz::prelude::{DeserializeError, SerializeError},
     Error as ConsensusError, Fork,
 };
+use std::sync::Arc;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum BoostError {
     #[error("bid public key {bid} does not match relay public key {relay}")]
     BidPublicKeyMismatch { bid: BlsPublicKey, relay: BlsPublicKey },
+    #[error("bid parent hash {bid} does not match requested parent hash {request}")]
+    BidParentHashMismatch { bid: Hash32, request: Hash32 },
+    #[error("bid {0} does not match the payload attributes from the consensus node")]
+    BidPayloadAttributesMismatch(&'static str),
     #[error("could not find relay with outstanding bid to accept for block {0}")]
     MissingOpenBid(Hash32),
     #[error("could not register with any relay")]
     CouldNotRegister,
+    #[error("not ready to serve auctions: {0}")]
+    NotReady(&'static str),
     #[error("no payload returned for opened bid with block hash {0:?}")]
     MissingPayload(Hash32),
     #[error("returned payload block hash {provided} did not match expected {expected}")]
@@ -55,14 +63,40 @@ pub enum RelayError {
     UnknownValidatorIndex(ValidatorIndex),
     #[error("builder with public key {0:?} is not currently registered")]
     BuilderNotRegistered(BlsPublicKey),
+    #[error("builder with public key {0:?} is denied")]
+    BuilderDenied(BlsPublicKey),
+    #[error("header submission for a Deneb payload is missing its blob commitments")]
+    MissingBlobCommitments,
+    #[error("block {0:?} was bid without its payload, which has not arrived")]
+    MissingPayload(Hash32),
+    #[error("builder with public key {0:?} may not bid without its payload")]
+    HeaderSubmissionNotAllowed(BlsPublicKey),
+    #[error(
+        "signed blinded beacon block for slot {0} arrived {1} ms into the slot, past the cutoff"
+    )]
+    LateSignedBlindedBeaconBlock(Slot, u64),
+    #[error("execution payload does not end with a payment to the proposer's fee recipient")]
+    MissingProposerPayment,
+    #[error(
+        "execution payload pays the proposer through its fee recipient, which can not be verified"
+    )]
+    UnverifiableProposerPayment,
+    #[error("bid trace declares value of {0} but execution payload pays the proposer {1}")]
+    InsufficientProposerPayment(U256, U256),
 }
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("expecting data from {expected} but provided {provided}")]
     InvalidFork { expected: Fork, provided: Fork },
+    #[error("data from {0} is not supported")]
+    UnsupportedFork(Fork),
     #[error("no bid prepared for request {0}")]
     NoBidPrepared(AuctionRequest),
+    #[error("relay auth token is not a valid header value")]
+    InvalidAuthToken,
+    #[error("invalid configuration: {0}")]
+    InvalidConfig(String),
     #[error(transparent)]
     ValidatorRegistry(#[from] crate::validator_registry::Error),
     #[error(transparent)]
@@ -76,7 +110,14 @@ pub enum Error {
     #[error(transparent)]
     Consensus(#[from] ConsensusError),
     #[error(transparent)]
+    Ssz(#[from] DeserializeError),
+    #[error(transparent)]
+    SszSerialize(#[from] SerializeError),
+    #[error(transparent)]
     Api(#[from] ApiError),
+    /// An error from a request shared by several callers
+    #[error(transparent)]
+    Shared(Arc<Error>),
 }
 
 #[cfg(feature = "api")]
@@ -90,10 +131,17 @@ use axum::response::{IntoResponse, Response};
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
         let message = self.to_string();
-        let code = match self {
-            Self::NoBidPrepared(..) => StatusCode::NO_CONTENT,
-            _ => StatusCode::BAD_REQUEST,
-        };
+        let code = status_code(&self);
         (code, Json(beacon_api_client::ApiError::ErrorMessage { code, message })).into_response()
     }
 }
+
+#[cfg(feature = "api")]
+fn status_code(err: &Error) -> StatusCode {
+    match err {
+        Error::NoBidPrepared(..) => StatusCode::NO_CONTENT,
+        Error::Boost(BoostError::NotReady(..)) => StatusCode::SERVICE_UNAVAILABLE,
+        Error::Shared(err) => status_code(err),
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
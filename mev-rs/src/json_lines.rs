@@ -0,0 +1,189 @@
+use serde::Serialize;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, SyncSender, TrySendError},
+    thread::{self, JoinHandle},
+};
+use tracing::{error, info, warn};
+
+/// Appends entries to a file as lines of JSON from a dedicated thread, so recording an entry
+/// never blocks the caller on file I/O
+///
+/// At most `capacity` entries wait to be written; further entries are refused rather than held
+/// in memory. Entries still queued when the writer is dropped are written before it returns.
+pub struct JsonLinesWriter<T> {
+    entries: Option<SyncSender<T>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl<T: Serialize + Send + 'static> JsonLinesWriter<T> {
+    /// Opens the file at `path` for appending and spawns the thread `name` writing to it
+    ///
+    /// If `max_file_bytes` is set, the file is moved to [`rotated_path`] once it reaches that
+    /// size, replacing any previously rotated file, and writing continues to a new file.
+    pub fn spawn(
+        name: &str,
+        path: PathBuf,
+        capacity: usize,
+        max_file_bytes: Option<u64>,
+    ) -> io::Result<Self> {
+        let output = Output::open(path, max_file_bytes)?;
+        let (tx, rx) = mpsc::sync_channel(capacity);
+        let handle = thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || write_entries(output, rx))?;
+        Ok(Self { entries: Some(tx), handle: Some(handle) })
+    }
+
+    /// Queues `entry` to be written, handing it back if the queue is full or the writer stopped
+    pub fn append(&self, entry: T) -> Result<(), TrySendError<T>> {
+        match self.entries.as_ref() {
+            Some(entries) => entries.try_send(entry),
+            None => Err(TrySendError::Disconnected(entry)),
+        }
+    }
+}
+
+impl<T> Drop for JsonLinesWriter<T> {
+    fn drop(&mut self) {
+        // NOTE: closing the channel lets the writer finish the backlog and exit
+        drop(self.entries.take());
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                error!("JSON lines writer panicked");
+            }
+        }
+    }
+}
+
+/// Path the file at `path` is moved to when rotated
+pub fn rotated_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
+
+struct Output {
+    path: PathBuf,
+    file: BufWriter<File>,
+    len: u64,
+    max_file_bytes: Option<u64>,
+}
+
+impl Output {
+    fn open(path: PathBuf, max_file_bytes: Option<u64>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let len = file.metadata()?.len();
+        Ok(Self { path, file: BufWriter::new(file), len, max_file_bytes })
+    }
+
+    fn write(&mut self, entry: &impl Serialize) -> io::Result<()> {
+        if self.max_file_bytes.is_some_and(|max_file_bytes| self.len >= max_file_bytes) {
+            // NOTE: keep writing to the current file rather than losing entries
+            if let Err(err) = self.rotate() {
+                warn!(%err, path = %self.path.display(), "could not rotate file");
+            }
+        }
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        self.len += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        fs::rename(&self.path, rotated_path(&self.path))?;
+        let file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.file = BufWriter::new(file);
+        self.len = 0;
+        info!(path = %self.path.display(), "rotated file");
+        Ok(())
+    }
+}
+
+fn write_entries<T: Serialize>(mut output: Output, entries: Receiver<T>) {
+    while let Ok(entry) = entries.recv() {
+        // NOTE: write any backlog before flushing so bursts of entries are flushed once
+        for entry in std::iter::once(entry).chain(entries.try_iter()) {
+            if let Err(err) = output.write(&entry) {
+                warn!(%err, path = %output.path.display(), "could not write entry");
+            }
+        }
+        if let Err(err) = output.file.flush() {
+            warn!(%err, path = %output.path.display(), "could not flush entries");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("mev-rs-{name}-{}.jsonl", std::process::id()))
+    }
+
+    fn read_lines(path: &Path) -> Vec<u64> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_writes_backlog_on_drop() {
+        let path = temp_path("backlog");
+        let _ = fs::remove_file(&path);
+
+        let writer = JsonLinesWriter::spawn("test-writer", path.clone(), 8, None).unwrap();
+        for entry in 0..8u64 {
+            writer.append(entry).unwrap();
+        }
+        drop(writer);
+
+        let entries = read_lines(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(entries, (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_refuses_entries_when_full() {
+        let (tx, rx) = mpsc::sync_channel(1);
+        let writer = JsonLinesWriter { entries: Some(tx), handle: None };
+        assert!(writer.append(0u64).is_ok());
+        assert!(matches!(writer.append(1), Err(TrySendError::Full(1))));
+        drop(writer);
+        assert_eq!(rx.iter().collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    fn test_rotates_full_file() {
+        let path = temp_path("rotate");
+        let rotated = rotated_path(&path);
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(&rotated);
+
+        // NOTE: each entry is a single digit and a newline
+        let writer = JsonLinesWriter::spawn("test-writer", path.clone(), 8, Some(4)).unwrap();
+        for entry in 0..5u64 {
+            writer.append(entry).unwrap();
+        }
+        drop(writer);
+
+        let (entries, rotated_entries) = (read_lines(&path), read_lines(&rotated));
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&rotated).unwrap();
+        assert_eq!(rotated_entries, vec![2, 3]);
+        assert_eq!(entries, vec![4]);
+    }
+
+    #[test]
+    fn test_requires_writable_path() {
+        let path = std::env::temp_dir().join("mev-rs-missing-dir").join("entries.jsonl");
+        assert!(JsonLinesWriter::<u64>::spawn("test-writer", path, 1, None).is_err());
+    }
+}
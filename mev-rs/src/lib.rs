@@ -1,14 +1,24 @@
+#[cfg(feature = "api")]
+pub mod admin;
 pub mod blinded_block_provider;
 pub mod blinded_block_relayer;
 pub mod block_validation;
+#[cfg(feature = "api")]
+pub mod caller;
 pub mod config;
 mod error;
 mod genesis;
+#[cfg(feature = "api")]
+pub mod json_lines;
 mod proposer_scheduler;
 pub mod relay;
+#[cfg(feature = "api")]
+pub mod request_id;
 #[cfg(feature = "serde")]
 pub mod serde;
 pub mod signing;
+#[cfg(feature = "api")]
+pub mod telemetry;
 pub mod types;
 mod validator_registry;
 
@@ -1,9 +1,10 @@
 use crate::{types::ProposerSchedule, validator_registry::ValidatorRegistry};
 use beacon_api_client::{Error as ApiError, ProposerDuty};
-use ethereum_consensus::primitives::{Epoch, Slot};
+use ethereum_consensus::primitives::{BlsPublicKey, Epoch, Root, Slot};
 use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
 use thiserror::Error;
-use tracing::warn;
+use tracing::{info, warn};
 
 #[cfg(not(feature = "minimal-preset"))]
 use beacon_api_client::mainnet::Client;
@@ -18,88 +19,159 @@ pub enum Error {
 
 pub struct ProposerScheduler {
     api: Client,
-    slots_per_epoch: Slot,
     state: Mutex<State>,
 }
 
 #[derive(Default)]
 struct State {
+    // the epoch the `duties` were last refreshed for
+    current_epoch: Option<Epoch>,
+    // proposer duties for the current and next epoch, along with their dependent root
+    // so that re-orgs can be detected
+    duties: HashMap<Epoch, (Root, Vec<ProposerDuty>)>,
     // schedules are monotonically increasing by `slot`
     // but may not be contiguous as schedules are created only
     // if we have a valid registration from the proposer
     proposer_schedule: Vec<ProposerSchedule>,
 }
 
+fn compute_schedules<'a>(
+    duties: impl Iterator<Item = &'a ProposerDuty>,
+    validator_registry: &ValidatorRegistry,
+) -> Vec<ProposerSchedule> {
+    let mut schedules = duties
+        .filter_map(|duty| {
+            let public_key = &duty.public_key;
+            validator_registry.get_signed_registration(public_key).map(|entry| ProposerSchedule {
+                slot: duty.slot,
+                validator_index: duty.validator_index,
+                entry: entry.as_ref().clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+    schedules.sort_by_key(|schedule| schedule.slot);
+    schedules
+}
+
 impl ProposerScheduler {
-    pub fn new(api: Client, slots_per_epoch: Slot) -> Self {
-        Self { api, slots_per_epoch, state: Default::default() }
+    pub fn new(api: Client) -> Self {
+        Self { api, state: Default::default() }
+    }
+
+    // Fetches the proposer duties for `epoch`, returning `true` if they differ from those
+    // already known.
+    async fn fetch_duties(&self, epoch: Epoch) -> Result<bool, Error> {
+        let (dependent_root, duties) = self.api.get_proposer_duties(epoch).await?;
+        let mut state = self.state.lock();
+        let changed = state
+            .duties
+            .get(&epoch)
+            .map(|(known_root, _)| known_root != &dependent_root)
+            .unwrap_or(true);
+        state.duties.insert(epoch, (dependent_root, duties));
+        Ok(changed)
+    }
+
+    // Rebuilds the schedule from the known duties for the current epoch `epoch` and the next
+    // epoch so that it reflects the latest validator registrations.
+    fn rebuild_schedule(&self, epoch: Epoch, validator_registry: &ValidatorRegistry) {
+        let duties = {
+            let mut state = self.state.lock();
+            state.duties.retain(|duty_epoch, _| *duty_epoch >= epoch);
+            state.duties.values().flat_map(|(_, duties)| duties.clone()).collect::<Vec<_>>()
+        };
+        // compute the schedules so we do the work *before* grabbing the lock again
+        let proposer_schedule = compute_schedules(duties.iter(), validator_registry);
+        self.state.lock().proposer_schedule = proposer_schedule;
     }
 
-    async fn fetch_duties_if_missing(
+    /// Refreshes the schedule for the current epoch `epoch` and the next epoch
+    ///
+    /// Duties are fetched again when the epoch advances, as the duties for the next epoch
+    /// may have changed since they were first fetched; otherwise only missing duties are
+    /// fetched and the schedule is rebuilt with the latest validator registrations.
+    pub async fn on_epoch(
         &self,
         epoch: Epoch,
-        all_duties: &mut Vec<ProposerDuty>,
+        validator_registry: &ValidatorRegistry,
     ) -> Result<(), Error> {
-        {
-            let slot = epoch * self.slots_per_epoch;
-            let state = self.state.lock();
-            if state.proposer_schedule.iter().any(|schedule| schedule.slot >= slot) {
-                return Ok(())
+        let is_new_epoch = {
+            let mut state = self.state.lock();
+            let is_new_epoch = state.current_epoch != Some(epoch);
+            state.current_epoch = Some(epoch);
+            is_new_epoch
+        };
+        for epoch in [epoch, epoch + 1] {
+            if !is_new_epoch && self.state.lock().duties.contains_key(&epoch) {
+                continue
+            }
+            if let Err(err) = self.fetch_duties(epoch).await {
+                warn!(%err, epoch, "could not get proposer duties from consensus");
             }
         }
-        // TODO be tolerant to re-orgs
-        let (_dependent_root, duties) = self.api.get_proposer_duties(epoch).await?;
-        all_duties.extend(duties);
+        self.rebuild_schedule(epoch, validator_registry);
         Ok(())
     }
 
-    // Fetches proposer duties for the current epoch `epoch` and the next epoch.
-    async fn fetch_new_duties(&self, epoch: Epoch) -> Vec<ProposerDuty> {
-        let mut duties = vec![];
-        if let Err(err) = self.fetch_duties_if_missing(epoch, &mut duties).await {
-            warn!(%err, epoch, "could not get proposer duties from consensus");
+    /// Re-fetches the proposer duties for the current epoch `epoch` and the next epoch after the
+    /// head of the chain changes, rebuilding the schedule if any duties now depend on a
+    /// different block
+    ///
+    /// Returns `true` if the schedule changed.
+    pub async fn on_reorg(
+        &self,
+        epoch: Epoch,
+        validator_registry: &ValidatorRegistry,
+    ) -> Result<bool, Error> {
+        let mut changed = false;
+        for epoch in [epoch, epoch + 1] {
+            if self.fetch_duties(epoch).await? {
+                info!(epoch, "proposer duties changed after re-org");
+                changed = true;
+            }
         }
-        if let Err(err) = self.fetch_duties_if_missing(epoch + 1, &mut duties).await {
-            warn!(%err, epoch = epoch + 1, "could not get proposer duties from consensus");
+        if changed {
+            self.rebuild_schedule(epoch, validator_registry);
         }
-        duties
+        Ok(changed)
     }
 
-    pub async fn on_epoch(
+    /// Returns the proposers for the current and next epoch without a validator registration
+    pub fn unregistered_proposers(
         &self,
-        epoch: Epoch,
         validator_registry: &ValidatorRegistry,
-    ) -> Result<(), Error> {
-        let extension = self
-            .fetch_new_duties(epoch)
-            .await
-            .iter()
-            .filter_map(|duty| {
-                let public_key = &duty.public_key;
-                validator_registry.get_signed_registration(public_key).map(|entry| {
-                    ProposerSchedule {
-                        slot: duty.slot,
-                        validator_index: duty.validator_index,
-                        entry: entry.clone(),
-                    }
-                })
-            })
-            // collect so we do the work *before* grabbing the lock
-            .collect::<Vec<_>>();
-
-        let slot = epoch * self.slots_per_epoch;
-        let mut state = self.state.lock();
-        // drop old schedules
-        state.proposer_schedule.retain(|schedule| schedule.slot >= slot);
-        // add new schedules
-        state.proposer_schedule.extend(extension);
-        Ok(())
+    ) -> HashSet<BlsPublicKey> {
+        let public_keys = {
+            let state = self.state.lock();
+            state
+                .duties
+                .values()
+                .flat_map(|(_, duties)| duties.iter().map(|duty| duty.public_key.clone()))
+                .collect::<HashSet<_>>()
+        };
+        public_keys
+            .into_iter()
+            .filter(|public_key| validator_registry.get_signed_registration(public_key).is_none())
+            .collect()
     }
 
+    /// Returns the schedule of proposers with a validator registration for the current and
+    /// next epoch
     pub fn get_proposal_schedule(&self) -> Result<Vec<ProposerSchedule>, Error> {
         // NOTE: if external APIs hold, then the expected schedules are
         // those currently in the `state`.
         let state = self.state.lock();
         Ok(state.proposer_schedule.clone())
     }
+
+    /// Returns the schedule of the proposer for `slot` if it is in the current or next epoch and
+    /// has a validator registration
+    pub fn get_proposer_schedule(&self, slot: Slot) -> Option<ProposerSchedule> {
+        let state = self.state.lock();
+        let schedules = &state.proposer_schedule;
+        schedules
+            .binary_search_by_key(&slot, |schedule| schedule.slot)
+            .ok()
+            .map(|index| schedules[index].clone())
+    }
 }
@@ -1,31 +1,59 @@
 use crate::{
-    blinded_block_provider::Client as BlockProvider,
-    blinded_block_relayer::{BlindedBlockRelayer, Client as Relayer},
+    blinded_block_provider::{Client as BlockProvider, RetryConfig},
+    blinded_block_relayer::{
+        BlindedBlockRelayer, BlockSubmissionFilter, Client as Relayer, DeliveredPayloadFilter,
+    },
+    config::RelayClientConfig,
     error::Error,
-    types::{ProposerSchedule, SignedBidSubmission},
+    types::{
+        block_submission::data_api::{PayloadTrace, SubmissionTrace},
+        ProposerSchedule, SignedBidSubmission, SignedHeaderSubmission,
+    },
 };
 use async_trait::async_trait;
 use beacon_api_client::Client as BeaconClient;
 use ethereum_consensus::{
-    crypto::BlsError, primitives::BlsPublicKey, serde::try_bytes_from_hex_str,
+    crypto::BlsError,
+    primitives::{BlsPublicKey, Slot},
+    serde::try_bytes_from_hex_str,
 };
-use std::{cmp, fmt, hash, ops::Deref};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use std::{cmp, fmt, hash, ops::Deref, time::Duration};
 use tracing::{error, warn};
 use url::Url;
 
+/// A relay given as a URL of the form `https://{public_key}[:{auth_token}]@{host}[/{path}]`
+///
+/// If present, the `auth_token` is sent to the relay as a bearer token and is removed from `url`.
+/// Any `path` is kept as a prefix to the relay's APIs, e.g. for relays behind a reverse proxy.
 pub struct RelayEndpoint {
     url: Url,
     public_key: BlsPublicKey,
+    auth_token: Option<String>,
 }
 
 impl TryFrom<Url> for RelayEndpoint {
     type Error = BlsError;
 
-    fn try_from(url: Url) -> Result<Self, Self::Error> {
+    fn try_from(mut url: Url) -> Result<Self, Self::Error> {
         let public_key = try_bytes_from_hex_str(url.username())?;
         let public_key = BlsPublicKey::try_from(&public_key[..])?;
 
-        Ok(Self { url, public_key })
+        let auth_token = url.password().map(String::from);
+        if auth_token.is_some() {
+            // NOTE: `set_password` only fails for URLs that cannot carry credentials,
+            // which is not the case given a password was present
+            let _ = url.set_password(None);
+        }
+
+        Ok(Self { url, public_key, auth_token })
+    }
+}
+
+impl RelayEndpoint {
+    /// Public key of the relay, as given in the URL of the endpoint
+    pub fn public_key(&self) -> &BlsPublicKey {
+        &self.public_key
     }
 }
 
@@ -41,6 +69,15 @@ impl fmt::Display for RelayEndpoint {
     }
 }
 
+/// Returns `url` with a trailing `/` so the API paths joined to it keep any path prefix in `url`
+pub(crate) fn as_base_url(mut url: Url) -> Url {
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+    url
+}
+
 // TODO: refactor to yield error
 pub fn parse_relay_endpoints(relay_urls: &[String]) -> Vec<RelayEndpoint> {
     let mut relays = vec![];
@@ -60,6 +97,20 @@ pub fn parse_relay_endpoints(relay_urls: &[String]) -> Vec<RelayEndpoint> {
     relays
 }
 
+/// Creates a [`Relay`] for each endpoint, skipping any whose HTTP client could not be built
+pub fn create_relays(endpoints: Vec<RelayEndpoint>, config: &RelayClientConfig) -> Vec<Relay> {
+    endpoints
+        .into_iter()
+        .filter_map(|endpoint| {
+            let url = endpoint.url.clone();
+            Relay::new(endpoint, config)
+                .map_err(|err| warn!(%err, %url, "error creating client for relay"))
+                .ok()
+        })
+        .collect()
+}
+
+#[derive(Clone)]
 pub struct Relay {
     provider: BlockProvider,
     relayer: Relayer,
@@ -101,14 +152,89 @@ impl Deref for Relay {
     }
 }
 
-impl From<RelayEndpoint> for Relay {
-    fn from(value: RelayEndpoint) -> Self {
-        let RelayEndpoint { url, public_key } = value;
+/// Time after which failing status checks are no longer retried, if not configured
+pub const DEFAULT_RETRY_MAX_ELAPSED_MS: u64 = 5000;
+
+fn build_http_client(
+    config: &RelayClientConfig,
+    auth_token: Option<&str>,
+) -> Result<reqwest::Client, Error> {
+    let mut builder = reqwest::Client::builder()
+        .tcp_keepalive(config.tcp_keepalive_ms.map(Duration::from_millis));
+    if let Some(auth_token) = auth_token {
+        let mut value = HeaderValue::from_str(&format!("Bearer {auth_token}"))
+            .map_err(|_| Error::InvalidAuthToken)?;
+        value.set_sensitive(true);
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, value);
+        builder = builder.default_headers(headers);
+    }
+    if let Some(timeout) = config.pool_idle_timeout_ms {
+        builder = builder.pool_idle_timeout(Duration::from_millis(timeout));
+    }
+    if let Some(timeout) = config.connect_timeout_ms {
+        builder = builder.connect_timeout(Duration::from_millis(timeout));
+    }
+    if let Some(timeout) = config.request_timeout_ms {
+        builder = builder.timeout(Duration::from_millis(timeout));
+    }
+    if let Some(max_idle) = config.pool_max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(max_idle);
+    }
+    builder.build().map_err(|err| beacon_api_client::Error::Http(err).into())
+}
+
+impl Relay {
+    /// Connects to the relay at `endpoint` with an HTTP client built from `config`
+    pub fn new(endpoint: RelayEndpoint, config: &RelayClientConfig) -> Result<Self, Error> {
+        let RelayEndpoint { mut url, public_key, auth_token } = endpoint;
+        let http = build_http_client(config, auth_token.as_deref())?;
+        // NOTE: the public key stays in `endpoint` to identify the relay in logs and metrics
         let endpoint = url.clone();
-        let api_client = BeaconClient::new(url);
-        let provider = BlockProvider::new(api_client.clone());
-        let relayer = Relayer::new(api_client.clone());
-        Self { provider, relayer, public_key, endpoint }
+        if auth_token.is_some() {
+            // NOTE: the HTTP client would otherwise send the credentials in `url` as basic auth,
+            // overriding the bearer token
+            let _ = url.set_username("");
+        }
+        let api_client = BeaconClient::new_with_client(http, url);
+        let mut provider = BlockProvider::new(api_client.clone());
+        if let Some(initial_interval) = config.retry_initial_interval_ms {
+            provider = provider.with_retry(RetryConfig {
+                initial_interval: Duration::from_millis(initial_interval),
+                max_elapsed_time: Duration::from_millis(
+                    config.retry_max_elapsed_ms.unwrap_or(DEFAULT_RETRY_MAX_ELAPSED_MS),
+                ),
+            });
+        }
+        let relayer =
+            Relayer::new(api_client.clone()).with_submission_encoding(config.submission_encoding);
+        Ok(Self { provider, relayer, public_key, endpoint })
+    }
+
+    /// Submit bids so the relay accepts lower ones from the builder later in the same slot
+    pub fn with_cancellations(mut self, enabled: bool) -> Self {
+        self.relayer = self.relayer.with_cancellations(enabled);
+        self
+    }
+
+    pub fn cancellations(&self) -> bool {
+        self.relayer.cancellations()
+    }
+
+    /// Fetches the payloads delivered by the relay matching `filters` from its data API
+    pub async fn get_delivered_payloads(
+        &self,
+        filters: &DeliveredPayloadFilter,
+    ) -> Result<Vec<PayloadTrace>, Error> {
+        self.relayer.get_delivered_payloads(filters).await
+    }
+
+    /// Fetches the block submissions received by the relay matching `filters` from its data API
+    pub async fn get_block_submissions(
+        &self,
+        filters: &BlockSubmissionFilter,
+    ) -> Result<Vec<SubmissionTrace>, Error> {
+        self.relayer.get_block_submissions(filters).await
     }
 }
 
@@ -118,10 +244,18 @@ impl BlindedBlockRelayer for Relay {
         self.relayer.get_proposal_schedule().await
     }
 
+    async fn get_proposer_schedule(&self, slot: Slot) -> Result<Option<ProposerSchedule>, Error> {
+        self.relayer.get_proposer_schedule(slot).await
+    }
+
     async fn submit_bid(&self, signed_submission: &SignedBidSubmission) -> Result<(), Error> {
         // TODO: retry on error
         self.relayer.submit_bid(signed_submission).await
     }
+
+    async fn submit_header(&self, signed_submission: &SignedHeaderSubmission) -> Result<(), Error> {
+        self.relayer.submit_header(signed_submission).await
+    }
 }
 
 #[cfg(test)]
@@ -156,6 +290,30 @@ mod tests {
         assert_eq!(endpoint.public_key, BlsPublicKey::try_from(bytes.as_ref()).unwrap());
     }
 
+    #[test]
+    fn parse_relay_endpoint_with_auth_token() {
+        let mut url = Url::parse(RELAY_URL).unwrap();
+        let expected_url = url.clone();
+        url.set_password(Some("some-token")).unwrap();
+
+        let endpoint = RelayEndpoint::try_from(url).unwrap();
+        assert_eq!(endpoint.url, expected_url);
+        assert_eq!(endpoint.auth_token.as_deref(), Some("some-token"));
+    }
+
+    #[test]
+    fn join_paths_under_prefix() {
+        let path = "eth/v1/builder/status";
+        for (url, expected) in [
+            (URL, "https://relay.com/eth/v1/builder/status"),
+            ("https://relay.com/relay", "https://relay.com/relay/eth/v1/builder/status"),
+            ("https://relay.com/relay/", "https://relay.com/relay/eth/v1/builder/status"),
+        ] {
+            let url = as_base_url(Url::parse(url).unwrap());
+            assert_eq!(url.join(path).unwrap().as_str(), expected);
+        }
+    }
+
     #[test]
     #[should_panic]
     fn parse_relay_endpoint_missing_public_key() {
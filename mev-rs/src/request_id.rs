@@ -0,0 +1,57 @@
+use axum::{
+    http::{HeaderName, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::future::Future;
+use tracing::{info_span, Instrument, Span};
+
+/// Header used to correlate a request across services
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+// the longest incoming request id that is honored before generating a fresh one
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Returns the id of the request currently being served by this task, if any
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Wraps `future` so it keeps the request id and tracing span of the current task, for futures
+/// that are spawned onto a new task
+pub fn in_current_request<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    let request_id = current();
+    async move {
+        match request_id {
+            Some(request_id) => REQUEST_ID.scope(request_id, future).await,
+            None => future.await,
+        }
+    }
+    .instrument(Span::current())
+}
+
+fn generate() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+fn from_request<B>(request: &Request<B>) -> Option<String> {
+    let value = request.headers().get(&REQUEST_ID_HEADER)?.to_str().ok()?;
+    (!value.is_empty() && value.len() <= MAX_REQUEST_ID_LEN).then(|| value.to_string())
+}
+
+/// Middleware that takes the request id from the incoming `X-Request-Id` header, or generates
+/// a new one, and makes it available to the handler, its tracing span and the response headers
+pub(crate) async fn propagate_request_id<B>(request: Request<B>, next: Next<B>) -> Response {
+    let request_id = from_request(&request).unwrap_or_else(generate);
+    let span = info_span!("request", request_id = %request_id, method = %request.method(), path = %request.uri().path());
+    let mut response =
+        REQUEST_ID.scope(request_id.clone(), next.run(request).instrument(span)).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
@@ -1,5 +1,52 @@
 pub(crate) use ethereum_consensus::serde::as_str;
 
+/// Deserializes an [`std::net::IpAddr`], also accepting IPv6 addresses in brackets like `[::]`
+pub mod ip_addr {
+    use ::serde::{de::Error, Deserialize, Deserializer};
+    use std::net::IpAddr;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<IpAddr, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let s = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')).unwrap_or(&s);
+        s.parse().map_err(Error::custom)
+    }
+}
+
+/// Deserializes a secret given either inline or as a reference to where it is kept:
+/// `file:/path/to/secret` reads the secret from a file, ignoring surrounding whitespace, and
+/// `env:VAR` reads it from the environment variable `VAR`
+pub mod secret {
+    use ::serde::{
+        de::{value::StringDeserializer, Error},
+        Deserialize, Deserializer,
+    };
+    use std::{env, fs};
+
+    const FILE_PREFIX: &str = "file:";
+    const ENV_PREFIX: &str = "env:";
+
+    /// Returns the secret referred to by `value`, or `value` itself if it is not a reference
+    pub fn resolve(value: String) -> Result<String, String> {
+        if let Some(path) = value.strip_prefix(FILE_PREFIX) {
+            fs::read_to_string(path)
+                .map(|secret| secret.trim().to_string())
+                .map_err(|err| format!("could not read secret from `{path}`: {err}"))
+        } else if let Some(var) = value.strip_prefix(ENV_PREFIX) {
+            env::var(var).map_err(|err| format!("could not read secret from `{var}`: {err}"))
+        } else {
+            Ok(value)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        let secret = resolve(value).map_err(Error::custom)?;
+        T::deserialize(StringDeserializer::<D::Error>::new(secret))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use ethereum_consensus::{primitives::U256, types::mainnet::ExecutionPayloadHeader};
@@ -24,4 +71,54 @@ mod tests {
         dbg!(&auction_request);
         println!("{auction_request}");
     }
+
+    #[test]
+    fn test_ip_addr() {
+        use serde::Deserialize;
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+        #[derive(Deserialize)]
+        struct Config {
+            #[serde(deserialize_with = "crate::serde::ip_addr::deserialize")]
+            host: IpAddr,
+        }
+
+        for (input, expected) in [
+            ("0.0.0.0", IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+            ("::", IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+            ("[::]", IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+            ("[::1]", IpAddr::V6(Ipv6Addr::LOCALHOST)),
+        ] {
+            let config: Config = toml::from_str(&format!("host = \"{input}\"")).unwrap();
+            assert_eq!(config.host, expected);
+        }
+        assert!(toml::from_str::<Config>("host = \"[::\"").is_err());
+    }
+
+    #[test]
+    fn test_secret() {
+        use serde::Deserialize;
+
+        #[derive(Deserialize)]
+        struct Config {
+            #[serde(deserialize_with = "crate::serde::secret::deserialize")]
+            secret: String,
+        }
+
+        let path = std::env::temp_dir().join("mev-rs-test-secret");
+        std::fs::write(&path, "from-file\n").unwrap();
+        std::env::set_var("SECRET_DESERIALIZE_TEST_VALUE", "from-env");
+
+        for (input, expected) in [
+            ("inline".to_string(), "inline"),
+            (format!("file:{}", path.display()), "from-file"),
+            ("env:SECRET_DESERIALIZE_TEST_VALUE".to_string(), "from-env"),
+        ] {
+            let config: Config = toml::from_str(&format!("secret = \"{input}\"")).unwrap();
+            assert_eq!(config.secret, expected);
+        }
+        assert!(
+            toml::from_str::<Config>("secret = \"env:SECRET_DESERIALIZE_TEST_MISSING\"").is_err()
+        );
+    }
 }
@@ -1,5 +1,7 @@
+pub use ethereum_consensus::{
+    builder::compute_builder_domain, crypto::SecretKey, signing::verify_signed_data,
+};
 use ethereum_consensus::{
-    builder::compute_builder_domain,
     crypto,
     domains::DomainType,
     phase0::compute_domain,
@@ -9,7 +11,6 @@ use ethereum_consensus::{
     state_transition::Context,
     Error,
 };
-pub use ethereum_consensus::{crypto::SecretKey, signing::verify_signed_data};
 
 pub fn compute_consensus_domain(
     slot: Slot,
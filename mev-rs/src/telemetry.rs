@@ -0,0 +1,23 @@
+use crate::config::MetricsConfig;
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder};
+use std::net::SocketAddr;
+use tracing::{error, info};
+
+// Buckets for request latencies, in seconds.
+const LATENCY_BUCKETS_SECS: &[f64] = &[0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+
+/// Installs the process-wide metrics recorder, serving metrics in the Prometheus format at
+/// `/metrics` on the configured address
+///
+/// Metrics whose name ends in `_seconds` are exported as histograms suitable for latencies.
+pub fn install_metrics_exporter(config: &MetricsConfig) {
+    let addr = SocketAddr::from((config.host, config.port));
+    let result = PrometheusBuilder::new()
+        .with_http_listener(addr)
+        .set_buckets_for_metric(Matcher::Suffix("_seconds".to_string()), LATENCY_BUCKETS_SECS)
+        .and_then(PrometheusBuilder::install);
+    match result {
+        Ok(()) => info!("serving metrics at {addr}..."),
+        Err(err) => error!(%err, "could not install metrics exporter"),
+    }
+}
@@ -1,10 +1,20 @@
-use crate::types::{auction_contents::BlobsBundle, ExecutionPayload};
+use crate::{
+    types::{auction_contents::BlobsBundle, ExecutionPayload, ExecutionPayloadHeader},
+    Error,
+};
 use ethereum_consensus::{
+    crypto::KzgCommitment,
     primitives::{BlsPublicKey, BlsSignature, ExecutionAddress, Hash32, Slot},
     ssz::prelude::*,
+    state_transition::Context,
     Fork,
 };
 
+#[cfg(not(feature = "minimal-preset"))]
+use ethereum_consensus::deneb::mainnet::MAX_BLOB_COMMITMENTS_PER_BLOCK;
+#[cfg(feature = "minimal-preset")]
+use ethereum_consensus::deneb::minimal::MAX_BLOB_COMMITMENTS_PER_BLOCK;
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash, SimpleSerialize)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BidTrace {
@@ -89,6 +99,24 @@ pub mod data_api {
         #[serde(with = "crate::serde::as_str")]
         pub timestamp_ms: u128,
     }
+
+    /// A signed blinded beacon block the relay refused to unblind as it arrived past the cutoff
+    /// in its slot
+    // NOTE: non-standard trace
+    #[derive(Debug, Default, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct LatePayloadRequestTrace {
+        #[serde(with = "crate::serde::as_str")]
+        pub slot: Slot,
+        pub parent_hash: Hash32,
+        pub block_hash: Hash32,
+        #[serde(rename = "proposer_pubkey")]
+        pub proposer_public_key: BlsPublicKey,
+        #[serde(with = "crate::serde::as_str")]
+        pub ms_into_slot: u64,
+        #[serde(with = "crate::serde::as_str")]
+        pub timestamp_ms: u128,
+    }
 }
 
 pub mod bellatrix {
@@ -123,6 +151,74 @@ pub mod deneb {
     }
 }
 
+// The polymorphic `ExecutionPayload` can only be decoded from SSZ once its fork is known, so
+// submissions are decoded with the payload type of a specific fork and then converted.
+mod encoding {
+    use super::{
+        BidTrace, BlobsBundle, BlsSignature, KzgCommitment, MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    };
+    use ethereum_consensus::ssz::prelude::*;
+
+    #[cfg(not(feature = "minimal-preset"))]
+    use ethereum_consensus::{
+        bellatrix::mainnet as bellatrix, capella::mainnet as capella, deneb::mainnet as deneb,
+    };
+    #[cfg(feature = "minimal-preset")]
+    use ethereum_consensus::{
+        bellatrix::minimal as bellatrix, capella::minimal as capella, deneb::minimal as deneb,
+    };
+
+    #[derive(Debug, Serializable)]
+    pub struct BellatrixSubmission {
+        pub message: BidTrace,
+        pub execution_payload: bellatrix::ExecutionPayload,
+        pub signature: BlsSignature,
+    }
+
+    #[derive(Debug, Serializable)]
+    pub struct CapellaSubmission {
+        pub message: BidTrace,
+        pub execution_payload: capella::ExecutionPayload,
+        pub signature: BlsSignature,
+    }
+
+    #[derive(Debug, Serializable)]
+    pub struct DenebSubmission {
+        pub message: BidTrace,
+        pub execution_payload: deneb::ExecutionPayload,
+        pub blobs_bundle: BlobsBundle,
+        pub signature: BlsSignature,
+    }
+
+    #[derive(Debug, Serializable)]
+    pub struct BellatrixHeaderSubmission {
+        pub message: BidTrace,
+        pub execution_payload_header: bellatrix::ExecutionPayloadHeader,
+        pub signature: BlsSignature,
+    }
+
+    #[derive(Debug, Serializable)]
+    pub struct CapellaHeaderSubmission {
+        pub message: BidTrace,
+        pub execution_payload_header: capella::ExecutionPayloadHeader,
+        pub signature: BlsSignature,
+    }
+
+    #[derive(Debug, Serializable)]
+    pub struct DenebHeaderSubmission {
+        pub message: BidTrace,
+        pub execution_payload_header: deneb::ExecutionPayloadHeader,
+        pub blob_kzg_commitments: List<KzgCommitment, MAX_BLOB_COMMITMENTS_PER_BLOCK>,
+        pub signature: BlsSignature,
+    }
+}
+
+// The bid trace is fixed-size and encoded first, so the slot of a submission is read from the
+// start of its SSZ encoding regardless of the fork.
+fn slot_from_ssz(encoding: &[u8]) -> Result<Slot, Error> {
+    Ok(Slot::deserialize(&encoding[..encoding.len().min(8)])?)
+}
+
 #[derive(Debug, Clone, Serializable, HashTreeRoot)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[serde(untagged)]
@@ -153,6 +249,45 @@ impl<'de> serde::Deserialize<'de> for SignedBidSubmission {
 }
 
 impl SignedBidSubmission {
+    /// Decodes the SSZ encoding of a submission made during `fork`
+    pub fn deserialize_for_fork(encoding: &[u8], fork: Fork) -> Result<Self, Error> {
+        let submission = match fork {
+            Fork::Bellatrix => {
+                let inner = encoding::BellatrixSubmission::deserialize(encoding)?;
+                Self::Bellatrix(bellatrix::SignedBidSubmission {
+                    message: inner.message,
+                    execution_payload: ExecutionPayload::Bellatrix(inner.execution_payload),
+                    signature: inner.signature,
+                })
+            }
+            Fork::Capella => {
+                let inner = encoding::CapellaSubmission::deserialize(encoding)?;
+                Self::Capella(capella::SignedBidSubmission {
+                    message: inner.message,
+                    execution_payload: ExecutionPayload::Capella(inner.execution_payload),
+                    signature: inner.signature,
+                })
+            }
+            Fork::Deneb => {
+                let inner = encoding::DenebSubmission::deserialize(encoding)?;
+                Self::Deneb(deneb::SignedBidSubmission {
+                    message: inner.message,
+                    execution_payload: ExecutionPayload::Deneb(inner.execution_payload),
+                    blobs_bundle: inner.blobs_bundle,
+                    signature: inner.signature,
+                })
+            }
+            fork => return Err(Error::UnsupportedFork(fork)),
+        };
+        Ok(submission)
+    }
+
+    /// Decodes the SSZ encoding of a submission for the fork active at the slot of its bid trace
+    pub fn deserialize_for_slot(encoding: &[u8], context: &Context) -> Result<Self, Error> {
+        let slot = slot_from_ssz(encoding)?;
+        Self::deserialize_for_fork(encoding, context.fork_for(slot))
+    }
+
     pub fn version(&self) -> Fork {
         match self {
             Self::Bellatrix(..) => Fork::Bellatrix,
@@ -193,3 +328,68 @@ impl SignedBidSubmission {
         }
     }
 }
+
+/// A bid submitted ahead of its execution payload
+///
+/// The payload follows in a [`SignedBidSubmission`] with the same block hash, which the relay
+/// must receive before the bid can be opened.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignedHeaderSubmission {
+    pub message: BidTrace,
+    pub execution_payload_header: ExecutionPayloadHeader,
+    /// Commitments to the blobs of the payload, required from Deneb
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_kzg_commitments: Option<List<KzgCommitment, MAX_BLOB_COMMITMENTS_PER_BLOCK>>,
+    pub signature: BlsSignature,
+}
+
+impl SignedHeaderSubmission {
+    /// Decodes the SSZ encoding of a header submission made during `fork`
+    pub fn deserialize_for_fork(encoding: &[u8], fork: Fork) -> Result<Self, Error> {
+        let submission = match fork {
+            Fork::Bellatrix => {
+                let inner = encoding::BellatrixHeaderSubmission::deserialize(encoding)?;
+                Self {
+                    message: inner.message,
+                    execution_payload_header: ExecutionPayloadHeader::Bellatrix(
+                        inner.execution_payload_header,
+                    ),
+                    blob_kzg_commitments: None,
+                    signature: inner.signature,
+                }
+            }
+            Fork::Capella => {
+                let inner = encoding::CapellaHeaderSubmission::deserialize(encoding)?;
+                Self {
+                    message: inner.message,
+                    execution_payload_header: ExecutionPayloadHeader::Capella(
+                        inner.execution_payload_header,
+                    ),
+                    blob_kzg_commitments: None,
+                    signature: inner.signature,
+                }
+            }
+            Fork::Deneb => {
+                let inner = encoding::DenebHeaderSubmission::deserialize(encoding)?;
+                Self {
+                    message: inner.message,
+                    execution_payload_header: ExecutionPayloadHeader::Deneb(
+                        inner.execution_payload_header,
+                    ),
+                    blob_kzg_commitments: Some(inner.blob_kzg_commitments),
+                    signature: inner.signature,
+                }
+            }
+            fork => return Err(Error::UnsupportedFork(fork)),
+        };
+        Ok(submission)
+    }
+
+    /// Decodes the SSZ encoding of a header submission for the fork active at the slot of its
+    /// bid trace
+    pub fn deserialize_for_slot(encoding: &[u8], context: &Context) -> Result<Self, Error> {
+        let slot = slot_from_ssz(encoding)?;
+        Self::deserialize_for_fork(encoding, context.fork_for(slot))
+    }
+}
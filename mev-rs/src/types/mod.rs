@@ -6,7 +6,7 @@ mod proposer_schedule;
 
 pub use auction_contents::{deneb::BlobsBundle, AuctionContents};
 pub use auction_request::*;
-pub use block_submission::{BidTrace, SignedBidSubmission};
+pub use block_submission::{BidTrace, SignedBidSubmission, SignedHeaderSubmission};
 pub use builder_bid::{BuilderBid, SignedBuilderBid};
 pub use ethereum_consensus::builder::SignedValidatorRegistration;
 pub use ethereum_consensus_types::{
@@ -1,25 +1,52 @@
-use crate::{signing::verify_signed_builder_data, types::SignedValidatorRegistration};
-use beacon_api_client::{Error as ApiError, StateId, ValidatorStatus, ValidatorSummary};
+use crate::{
+    blinded_block_relayer::RegistrationStats,
+    signing::{compute_builder_domain, verify_signed_data},
+    types::SignedValidatorRegistration,
+};
+use beacon_api_client::{
+    Error as ApiError, PublicKeyOrIndex, StateId, ValidatorStatus, ValidatorSummary,
+};
 use ethereum_consensus::{
     builder::ValidatorRegistration,
-    primitives::{BlsPublicKey, Epoch, Slot, ValidatorIndex},
+    primitives::{BlsPublicKey, Domain, Epoch, Slot, ValidatorIndex},
     state_transition::Context,
     Error as ConsensusError,
 };
+use futures::{stream, StreamExt, TryStreamExt};
 use parking_lot::RwLock;
-use rayon::prelude::*;
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
 use std::{
     cmp::Ordering,
     collections::{HashMap, HashSet},
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread,
 };
 use thiserror::Error;
-use tracing::trace;
+use tracing::{error, info, trace, warn};
 
 #[cfg(not(feature = "minimal-preset"))]
 use beacon_api_client::mainnet::Client;
 #[cfg(feature = "minimal-preset")]
 use beacon_api_client::minimal::Client;
 
+// Number of validators requested from the beacon node at a time.
+const VALIDATOR_CHUNK_SIZE: usize = 512;
+// Number of requests for chunks of the validator set in flight at a time.
+const VALIDATOR_FETCH_CONCURRENCY: usize = 8;
+// Number of public keys to look up from the beacon node at a time.
+const PUBLIC_KEY_CHUNK_SIZE: usize = 64;
+// Only these validators can register or propose, so the rest of the validator set is skipped.
+const RELEVANT_VALIDATOR_STATUSES: [ValidatorStatus; 2] =
+    [ValidatorStatus::Pending, ValidatorStatus::Active];
+// Number of epochs between rewrites of the registrations file to hold only the latest ones.
+const COMPACTION_INTERVAL_EPOCHS: Epoch = 32;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("local time is {1} but registration has timestamp from future: {0:?}")]
@@ -81,34 +108,206 @@ fn validate_validator_status(
 #[derive(Default, Debug)]
 pub struct State {
     // data from registered validators
-    validator_preferences: HashMap<BlsPublicKey, SignedValidatorRegistration>,
+    validator_preferences: HashMap<BlsPublicKey, Arc<SignedValidatorRegistration>>,
     // data from consensus
     validators: HashMap<BlsPublicKey, ValidatorSummary>,
     pubkeys_by_index: HashMap<ValidatorIndex, BlsPublicKey>,
+    // count of validators registering for the first time in the current and the last epoch
+    new_registrations: usize,
+    new_registrations_last_epoch: usize,
+}
+
+// Changes to the persisted registrations, applied in order by a dedicated writer thread
+enum PersistRequest {
+    Append(Vec<Arc<SignedValidatorRegistration>>),
+    Compact(Vec<Arc<SignedValidatorRegistration>>),
 }
 
 // Maintains validators we are aware of
+//
+// Registrations are optionally persisted to a file with one JSON entry per line so they
+// survive restarts, rather than waiting for each validator to register again. The file is
+// written from a dedicated thread, and periodically compacted to the latest registrations.
 pub struct ValidatorRegistry {
     client: Client,
     slots_per_epoch: Slot,
     state: RwLock<State>,
+    persister: Option<Sender<PersistRequest>>,
+    // dedicated pool to verify registrations, otherwise the global `rayon` pool is used
+    thread_pool: Option<ThreadPool>,
 }
 
 impl ValidatorRegistry {
-    pub fn new(client: Client, slots_per_epoch: Slot) -> Self {
-        let state = RwLock::new(Default::default());
-        Self { client, slots_per_epoch, state }
+    pub fn new(
+        client: Client,
+        slots_per_epoch: Slot,
+        path: Option<PathBuf>,
+        registration_threads: Option<usize>,
+    ) -> Self {
+        let thread_pool = registration_threads.and_then(|threads| {
+            match ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name(|index| format!("registrations-{index}"))
+                .build()
+            {
+                Ok(thread_pool) => Some(thread_pool),
+                Err(err) => {
+                    warn!(%err, threads, "could not build registration thread pool; using global pool");
+                    None
+                }
+            }
+        });
+        let mut state = State::default();
+        if let Some(path) = path.as_ref() {
+            match load(path) {
+                Ok(registrations) => {
+                    info!(count = registrations.len(), path = %path.display(), "loaded validator registrations");
+                    state.validator_preferences = registrations;
+                    // NOTE: rewrite the file so that it only holds the latest registrations
+                    if let Err(err) = compact(path, state.validator_preferences.values()) {
+                        warn!(%err, path = %path.display(), "could not compact validator registrations");
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => {
+                    warn!(%err, path = %path.display(), "could not load validator registrations")
+                }
+            }
+        }
+        let persister = path.and_then(|path| {
+            let (tx, rx) = mpsc::channel();
+            let spawned = thread::Builder::new()
+                .name("validator-registrations".to_string())
+                .spawn(move || persist_registrations(&path, rx));
+            match spawned {
+                Ok(_) => Some(tx),
+                Err(err) => {
+                    error!(%err, "could not spawn validator registration writer");
+                    None
+                }
+            }
+        });
+        Self { client, slots_per_epoch, state: RwLock::new(state), persister, thread_pool }
     }
 
-    // TODO: load more efficiently
+    // Fetches the pending and active validators in chunks of indices, updating the state as each
+    // chunk arrives.
     pub async fn on_epoch(&self, epoch: Epoch) -> Result<(), Error> {
+        {
+            let mut state = self.state.write();
+            state.new_registrations_last_epoch = std::mem::take(&mut state.new_registrations);
+            if epoch % COMPACTION_INTERVAL_EPOCHS == 0 {
+                // NOTE: request compaction under the lock so no update is appended before it
+                let registrations = state.validator_preferences.values().cloned().collect();
+                self.persist(PersistRequest::Compact(registrations));
+            }
+        }
         let slot = epoch * self.slots_per_epoch;
-        let summaries = self.client.get_validators(StateId::Slot(slot), &[], &[]).await?;
+        // NOTE: chunks are filtered by status so find the end of the validator set up front
+        let validator_count = self.fetch_validator_count(slot).await?;
+        let mut chunks = stream::iter((0..validator_count).step_by(VALIDATOR_CHUNK_SIZE))
+            .map(|start| {
+                let end = validator_count.min(start + VALIDATOR_CHUNK_SIZE);
+                let indices = (start..end).map(PublicKeyOrIndex::Index).collect::<Vec<_>>();
+                async move {
+                    self.client
+                        .get_validators(StateId::Slot(slot), &indices, &RELEVANT_VALIDATOR_STATUSES)
+                        .await
+                }
+            })
+            .buffer_unordered(VALIDATOR_FETCH_CONCURRENCY);
+        let mut fetched = HashSet::new();
+        while let Some(summaries) = chunks.try_next().await? {
+            let mut state = self.state.write();
+            for summary in summaries.into_iter() {
+                fetched.insert(summary.index);
+                let public_key = summary.validator.public_key.clone();
+                state.pubkeys_by_index.insert(summary.index, public_key.clone());
+                state.validators.insert(public_key, summary);
+            }
+        }
+
+        // drop any validators that are no longer pending or active
         let mut state = self.state.write();
-        for summary in summaries.into_iter() {
-            let public_key = summary.validator.public_key.clone();
-            state.pubkeys_by_index.insert(summary.index, public_key.clone());
-            state.validators.insert(public_key, summary);
+        state.pubkeys_by_index.retain(|index, _| fetched.contains(index));
+        state.validators.retain(|_, summary| fetched.contains(&summary.index));
+        trace!(epoch, validator_count, relevant = fetched.len(), "fetched validator set");
+        Ok(())
+    }
+
+    async fn has_validator(&self, slot: Slot, index: ValidatorIndex) -> Result<bool, Error> {
+        let summaries = self
+            .client
+            .get_validators(StateId::Slot(slot), &[PublicKeyOrIndex::Index(index)], &[])
+            .await?;
+        Ok(!summaries.is_empty())
+    }
+
+    // Finds the number of validators in the state at `slot` by searching for the first index
+    // without a validator, starting from the end of the validator set seen last.
+    async fn fetch_validator_count(&self, slot: Slot) -> Result<usize, Error> {
+        if !self.has_validator(slot, 0).await? {
+            return Ok(0)
+        }
+        // `low` always has a validator and `high` never does
+        let mut low = 0;
+        let hint = self.state.read().pubkeys_by_index.keys().max().copied().unwrap_or_default();
+        if hint > 0 && self.has_validator(slot, hint).await? {
+            low = hint;
+        }
+        let mut step = 1;
+        let mut high = loop {
+            let probe = low + step;
+            if !self.has_validator(slot, probe).await? {
+                break probe
+            }
+            low = probe;
+            step *= 2;
+        };
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if self.has_validator(slot, middle).await? {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        Ok(high)
+    }
+
+    /// Looks up the validators for any registrations from public keys not yet known, e.g.
+    /// validators that became pending since the last epoch
+    pub async fn fetch_unknown_validators(
+        &self,
+        registrations: &[SignedValidatorRegistration],
+    ) -> Result<(), Error> {
+        let unknown_public_keys = {
+            let state = self.state.read();
+            registrations
+                .iter()
+                .map(|registration| &registration.message.public_key)
+                .filter(|public_key| !state.validators.contains_key(public_key))
+                .cloned()
+                .collect::<HashSet<_>>()
+        };
+        let unknown_public_keys =
+            unknown_public_keys.into_iter().map(PublicKeyOrIndex::PublicKey).collect::<Vec<_>>();
+        for public_keys in unknown_public_keys.chunks(PUBLIC_KEY_CHUNK_SIZE) {
+            let summaries = self
+                .client
+                .get_validators(StateId::Head, public_keys, &RELEVANT_VALIDATOR_STATUSES)
+                .await?;
+            trace!(
+                requested = public_keys.len(),
+                found = summaries.len(),
+                "looked up unknown validators"
+            );
+            let mut state = self.state.write();
+            for summary in summaries.into_iter() {
+                let public_key = summary.validator.public_key.clone();
+                state.pubkeys_by_index.insert(summary.index, public_key.clone());
+                state.validators.insert(public_key, summary);
+            }
         }
         Ok(())
     }
@@ -125,6 +324,13 @@ impl ValidatorRegistry {
         state.validator_preferences.len()
     }
 
+    pub fn registration_stats(&self) -> RegistrationStats {
+        let state = self.state.read();
+        let registrations =
+            state.validator_preferences.values().map(|registration| &registration.message);
+        summarize_registrations(registrations, state.new_registrations_last_epoch)
+    }
+
     // pub fn get_validator_index(&self, public_key: &BlsPublicKey) -> Option<ValidatorIndex> {
     //     let state = self.state.read();
     //     state.validators.get(public_key).map(|v| v.index)
@@ -135,7 +341,7 @@ impl ValidatorRegistry {
     pub fn get_signed_registration(
         &self,
         public_key: &BlsPublicKey,
-    ) -> Option<SignedValidatorRegistration> {
+    ) -> Option<Arc<SignedValidatorRegistration>> {
         let state = self.state.read();
         state.validator_preferences.get(public_key).cloned()
     }
@@ -152,41 +358,52 @@ impl ValidatorRegistry {
     //         .map(|(key, _)| key.clone())
     // }
 
+    // Validates the `registration`, checking the timestamp and validator status before
+    // doing any signature verification so that repeated registrations stay cheap.
     fn process_registration<'a>(
         &'a self,
         registration: &'a SignedValidatorRegistration,
         current_timestamp: u64,
-        context: &Context,
+        builder_domain: Domain,
     ) -> Result<Option<&'a SignedValidatorRegistration>, Error> {
-        let state = self.state.read();
-        let latest_timestamp = state
-            .validator_preferences
-            .get(&registration.message.public_key)
-            .map(|r| r.message.timestamp);
         let message = &registration.message;
-
         validate_registration_is_not_from_future(message, current_timestamp)?;
 
-        let registration_status = if let Some(latest_timestamp) = latest_timestamp {
-            let status =
-                determine_validator_registration_status(message.timestamp, latest_timestamp);
-            if matches!(status, ValidatorRegistrationStatus::Outdated) {
-                return Err(Error::OutdatedRegistration(message.clone(), latest_timestamp))
+        let public_key = &message.public_key;
+        let registration_status = {
+            let state = self.state.read();
+            let latest = state.validator_preferences.get(public_key);
+            let registration_status = match latest {
+                Some(latest) => {
+                    let latest_timestamp = latest.message.timestamp;
+                    let status = determine_validator_registration_status(
+                        message.timestamp,
+                        latest_timestamp,
+                    );
+                    if matches!(status, ValidatorRegistrationStatus::Outdated) {
+                        return Err(Error::OutdatedRegistration(message.clone(), latest_timestamp))
+                    }
+                    status
+                }
+                None => ValidatorRegistrationStatus::New,
+            };
+
+            let validator_status = state
+                .validators
+                .get(public_key)
+                .map(|validator| validator.status)
+                .ok_or(Error::UnknownPubkey)?;
+            validate_validator_status(message, validator_status)?;
+
+            // NOTE: validators re-send the same registration every epoch, which has been
+            // verified already
+            if latest.is_some_and(|latest| latest.as_ref() == registration) {
+                return Ok(None)
             }
-            status
-        } else {
-            ValidatorRegistrationStatus::New
+            registration_status
         };
 
-        let public_key = &message.public_key;
-        let validator_status = state
-            .validators
-            .get(public_key)
-            .map(|validator| validator.status)
-            .ok_or(Error::UnknownPubkey)?;
-        validate_validator_status(message, validator_status)?;
-
-        verify_signed_builder_data(message, &message.public_key, &registration.signature, context)?;
+        verify_signed_data(message, &registration.signature, &message.public_key, builder_domain)?;
 
         let update = if matches!(registration_status, ValidatorRegistrationStatus::New) {
             trace!(%public_key, "processed new registration");
@@ -205,20 +422,168 @@ impl ValidatorRegistry {
         current_timestamp: u64,
         context: &Context,
     ) -> (HashSet<BlsPublicKey>, Vec<Error>) {
-        let (updates, errs): (Vec<_>, Vec<_>) = registrations
-            .par_iter()
-            .map(|registration| self.process_registration(registration, current_timestamp, context))
-            .partition(|result| result.is_ok());
-        let mut state = self.state.write();
+        // NOTE: the domain is fixed for the network so compute it once for the whole batch
+        let builder_domain = match compute_builder_domain(context) {
+            Ok(domain) => domain,
+            Err(err) => return (Default::default(), vec![err.into()]),
+        };
+        let process = || {
+            registrations
+                .par_iter()
+                .map(|registration| {
+                    self.process_registration(registration, current_timestamp, builder_domain)
+                })
+                .partition::<Vec<_>, Vec<_>, _>(|result| result.is_ok())
+        };
+        let (updates, errs) = match self.thread_pool.as_ref() {
+            Some(thread_pool) => thread_pool.install(process),
+            None => process(),
+        };
         let mut updated_keys = HashSet::new();
-        for update in updates {
-            if let Some(signed_registration) = update.expect("validated successfully") {
-                let public_key = signed_registration.message.public_key.clone();
-                updated_keys.insert(public_key.clone());
-                state.validator_preferences.insert(public_key, signed_registration.clone());
+        let mut updated_registrations = vec![];
+        {
+            let mut state = self.state.write();
+            for update in updates {
+                if let Some(signed_registration) = update.expect("validated successfully") {
+                    let public_key = signed_registration.message.public_key.clone();
+                    updated_keys.insert(public_key.clone());
+                    let signed_registration = Arc::new(signed_registration.clone());
+                    let previous =
+                        state.validator_preferences.insert(public_key, signed_registration.clone());
+                    if previous.is_none() {
+                        state.new_registrations += 1;
+                    }
+                    updated_registrations.push(signed_registration);
+                }
             }
         }
+        if !updated_registrations.is_empty() {
+            self.persist(PersistRequest::Append(updated_registrations));
+        }
 
         (updated_keys, errs.into_iter().map(|err| err.expect_err("validation failed")).collect())
     }
+
+    fn persist(&self, request: PersistRequest) {
+        let Some(persister) = self.persister.as_ref() else { return };
+        if persister.send(request).is_err() {
+            warn!("validator registration writer has stopped; registrations are not persisted");
+        }
+    }
+}
+
+fn persist_registrations(path: &Path, requests: Receiver<PersistRequest>) {
+    while let Ok(request) = requests.recv() {
+        let result = match &request {
+            PersistRequest::Append(registrations) => append(path, registrations),
+            PersistRequest::Compact(registrations) => compact(path, registrations),
+        };
+        if let Err(err) = result {
+            warn!(%err, path = %path.display(), "could not persist validator registrations");
+        }
+    }
+}
+
+fn append(path: &Path, registrations: &[Arc<SignedValidatorRegistration>]) -> io::Result<()> {
+    let mut data = vec![];
+    for registration in registrations {
+        serde_json::to_writer(&mut data, registration.as_ref())?;
+        data.push(b'\n');
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(&data)
+}
+
+fn summarize_registrations<'a>(
+    registrations: impl Iterator<Item = &'a ValidatorRegistration>,
+    new_in_last_epoch: usize,
+) -> RegistrationStats {
+    let mut stats = RegistrationStats { new_in_last_epoch, ..Default::default() };
+    let mut fee_recipients = HashSet::new();
+    for registration in registrations {
+        stats.total += 1;
+        fee_recipients.insert(&registration.fee_recipient);
+        *stats.gas_limits.entry(registration.gas_limit).or_default() += 1;
+    }
+    stats.distinct_fee_recipients = fee_recipients.len();
+    stats
+}
+
+// Loads the registrations persisted to `path`, keeping the latest one for each validator.
+fn load(path: &Path) -> io::Result<HashMap<BlsPublicKey, Arc<SignedValidatorRegistration>>> {
+    let data = fs::read_to_string(path)?;
+    let mut registrations = HashMap::<_, Arc<SignedValidatorRegistration>>::new();
+    for line in data.lines().filter(|line| !line.trim().is_empty()) {
+        let registration: SignedValidatorRegistration = match serde_json::from_str(line) {
+            Ok(registration) => registration,
+            Err(err) => {
+                warn!(%err, path = %path.display(), "skipping invalid validator registration");
+                continue
+            }
+        };
+        let public_key = registration.message.public_key.clone();
+        match registrations.get(&public_key) {
+            Some(existing) if existing.message.timestamp >= registration.message.timestamp => {}
+            _ => {
+                registrations.insert(public_key, Arc::new(registration));
+            }
+        }
+    }
+    Ok(registrations)
+}
+
+fn compact<'a>(
+    path: &Path,
+    registrations: impl IntoIterator<Item = &'a Arc<SignedValidatorRegistration>>,
+) -> io::Result<()> {
+    let mut data = vec![];
+    for registration in registrations {
+        serde_json::to_writer(&mut data, registration.as_ref())?;
+        data.push(b'\n');
+    }
+    // NOTE: write to a temporary file first so a crash can not leave a partial file behind
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, data)?;
+    fs::rename(tmp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethereum_consensus::primitives::ExecutionAddress;
+
+    #[test]
+    fn test_load_keeps_latest_registration() {
+        let path = std::env::temp_dir().join("mev-rs-test-validator-registrations.jsonl");
+        let mut registrations = vec![];
+        for timestamp in [2, 3, 1] {
+            let mut registration = SignedValidatorRegistration::default();
+            registration.message.timestamp = timestamp;
+            registrations.push(serde_json::to_string(&registration).unwrap());
+        }
+        registrations.push("not a registration".to_string());
+        fs::write(&path, registrations.join("\n")).unwrap();
+
+        let loaded = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[&BlsPublicKey::default()].message.timestamp, 3);
+    }
+
+    #[test]
+    fn test_summarize_registrations() {
+        let registrations = [(1, 30_000_000), (2, 30_000_000), (1, 36_000_000)]
+            .into_iter()
+            .map(|(fee_recipient, gas_limit)| {
+                let fee_recipient =
+                    ExecutionAddress::try_from([fee_recipient; 20].as_ref()).unwrap();
+                ValidatorRegistration { fee_recipient, gas_limit, ..Default::default() }
+            })
+            .collect::<Vec<_>>();
+        let stats = summarize_registrations(registrations.iter(), 2);
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.new_in_last_epoch, 2);
+        assert_eq!(stats.distinct_fee_recipients, 2);
+        assert_eq!(stats.gas_limits, [(30_000_000, 2), (36_000_000, 1)].into());
+    }
 }